@@ -1,8 +1,16 @@
 //! API service specific configuration
 
-use shared::AppConfig;
+use cache::RateLimitAlgorithm;
+use chrono::{DateTime, Utc};
+use shared::{AppConfig, Secret, ValidateConfig};
+pub use shared::{ApiErrorResponse, ApiMetadata};
 use serde::{Deserialize, Serialize};
 
+use crate::{
+    middleware::{cors::cors_layer, rate_limit::parse_network},
+    routes::SERVED_VERSIONS,
+};
+
 /// API service configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ApiConfig {
@@ -11,13 +19,14 @@ pub struct ApiConfig {
     pub app: AppConfig,
     
     /// API specific settings
+    #[serde(default)]
     pub api: ApiSettings,
 }
 
 /// API specific settings
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ApiSettings {
-    /// API version
+    /// Default API version, used for requests that do not name one
     pub version: String,
     
     /// API title
@@ -49,6 +58,231 @@ pub struct ApiSettings {
     
     /// Authentication settings
     pub auth: AuthSettings,
+
+    /// GraphQL endpoint settings
+    #[serde(default)]
+    pub graphql: GraphQLSettings,
+
+    /// WebSocket notification settings
+    #[serde(default)]
+    pub websocket: WebSocketSettings,
+
+    /// Server-Sent Events stream settings
+    #[serde(default)]
+    pub event_stream: EventStreamSettings,
+
+    /// Domain events published to Kafka
+    #[serde(default)]
+    pub domain_events: DomainEventSettings,
+
+    /// Rate limiting settings
+    #[serde(default)]
+    pub rate_limit: RateLimitSettings,
+
+    /// Request limits for specific routes; the first matching prefix applies
+    #[serde(default)]
+    pub route_overrides: Vec<RouteOverride>,
+
+    /// Supported API versions
+    #[serde(default)]
+    pub versioning: VersioningSettings,
+}
+
+/// API versioning settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VersioningSettings {
+    /// Versions served, e.g. `v1`; requests for any other version are rejected
+    pub versions: Vec<ApiVersionSettings>,
+}
+
+/// Lifecycle of a single API version
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiVersionSettings {
+    /// Version name as it appears in the path, e.g. `v1`
+    pub name: String,
+
+    /// When the version was deprecated; deprecated versions get a `Deprecation` header
+    #[serde(default)]
+    pub deprecated_at: Option<DateTime<Utc>>,
+
+    /// When the version will be removed, sent as the `Sunset` header
+    #[serde(default)]
+    pub sunset_at: Option<DateTime<Utc>>,
+
+    /// Migration guide, sent as a `Link` with `rel="deprecation"`
+    #[serde(default)]
+    pub deprecation_link: Option<String>,
+}
+
+impl VersioningSettings {
+    /// Look up a supported version by name
+    pub fn find(&self, name: &str) -> Option<&ApiVersionSettings> {
+        self.versions.iter().find(|version| version.name == name)
+    }
+}
+
+/// Request limits for routes under a path prefix, e.g. a larger body limit for uploads
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RouteOverride {
+    /// Path prefix the override applies to; API prefixes such as `/api/v1/files` cover every
+    /// API version
+    pub path_prefix: String,
+
+    /// Maximum request size in bytes, replacing `max_request_size`
+    #[serde(default)]
+    pub max_request_size: Option<usize>,
+
+    /// Request timeout in seconds, replacing `request_timeout`
+    #[serde(default)]
+    pub request_timeout: Option<u64>,
+}
+
+/// Rate limiting settings.
+///
+/// Requests not matching a tier use `security.rate_limit_requests` per `security.rate_limit_window`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RateLimitSettings {
+    /// Enable rate limiting of API routes
+    pub enabled: bool,
+
+    /// What to do with requests when Redis cannot be reached
+    pub failure_mode: RateLimitFailureMode,
+
+    /// Header identifying API key clients
+    pub api_key_header: String,
+
+    /// Keys rate limited as their own clients; any other key is ignored
+    pub api_keys: Vec<ApiKeySettings>,
+
+    /// Proxies, as IP addresses or CIDR ranges, whose `X-Forwarded-For` entries are trusted.
+    ///
+    /// The client IP is the right-most forwarded address that is not a trusted proxy;
+    /// when empty, `X-Forwarded-For` is ignored and the peer address is used.
+    pub trusted_proxies: Vec<String>,
+
+    /// Algorithm for requests not matching a tier
+    pub algorithm: RateLimitAlgorithm,
+
+    /// Route specific limits; the first tier whose prefix matches the path applies
+    pub tiers: Vec<RateLimitTier>,
+}
+
+/// Behaviour when the rate limit store is unavailable
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RateLimitFailureMode {
+    /// Let requests through unlimited
+    Open,
+    /// Reject requests with `503 Service Unavailable`
+    Closed,
+}
+
+/// API key accepted by the rate limiter
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiKeySettings {
+    /// Key name, part of the rate limit key
+    pub name: String,
+
+    /// Hex-encoded SHA-256 digest of the key, so keys are never stored in plain text
+    pub sha256: String,
+}
+
+/// Limit applied to routes under a path prefix
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RateLimitTier {
+    /// Tier name, part of the rate limit key
+    pub name: String,
+
+    /// Path prefix the tier applies to, e.g. `/api/v1/auth/`; API prefixes cover every API
+    /// version
+    pub path_prefix: String,
+
+    /// Requests allowed per window
+    pub requests: u32,
+
+    /// Window length in seconds
+    pub window: u64,
+
+    /// Algorithm enforcing the limit
+    #[serde(default)]
+    pub algorithm: RateLimitAlgorithm,
+}
+
+/// GraphQL endpoint settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GraphQLSettings {
+    /// Enable the `/graphql` endpoint
+    pub enabled: bool,
+
+    /// Maximum query nesting depth
+    pub max_depth: usize,
+
+    /// Maximum query complexity
+    pub max_complexity: usize,
+
+    /// Serve the GraphiQL playground (requires `enable_docs`)
+    pub enable_playground: bool,
+}
+
+/// WebSocket notification settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WebSocketSettings {
+    /// Enable the `/ws` endpoint
+    pub enabled: bool,
+
+    /// Interval between server pings in seconds
+    pub heartbeat_interval: u64,
+
+    /// Close connections that have been silent for this many seconds
+    pub client_timeout: u64,
+
+    /// Maximum queued outbound messages per connection before it is dropped as too slow
+    pub send_queue_size: usize,
+
+    /// Capacity of the in-process notification broadcast buffer
+    pub broadcast_capacity: usize,
+}
+
+/// Server-Sent Events stream settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EventStreamSettings {
+    /// Interval between polls of the events table in milliseconds
+    pub poll_interval: u64,
+
+    /// Maximum events fetched per poll
+    pub batch_size: u32,
+
+    /// Interval between keep-alive comments in seconds
+    pub keep_alive_interval: u64,
+
+    /// How far behind the newest streamed event to keep re-reading, in milliseconds.
+    ///
+    /// `created_at` is set when an event's transaction starts, so an event can become visible
+    /// after later-timestamped ones were streamed; events committed within this window are still
+    /// delivered, exactly once per stream.
+    pub reorder_window: u64,
+}
+
+/// Settings for domain events published to Kafka for the event service
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DomainEventSettings {
+    /// Publish domain events; when disabled, changes are only announced over WebSocket
+    pub enabled: bool,
+
+    /// Topic events are published to
+    pub topic: String,
+
+    /// Time to wait for room in the producer queue, in milliseconds
+    pub queue_timeout_ms: u64,
+
+    /// Producer message timeout in milliseconds
+    pub message_timeout_ms: u64,
 }
 
 /// Pagination settings
@@ -68,7 +302,7 @@ pub struct PaginationSettings {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AuthSettings {
     /// JWT secret key
-    pub jwt_secret: String,
+    pub jwt_secret: Secret<String>,
     
     /// JWT expiration time in seconds
     pub jwt_expiration: u64,
@@ -137,6 +371,91 @@ impl Default for ApiSettings {
             enable_compression: true,
             pagination: PaginationSettings::default(),
             auth: AuthSettings::default(),
+            graphql: GraphQLSettings::default(),
+            websocket: WebSocketSettings::default(),
+            event_stream: EventStreamSettings::default(),
+            domain_events: DomainEventSettings::default(),
+            rate_limit: RateLimitSettings::default(),
+            route_overrides: Vec::new(),
+            versioning: VersioningSettings::default(),
+        }
+    }
+}
+
+impl Default for RateLimitSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            failure_mode: RateLimitFailureMode::Open,
+            api_key_header: "X-API-Key".to_string(),
+            api_keys: Vec::new(),
+            trusted_proxies: Vec::new(),
+            algorithm: RateLimitAlgorithm::SlidingWindow,
+            tiers: vec![RateLimitTier {
+                name: "auth".to_string(),
+                path_prefix: format!("{}/auth/", shared::API_BASE_PATH),
+                requests: shared::rate_limits::AUTH_REQUESTS_PER_MINUTE,
+                window: 60,
+                algorithm: RateLimitAlgorithm::SlidingWindow,
+            }],
+        }
+    }
+}
+
+impl Default for VersioningSettings {
+    fn default() -> Self {
+        Self {
+            versions: vec![ApiVersionSettings {
+                name: "v1".to_string(),
+                deprecated_at: None,
+                sunset_at: None,
+                deprecation_link: None,
+            }],
+        }
+    }
+}
+
+impl Default for GraphQLSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_depth: 10,
+            max_complexity: 1000,
+            enable_playground: true,
+        }
+    }
+}
+
+impl Default for WebSocketSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            heartbeat_interval: 30,
+            client_timeout: 90,
+            send_queue_size: 64,
+            broadcast_capacity: 1024,
+        }
+    }
+}
+
+impl Default for EventStreamSettings {
+    fn default() -> Self {
+        Self {
+            poll_interval: 1000,
+            batch_size: 100,
+            keep_alive_interval: 15,
+            reorder_window: 5000,
+        }
+    }
+}
+
+impl Default for DomainEventSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            topic: "events".to_string(),
+            queue_timeout_ms: 5000,
+            message_timeout_ms: 30000,
         }
     }
 }
@@ -154,7 +473,7 @@ impl Default for PaginationSettings {
 impl Default for AuthSettings {
     fn default() -> Self {
         Self {
-            jwt_secret: "your-secret-key-change-in-production".to_string(),
+            jwt_secret: "your-secret-key-change-in-production".into(),
             jwt_expiration: 3600, // 1 hour
             jwt_issuer: "rust-microservices".to_string(),
             jwt_audience: "api-users".to_string(),
@@ -182,13 +501,30 @@ impl Default for LockoutSettings {
 impl ApiConfig {
     /// Load API configuration
     pub fn load() -> Result<Self, figment::Error> {
-        use figment::{providers::{Env, Format, Yaml}, Figment};
-        
-        Figment::new()
-            .merge(Yaml::file("config/api.yml"))
-            .merge(Yaml::file(format!("config/api-{}.yml", std::env::var("ENVIRONMENT").unwrap_or_else(|_| "development".to_string()))))
-            .merge(Env::prefixed("API_"))
-            .extract()
+        Self::load_from_path("config")
+    }
+
+    /// Load API configuration from a config directory
+    pub fn load_from_path(config_path: &str) -> Result<Self, figment::Error> {
+        Self::figment(config_path).extract()
+    }
+
+    /// API configuration sources, in order of precedence (lowest first).
+    ///
+    /// Base settings are read like [`AppConfig::figment`], then overlaid with `api.yml`,
+    /// `api-{environment}.yml` and `API_` variables, using `__` for nesting
+    /// (e.g. `API_RATE_LIMIT__ENABLED=false`). `API_*_FILE` variables read values from files.
+    pub fn figment(config_path: &str) -> figment::Figment {
+        use figment::providers::{Env, Format, Yaml};
+        use shared::EnvFiles;
+
+        let environment = std::env::var("ENVIRONMENT").unwrap_or_else(|_| "development".to_string());
+
+        AppConfig::figment(config_path)
+            .merge(Yaml::file(format!("{}/api.yml", config_path)))
+            .merge(Yaml::file(format!("{}/api-{}.yml", config_path, environment)))
+            .merge(Env::prefixed("API_").split("__").map(|key| format!("api.{}", key.as_str()).into()))
+            .merge(EnvFiles::prefixed("API_").nest_under("api"))
     }
     
     /// Get JWT expiration as Duration
@@ -210,16 +546,20 @@ impl ApiConfig {
     pub fn lockout_duration(&self) -> std::time::Duration {
         std::time::Duration::from_secs(self.api.auth.lockout.lockout_duration)
     }
-    
-    /// Validate API configuration
-    pub fn validate(&self) -> Result<(), String> {
+}
+
+impl ValidateConfig for ApiConfig {
+    fn validate(&self) -> Result<(), String> {
         // Validate base app config
         self.app.validate()?;
         
         // Validate JWT secret
-        if self.api.auth.jwt_secret.len() < 32 {
+        if self.api.auth.jwt_secret.expose().len() < 32 {
             return Err("JWT secret must be at least 32 characters".to_string());
         }
+        if self.app.is_production() && self.api.auth.jwt_secret.is_default() {
+            return Err("The default API JWT secret cannot be used in production".to_string());
+        }
         
         // Validate pagination settings
         if self.api.pagination.default_page_size > self.api.pagination.max_page_size {
@@ -231,63 +571,87 @@ impl ApiConfig {
             return Err("Password min length cannot be greater than max length".to_string());
         }
         
+        // Validate GraphQL limits
+        if self.api.graphql.enabled && (self.api.graphql.max_depth == 0 || self.api.graphql.max_complexity == 0) {
+            return Err("GraphQL depth and complexity limits must be greater than zero".to_string());
+        }
+        
+        // Validate WebSocket settings
+        if self.api.websocket.enabled {
+            if self.api.websocket.heartbeat_interval == 0 || self.api.websocket.client_timeout <= self.api.websocket.heartbeat_interval {
+                return Err("WebSocket client timeout must be greater than a non-zero heartbeat interval".to_string());
+            }
+            if self.api.websocket.send_queue_size == 0 || self.api.websocket.broadcast_capacity == 0 {
+                return Err("WebSocket queue sizes must be greater than zero".to_string());
+            }
+        }
+        
+        // Validate event stream settings
+        if self.api.event_stream.poll_interval == 0 || self.api.event_stream.batch_size == 0 {
+            return Err("Event stream poll interval and batch size must be greater than zero".to_string());
+        }
+        
+        // Validate domain event publishing
+        if self.api.domain_events.enabled && (self.api.domain_events.topic.is_empty() || self.api.domain_events.message_timeout_ms == 0) {
+            return Err("Domain events require a topic and a non-zero message timeout".to_string());
+        }
+        
+        // Validate rate limit tiers
+        if self.api.rate_limit.enabled {
+            if self.app.security.rate_limit_requests == 0 || self.app.security.rate_limit_window == 0 {
+                return Err("Default rate limit requests and window must be greater than zero".to_string());
+            }
+            if let Some(tier) = self.api.rate_limit.tiers.iter().find(|tier| tier.requests == 0 || tier.window == 0) {
+                return Err(format!("Rate limit tier '{}' must allow requests in a non-zero window", tier.name));
+            }
+        }
+        if let Some(proxy) = self.api.rate_limit.trusted_proxies.iter().find(|proxy| parse_network(proxy).is_none()) {
+            return Err(format!("Trusted proxy '{}' is not an IP address or CIDR range", proxy));
+        }
+        
         // Validate request size
         if self.api.max_request_size == 0 {
             return Err("Max request size cannot be zero".to_string());
         }
-        
-        Ok(())
-    }
-}
 
-/// API response metadata
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ApiMetadata {
-    pub version: String,
-    pub timestamp: chrono::DateTime<chrono::Utc>,
-    pub request_id: uuid::Uuid,
-    pub service: String,
-}
+        if self.api.request_timeout == 0 {
+            return Err("Request timeout cannot be zero".to_string());
+        }
 
-impl ApiMetadata {
-    pub fn new(version: String, service: String) -> Self {
-        Self {
-            version,
-            timestamp: chrono::Utc::now(),
-            request_id: uuid::Uuid::new_v4(),
-            service,
+        // Validate API versions
+        if let Some(version) = self.api.versioning.versions.iter().find(|version| !SERVED_VERSIONS.contains(&version.name.as_str())) {
+            return Err(format!("API version '{}' has no routes in this build", version.name));
+        }
+        if self.api.versioning.find(&self.api.version).is_none() {
+            return Err(format!("Default API version '{}' is not in the supported versions", self.api.version));
+        }
+        if let Some(version) = self.api.versioning.versions.iter().find(|version| !is_version_name(&version.name)) {
+            return Err(format!("Invalid API version name '{}', expected e.g. 'v2'", version.name));
         }
-    }
-}
 
-/// API error response
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ApiErrorResponse {
-    pub error: String,
-    pub message: String,
-    pub code: u16,
-    pub details: Option<serde_json::Value>,
-    pub metadata: ApiMetadata,
-}
+        // Validate route overrides
+        if let Some(route) = self.api.route_overrides.iter().find(|route| {
+            route.max_request_size == Some(0) || route.request_timeout == Some(0)
+        }) {
+            return Err(format!("Route override for '{}' cannot use a zero limit", route.path_prefix));
+        }
 
-impl ApiErrorResponse {
-    pub fn new(
-        error: String,
-        message: String,
-        code: u16,
-        details: Option<serde_json::Value>,
-        metadata: ApiMetadata,
-    ) -> Self {
-        Self {
-            error,
-            message,
-            code,
-            details,
-            metadata,
+        // Validate CORS settings
+        if self.api.enable_cors {
+            cors_layer(&self.app.security).map_err(|e| e.to_string())?;
         }
+        
+        Ok(())
     }
 }
 
+/// Whether a path segment names an API version, e.g. `v2`
+pub fn is_version_name(segment: &str) -> bool {
+    segment
+        .strip_prefix('v')
+        .is_some_and(|number| !number.is_empty() && number.chars().all(|c| c.is_ascii_digit()))
+}
+
 /// API success response
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ApiSuccessResponse<T> {
@@ -321,7 +685,7 @@ mod tests {
         assert!(config.validate().is_ok());
         
         // Invalid JWT secret should fail
-        config.api.auth.jwt_secret = "short".to_string();
+        config.api.auth.jwt_secret = "short".into();
         assert!(config.validate().is_err());
         
         // Reset and test pagination
@@ -329,6 +693,35 @@ mod tests {
         config.api.pagination.default_page_size = 2000;
         config.api.pagination.max_page_size = 1000;
         assert!(config.validate().is_err());
+
+        // The default version must be served
+        config = ApiConfig::default();
+        config.api.version = "v2".to_string();
+        assert!(config.validate().is_err());
+
+        // Every configured version needs routes
+        config = ApiConfig::default();
+        config.api.versioning.versions.push(ApiVersionSettings {
+            name: "v9".to_string(),
+            deprecated_at: None,
+            sunset_at: None,
+            deprecation_link: None,
+        });
+        assert!(config.validate().is_err());
+
+        // Trusted proxies must be addresses or ranges
+        config = ApiConfig::default();
+        config.api.rate_limit.trusted_proxies.push("proxy.internal".to_string());
+        assert!(config.validate().is_err());
+
+        // Route overrides cannot disable limits
+        config = ApiConfig::default();
+        config.api.route_overrides.push(RouteOverride {
+            path_prefix: "/api/v1/files".to_string(),
+            max_request_size: Some(0),
+            request_timeout: None,
+        });
+        assert!(config.validate().is_err());
     }
 
     #[test]
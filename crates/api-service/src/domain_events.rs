@@ -0,0 +1,121 @@
+//! Domain events published to Kafka for the event service
+
+use database::{Order, Payment};
+use rdkafka::{
+    message::{Header, OwnedHeaders},
+    producer::{FutureProducer, FutureRecord, Producer},
+};
+use serde::Serialize;
+use shared::{kafka::client_config, services, telemetry, AppError, AppResult, Event, EventMetadata, KafkaConfig};
+use std::{fmt, time::Duration};
+use tracing::{debug, warn};
+
+use crate::config::DomainEventSettings;
+
+/// Publishes changes made through the API as [`Event`] envelopes.
+///
+/// Each message carries the correlation id and W3C trace context of the request, so the
+/// event service and the jobs it enqueues continue the request's trace.
+#[derive(Clone)]
+pub struct DomainEvents {
+    producer: Option<FutureProducer>,
+    topic: String,
+    queue_timeout: Duration,
+}
+
+impl DomainEvents {
+    pub fn new(kafka: &KafkaConfig, settings: &DomainEventSettings) -> AppResult<Self> {
+        let producer = if settings.enabled {
+            let producer = client_config(kafka)
+                .set("message.timeout.ms", settings.message_timeout_ms.to_string())
+                .create()
+                .map_err(|e| AppError::Kafka(format!("Failed to create producer: {}", e)))?;
+            Some(producer)
+        } else {
+            None
+        };
+
+        Ok(Self {
+            producer,
+            topic: settings.topic.clone(),
+            queue_timeout: Duration::from_millis(settings.queue_timeout_ms),
+        })
+    }
+
+    /// Publish an event keyed by `key`, so events about the same resource stay in order.
+    ///
+    /// The change is already committed, so a failure is logged rather than returned.
+    pub async fn publish<T: Serialize>(&self, key: &str, event: Event<T>) {
+        let Some(producer) = &self.producer else {
+            return;
+        };
+
+        let payload = match serde_json::to_vec(&event) {
+            Ok(payload) => payload,
+            Err(e) => {
+                warn!("Failed to serialize {} event: {}", event.metadata.event_type, e);
+                return;
+            }
+        };
+        let record = FutureRecord::to(&self.topic)
+            .key(key)
+            .payload(&payload)
+            .headers(event_headers(&event.metadata));
+
+        match producer.send(record, self.queue_timeout).await {
+            Ok((partition, offset)) => debug!(
+                topic = %self.topic,
+                partition,
+                offset,
+                event_type = %event.metadata.event_type,
+                "Domain event published"
+            ),
+            Err((e, _)) => warn!("Failed to publish {} event to {}: {}", event.metadata.event_type, self.topic, e),
+        }
+    }
+
+    /// Wait up to `timeout` for queued events to be delivered
+    pub async fn flush(&self, timeout: Duration) {
+        let Some(producer) = self.producer.clone() else {
+            return;
+        };
+
+        match tokio::task::spawn_blocking(move || producer.flush(timeout)).await {
+            Ok(Ok(())) => {}
+            Ok(Err(e)) => warn!("Failed to flush domain events: {}", e),
+            Err(e) => warn!("Domain event flush task failed: {}", e),
+        }
+    }
+}
+
+impl fmt::Debug for DomainEvents {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DomainEvents")
+            .field("enabled", &self.producer.is_some())
+            .field("topic", &self.topic)
+            .finish()
+    }
+}
+
+/// Kafka headers carrying the event's metadata plus the W3C trace context of the request
+fn event_headers(metadata: &EventMetadata) -> OwnedHeaders {
+    telemetry::event_headers(metadata)
+        .into_iter()
+        .fold(OwnedHeaders::new(), |headers, (key, value)| {
+            headers.insert(Header { key: &key, value: Some(&value) })
+        })
+}
+
+/// Event about a change to an order
+pub fn order_event<'a>(order: &'a Order, event_type: &str) -> Event<&'a Order> {
+    let mut event = Event::current(event_type, services::API, order);
+    event.metadata = event.metadata.with_tenant(order.tenant_id).with_user(order.user_id);
+    event
+}
+
+/// Event about a change to a payment
+pub fn payment_event<'a>(payment: &'a Payment, event_type: &str) -> Event<&'a Payment> {
+    let mut event = Event::current(event_type, services::API, payment);
+    event.metadata = event.metadata.with_tenant(payment.tenant_id).with_user(payment.user_id);
+    event
+}
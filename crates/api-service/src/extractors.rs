@@ -0,0 +1,112 @@
+//! Request extractors that validate their input
+
+use axum::{
+    async_trait,
+    extract::{FromRequest, FromRequestParts, Query, Request},
+    http::request::Parts,
+    Json,
+};
+use serde::de::DeserializeOwned;
+use shared::{AppError, ValidationErrors};
+use validator::Validate;
+
+/// JSON body that is deserialized and then checked with [`Validate`].
+///
+/// Malformed bodies are rejected with `BAD_REQUEST`, invalid ones with a
+/// `VALIDATION_ERROR` listing every failing field.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ValidatedJson<T>(pub T);
+
+#[async_trait]
+impl<T, S> FromRequest<S> for ValidatedJson<T>
+where
+    T: DeserializeOwned + Validate,
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request(request: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let Json(value) = Json::<T>::from_request(request, state)
+            .await
+            .map_err(|rejection| AppError::BadRequest(rejection.body_text()))?;
+
+        validate(value).map(ValidatedJson)
+    }
+}
+
+/// Query string that is deserialized and then checked with [`Validate`]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ValidatedQuery<T>(pub T);
+
+#[async_trait]
+impl<T, S> FromRequestParts<S> for ValidatedQuery<T>
+where
+    T: DeserializeOwned + Validate,
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let Query(value) = Query::<T>::from_request_parts(parts, state)
+            .await
+            .map_err(|rejection| AppError::BadRequest(rejection.body_text()))?;
+
+        validate(value).map(ValidatedQuery)
+    }
+}
+
+/// Check a value with [`Validate`], reporting every failing field as a `VALIDATION_ERROR`
+pub fn validate<T: Validate>(value: T) -> Result<T, AppError> {
+    value
+        .validate()
+        .map_err(|errors| AppError::FieldValidation(ValidationErrors::from(errors)))?;
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use shared::PaginationParams;
+
+    #[derive(Debug, serde::Deserialize, Validate)]
+    struct Payload {
+        #[validate(length(min = 3))]
+        name: String,
+    }
+
+    fn json_request(body: &'static str) -> Request {
+        Request::builder()
+            .method("POST")
+            .header("content-type", "application/json")
+            .body(Body::from(body))
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_validated_json() {
+        let ValidatedJson(payload) = ValidatedJson::<Payload>::from_request(json_request(r#"{"name":"abc"}"#), &())
+            .await
+            .unwrap();
+        assert_eq!(payload.name, "abc");
+
+        let error = ValidatedJson::<Payload>::from_request(json_request(r#"{"name":"ab"}"#), &())
+            .await
+            .unwrap_err();
+        assert!(matches!(error, AppError::FieldValidation(errors) if errors.errors[0].field == "name"));
+
+        let error = ValidatedJson::<Payload>::from_request(json_request("{"), &()).await.unwrap_err();
+        assert!(matches!(error, AppError::BadRequest(_)));
+    }
+
+    #[tokio::test]
+    async fn test_validated_query_rejects_large_page_size() {
+        let request = Request::builder().uri("/users?limit=5000").body(()).unwrap();
+        let (mut parts, _) = request.into_parts();
+
+        let error = ValidatedQuery::<PaginationParams>::from_request_parts(&mut parts, &())
+            .await
+            .unwrap_err();
+        assert!(matches!(error, AppError::FieldValidation(errors) if errors.errors[0].field == "limit"));
+    }
+}
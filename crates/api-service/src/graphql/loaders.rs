@@ -0,0 +1,157 @@
+//! DataLoaders batching repository lookups per GraphQL request
+//!
+//! Every loader is built per request with the caller's [`AuthContext`], so batched
+//! lookups stay scoped to the caller's tenant.
+
+use async_graphql::dataloader::{DataLoader, Loader};
+use database::{Order, Payment, User};
+use shared::{AppError, UserId};
+use std::{collections::HashMap, sync::Arc};
+use uuid::Uuid;
+
+use crate::{
+    middleware::auth::AuthContext,
+    services::{OrderService, PaymentService, UserService},
+    state::AppState,
+};
+
+/// Loads users by ID
+pub struct UserLoader {
+    service: UserService,
+    auth: AuthContext,
+}
+
+impl Loader<UserId> for UserLoader {
+    type Value = User;
+    type Error = Arc<AppError>;
+
+    async fn load(&self, keys: &[UserId]) -> Result<HashMap<UserId, User>, Self::Error> {
+        let users = self.service.get_many(&self.auth, keys).await?;
+        Ok(users.into_iter().map(|user| (user.id, user)).collect())
+    }
+}
+
+/// Loads orders by ID
+pub struct OrderLoader {
+    service: OrderService,
+    auth: AuthContext,
+}
+
+impl Loader<Uuid> for OrderLoader {
+    type Value = Order;
+    type Error = Arc<AppError>;
+
+    async fn load(&self, keys: &[Uuid]) -> Result<HashMap<Uuid, Order>, Self::Error> {
+        let orders = self.service.get_many(&self.auth, keys).await?;
+        Ok(orders.into_iter().map(|order| (order.id, order)).collect())
+    }
+}
+
+/// Loads payments by ID
+pub struct PaymentLoader {
+    service: PaymentService,
+    auth: AuthContext,
+}
+
+impl Loader<Uuid> for PaymentLoader {
+    type Value = Payment;
+    type Error = Arc<AppError>;
+
+    async fn load(&self, keys: &[Uuid]) -> Result<HashMap<Uuid, Payment>, Self::Error> {
+        let payments = self.service.get_many(&self.auth, keys).await?;
+        Ok(payments.into_iter().map(|payment| (payment.id, payment)).collect())
+    }
+}
+
+/// Loads the orders placed by each user
+pub struct OrdersByUserLoader {
+    service: OrderService,
+    auth: AuthContext,
+}
+
+impl Loader<UserId> for OrdersByUserLoader {
+    type Value = Vec<Order>;
+    type Error = Arc<AppError>;
+
+    async fn load(&self, keys: &[UserId]) -> Result<HashMap<UserId, Vec<Order>>, Self::Error> {
+        let orders = self.service.for_users(&self.auth, keys).await?;
+        Ok(group_by(orders, |order| order.user_id))
+    }
+}
+
+/// Loads the payments made for each order
+pub struct PaymentsByOrderLoader {
+    service: PaymentService,
+    auth: AuthContext,
+}
+
+impl Loader<Uuid> for PaymentsByOrderLoader {
+    type Value = Vec<Payment>;
+    type Error = Arc<AppError>;
+
+    async fn load(&self, keys: &[Uuid]) -> Result<HashMap<Uuid, Vec<Payment>>, Self::Error> {
+        let payments = self.service.for_orders(&self.auth, keys).await?;
+        Ok(group_by(payments, |payment| payment.order_id))
+    }
+}
+
+/// All loaders available to resolvers during a single request
+pub struct Loaders {
+    pub users: DataLoader<UserLoader>,
+    pub orders: DataLoader<OrderLoader>,
+    pub payments: DataLoader<PaymentLoader>,
+    pub orders_by_user: DataLoader<OrdersByUserLoader>,
+    pub payments_by_order: DataLoader<PaymentsByOrderLoader>,
+}
+
+impl Loaders {
+    pub fn new(state: &AppState, auth: &AuthContext) -> Self {
+        Self {
+            users: DataLoader::new(
+                UserLoader { service: UserService::new(state), auth: auth.clone() },
+                tokio::spawn,
+            ),
+            orders: DataLoader::new(
+                OrderLoader { service: OrderService::new(state), auth: auth.clone() },
+                tokio::spawn,
+            ),
+            payments: DataLoader::new(
+                PaymentLoader { service: PaymentService::new(state), auth: auth.clone() },
+                tokio::spawn,
+            ),
+            orders_by_user: DataLoader::new(
+                OrdersByUserLoader { service: OrderService::new(state), auth: auth.clone() },
+                tokio::spawn,
+            ),
+            payments_by_order: DataLoader::new(
+                PaymentsByOrderLoader { service: PaymentService::new(state), auth: auth.clone() },
+                tokio::spawn,
+            ),
+        }
+    }
+}
+
+/// Group rows by a key, keeping their original order within each group
+fn group_by<K, V>(values: Vec<V>, key: impl Fn(&V) -> K) -> HashMap<K, Vec<V>>
+where
+    K: std::hash::Hash + Eq,
+{
+    let mut grouped: HashMap<K, Vec<V>> = HashMap::new();
+    for value in values {
+        grouped.entry(key(&value)).or_default().push(value);
+    }
+    grouped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_group_by() {
+        let grouped = group_by(vec![(1, "a"), (2, "b"), (1, "c")], |(key, _)| *key);
+
+        assert_eq!(grouped[&1], vec![(1, "a"), (1, "c")]);
+        assert_eq!(grouped[&2], vec![(2, "b")]);
+    }
+}
@@ -0,0 +1,138 @@
+//! GraphQL API exposing users, orders and payments
+//!
+//! Resolvers reuse the service layer shared with the REST handlers, and run with the
+//! same [`AuthContext`] so every lookup is scoped to the caller's tenant.
+
+pub mod loaders;
+pub mod mutation;
+pub mod query;
+pub mod types;
+
+use async_graphql::{
+    http::GraphiQLSource, Context, EmptySubscription, Error, ErrorExtensions, Schema,
+};
+use async_graphql_axum::{GraphQLRequest, GraphQLResponse};
+use axum::{
+    extract::State,
+    response::{Html, IntoResponse},
+    Extension,
+};
+use shared::{error_response_settings, AppError};
+
+use crate::{config::GraphQLSettings, middleware::auth::AuthContext, routes::GRAPHQL_PATH, state::AppState};
+
+pub use loaders::Loaders;
+pub use mutation::MutationRoot;
+pub use query::QueryRoot;
+
+/// Executable GraphQL schema
+pub type ApiSchema = Schema<QueryRoot, MutationRoot, EmptySubscription>;
+
+/// Build the schema with the configured depth and complexity limits
+pub fn build_schema(settings: &GraphQLSettings) -> ApiSchema {
+    Schema::build(QueryRoot, MutationRoot, EmptySubscription)
+        .limit_depth(settings.max_depth)
+        .limit_complexity(settings.max_complexity)
+        .finish()
+}
+
+/// Execute a GraphQL request on behalf of the authenticated caller
+pub async fn graphql_handler(
+    State(state): State<AppState>,
+    Extension(schema): Extension<ApiSchema>,
+    auth: AuthContext,
+    request: GraphQLRequest,
+) -> GraphQLResponse {
+    let loaders = Loaders::new(&state, &auth);
+    let request = request
+        .into_inner()
+        .data(auth)
+        .data(state)
+        .data(loaders);
+
+    schema.execute(request).await.into()
+}
+
+/// Serve the GraphiQL playground
+pub async fn graphiql() -> impl IntoResponse {
+    Html(GraphiQLSource::build().endpoint(GRAPHQL_PATH).finish())
+}
+
+/// Authenticated caller of the current request
+pub(crate) fn auth_context<'a>(ctx: &Context<'a>) -> async_graphql::Result<&'a AuthContext> {
+    ctx.data::<AuthContext>()
+}
+
+/// Convert a service error into a GraphQL error carrying a machine-readable code.
+///
+/// Internal error details are only exposed when the REST error responses expose them too.
+pub fn gql_error(error: &AppError) -> Error {
+    if error.should_log_error() {
+        tracing::error!("GraphQL resolver failed: {}", error);
+    }
+
+    let message = if error.status_code() >= 500 && !error_response_settings().expose_internal_errors {
+        "Internal server error".to_string()
+    } else {
+        error.to_string()
+    };
+
+    let code = error.error_code();
+    let details = match error {
+        AppError::FieldValidation(errors) => serde_json::to_value(&errors.errors).ok(),
+        _ => None,
+    };
+
+    Error::new(message).extend_with(|_, extensions| {
+        extensions.set("code", code);
+        if let Some(details) = details {
+            extensions.set("details", async_graphql::Value::from_json(details).unwrap_or_default());
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_schema_builds() {
+        let schema = build_schema(&GraphQLSettings::default());
+        let sdl = schema.sdl();
+
+        assert!(sdl.contains("type User"));
+        assert!(sdl.contains("type OrderConnection"));
+        assert!(sdl.contains("createPayment"));
+    }
+
+    #[tokio::test]
+    async fn test_depth_limit_is_enforced() {
+        let schema = build_schema(&GraphQLSettings {
+            max_depth: 2,
+            ..GraphQLSettings::default()
+        });
+
+        let response = schema.execute("{ orders { edges { node { user { id } } } } }").await;
+        assert!(!response.errors.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_complexity_limit_is_enforced() {
+        let schema = build_schema(&GraphQLSettings {
+            max_complexity: 50,
+            ..GraphQLSettings::default()
+        });
+
+        let response = schema.execute("{ users(first: 100) { edges { node { id email } } } }").await;
+        assert!(!response.errors.is_empty());
+    }
+
+    #[test]
+    fn test_internal_errors_are_hidden() {
+        let error = gql_error(&AppError::Internal("connection refused".to_string()));
+        assert_eq!(error.message, "Internal server error");
+
+        let error = gql_error(&AppError::NotFound("User not found".to_string()));
+        assert!(error.message.contains("User not found"));
+    }
+}
@@ -0,0 +1,133 @@
+//! GraphQL mutation root
+
+use async_graphql::{Context, Object, Result};
+use database::{CreateOrderDto, CreateUserDto, UpdateUserDto};
+use shared::events;
+use uuid::Uuid;
+
+use super::{
+    auth_context, gql_error,
+    types::{CreateOrderInput, CreateUserInput, OrderNode, PaymentMethodType, PaymentNode, UpdateUserInput, UserNode},
+};
+use crate::{
+    domain_events::{order_event, payment_event},
+    extractors::validate,
+    realtime::{order_notification, payment_notification},
+    services::{OrderService, PaymentService, UserService},
+    state::AppState,
+};
+
+pub struct MutationRoot;
+
+#[Object]
+impl MutationRoot {
+    /// Create a user in the caller's tenant
+    async fn create_user(&self, ctx: &Context<'_>, input: CreateUserInput) -> Result<UserNode> {
+        let auth = auth_context(ctx)?;
+        let input = validate(input).map_err(|e| gql_error(&e))?;
+        let dto = CreateUserDto {
+            tenant_id: auth.tenant_id,
+            email: input.email,
+            username: input.username,
+            password: input.password,
+            first_name: input.first_name,
+            last_name: input.last_name,
+        };
+
+        let user = UserService::new(ctx.data::<AppState>()?)
+            .create(auth, dto)
+            .await
+            .map_err(|e| gql_error(&e))?;
+
+        Ok(UserNode(user))
+    }
+
+    /// Update a user of the caller's tenant
+    async fn update_user(&self, ctx: &Context<'_>, id: Uuid, input: UpdateUserInput) -> Result<UserNode> {
+        let auth = auth_context(ctx)?;
+        let input = validate(input).map_err(|e| gql_error(&e))?;
+        let dto = UpdateUserDto {
+            email: input.email,
+            username: input.username,
+            first_name: input.first_name,
+            last_name: input.last_name,
+            is_active: input.is_active,
+            is_verified: None,
+        };
+
+        let user = UserService::new(ctx.data::<AppState>()?)
+            .update(auth, &id, dto)
+            .await
+            .map_err(|e| gql_error(&e))?;
+
+        Ok(UserNode(user))
+    }
+
+    /// Delete a user of the caller's tenant
+    async fn delete_user(&self, ctx: &Context<'_>, id: Uuid) -> Result<bool> {
+        let auth = auth_context(ctx)?;
+
+        UserService::new(ctx.data::<AppState>()?)
+            .delete(auth, &id)
+            .await
+            .map_err(|e| gql_error(&e))
+    }
+
+    /// Place an order
+    async fn create_order(&self, ctx: &Context<'_>, input: CreateOrderInput) -> Result<OrderNode> {
+        let auth = auth_context(ctx)?;
+        let dto = CreateOrderDto {
+            tenant_id: auth.tenant_id,
+            user_id: input.user_id.unwrap_or(auth.user_id),
+            items: input.items,
+            total_amount: input.total_amount,
+            currency: input.currency,
+            shipping_address: input.shipping_address,
+            billing_address: input.billing_address,
+            notes: input.notes,
+        };
+
+        let state = ctx.data::<AppState>()?;
+        let order = OrderService::new(state)
+            .create(auth, dto)
+            .await
+            .map_err(|e| gql_error(&e))?;
+
+        state.notifications().publish(order_notification(&order, events::ORDER_CREATED)).await;
+        state.domain_events().publish(&order.id.to_string(), order_event(&order, events::ORDER_CREATED)).await;
+        Ok(OrderNode(order))
+    }
+
+    /// Cancel a pending or confirmed order
+    async fn cancel_order(&self, ctx: &Context<'_>, id: Uuid) -> Result<OrderNode> {
+        let auth = auth_context(ctx)?;
+
+        let state = ctx.data::<AppState>()?;
+        let order = OrderService::new(state)
+            .cancel(auth, &id)
+            .await
+            .map_err(|e| gql_error(&e))?;
+
+        state.notifications().publish(order_notification(&order, events::ORDER_CANCELLED)).await;
+        state.domain_events().publish(&order.id.to_string(), order_event(&order, events::ORDER_CANCELLED)).await;
+        Ok(OrderNode(order))
+    }
+
+    /// Start a payment for the full amount of an order
+    async fn create_payment(&self, ctx: &Context<'_>, order_id: Uuid, method: PaymentMethodType) -> Result<PaymentNode> {
+        let auth = auth_context(ctx)?;
+
+        let state = ctx.data::<AppState>()?;
+        let payment = PaymentService::new(state)
+            .create(auth, &order_id, method.into())
+            .await
+            .map_err(|e| gql_error(&e))?;
+
+        state.notifications().publish(payment_notification(&payment, events::PAYMENT_CREATED)).await;
+        state
+            .domain_events()
+            .publish(&payment.order_id.to_string(), payment_event(&payment, events::PAYMENT_CREATED))
+            .await;
+        Ok(PaymentNode(payment))
+    }
+}
@@ -0,0 +1,108 @@
+//! GraphQL query root
+
+use async_graphql::{connection::Connection, Context, Object, Result};
+use uuid::Uuid;
+
+use super::{
+    auth_context, gql_error,
+    loaders::Loaders,
+    types::{connection_complexity, offset_connection, OrderNode, PaymentNode, UserNode},
+};
+use crate::{
+    services::{OrderService, PaymentService, UserService},
+    state::AppState,
+};
+
+pub struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    /// The authenticated user
+    async fn me(&self, ctx: &Context<'_>) -> Result<UserNode> {
+        let auth = auth_context(ctx)?;
+        let user = UserService::new(ctx.data::<AppState>()?)
+            .get(auth, &auth.user_id)
+            .await
+            .map_err(|e| gql_error(&e))?;
+
+        Ok(UserNode(user))
+    }
+
+    /// Look up a user of the caller's tenant
+    async fn user(&self, ctx: &Context<'_>, id: Uuid) -> Result<Option<UserNode>> {
+        let user = ctx.data::<Loaders>()?.users.load_one(id).await.map_err(|e| gql_error(&e))?;
+        Ok(user.map(UserNode))
+    }
+
+    /// Users of the caller's tenant
+    #[graphql(complexity = "connection_complexity(first, last, child_complexity)")]
+    async fn users(
+        &self,
+        ctx: &Context<'_>,
+        after: Option<String>,
+        before: Option<String>,
+        first: Option<i32>,
+        last: Option<i32>,
+    ) -> Result<Connection<usize, UserNode>> {
+        let auth = auth_context(ctx)?;
+        let service = UserService::new(ctx.data::<AppState>()?);
+
+        offset_connection(after, before, first, last, |params| {
+            let service = &service;
+            async move { service.list(auth, &params).await }
+        })
+        .await
+    }
+
+    /// Look up an order of the caller's tenant
+    async fn order(&self, ctx: &Context<'_>, id: Uuid) -> Result<Option<OrderNode>> {
+        let order = ctx.data::<Loaders>()?.orders.load_one(id).await.map_err(|e| gql_error(&e))?;
+        Ok(order.map(OrderNode))
+    }
+
+    /// Orders of the caller's tenant
+    #[graphql(complexity = "connection_complexity(first, last, child_complexity)")]
+    async fn orders(
+        &self,
+        ctx: &Context<'_>,
+        after: Option<String>,
+        before: Option<String>,
+        first: Option<i32>,
+        last: Option<i32>,
+    ) -> Result<Connection<usize, OrderNode>> {
+        let auth = auth_context(ctx)?;
+        let service = OrderService::new(ctx.data::<AppState>()?);
+
+        offset_connection(after, before, first, last, |params| {
+            let service = &service;
+            async move { service.list(auth, &params).await }
+        })
+        .await
+    }
+
+    /// Look up a payment of the caller's tenant
+    async fn payment(&self, ctx: &Context<'_>, id: Uuid) -> Result<Option<PaymentNode>> {
+        let payment = ctx.data::<Loaders>()?.payments.load_one(id).await.map_err(|e| gql_error(&e))?;
+        Ok(payment.map(PaymentNode))
+    }
+
+    /// Payments of the caller's tenant
+    #[graphql(complexity = "connection_complexity(first, last, child_complexity)")]
+    async fn payments(
+        &self,
+        ctx: &Context<'_>,
+        after: Option<String>,
+        before: Option<String>,
+        first: Option<i32>,
+        last: Option<i32>,
+    ) -> Result<Connection<usize, PaymentNode>> {
+        let auth = auth_context(ctx)?;
+        let service = PaymentService::new(ctx.data::<AppState>()?);
+
+        offset_connection(after, before, first, last, |params| {
+            let service = &service;
+            async move { service.list(auth, &params).await }
+        })
+        .await
+    }
+}
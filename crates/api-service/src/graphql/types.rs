@@ -0,0 +1,515 @@
+//! GraphQL object, enum and input types
+
+use async_graphql::{connection::{self, Connection, Edge}, Context, Enum, InputObject, Object, OutputType, Result};
+use chrono::{DateTime, Utc};
+use database::{Order, OrderStatus, Payment, PaymentMethod, PaymentStatus, User};
+use shared::{validation, AppResult, PaginatedResponse, PaginationParams, DEFAULT_PAGE_SIZE, MAX_PAGE_SIZE};
+use std::future::Future;
+use uuid::Uuid;
+use validator::Validate;
+
+use super::{gql_error, loaders::Loaders};
+
+/// User exposed through GraphQL
+pub struct UserNode(pub User);
+
+impl From<User> for UserNode {
+    fn from(user: User) -> Self {
+        Self(user)
+    }
+}
+
+#[Object(name = "User")]
+impl UserNode {
+    async fn id(&self) -> Uuid {
+        self.0.id
+    }
+
+    async fn tenant_id(&self) -> Uuid {
+        self.0.tenant_id
+    }
+
+    async fn email(&self) -> &str {
+        &self.0.email
+    }
+
+    async fn username(&self) -> &str {
+        &self.0.username
+    }
+
+    async fn first_name(&self) -> Option<&str> {
+        self.0.first_name.as_deref()
+    }
+
+    async fn last_name(&self) -> Option<&str> {
+        self.0.last_name.as_deref()
+    }
+
+    async fn is_active(&self) -> bool {
+        self.0.is_active
+    }
+
+    async fn is_verified(&self) -> bool {
+        self.0.is_verified
+    }
+
+    async fn last_login_at(&self) -> Option<DateTime<Utc>> {
+        self.0.last_login_at
+    }
+
+    async fn created_at(&self) -> DateTime<Utc> {
+        self.0.created_at
+    }
+
+    async fn updated_at(&self) -> DateTime<Utc> {
+        self.0.updated_at
+    }
+
+    /// Orders placed by this user
+    #[graphql(complexity = "10 * child_complexity")]
+    async fn orders(&self, ctx: &Context<'_>) -> Result<Vec<OrderNode>> {
+        let orders = ctx
+            .data::<Loaders>()?
+            .orders_by_user
+            .load_one(self.0.id)
+            .await
+            .map_err(|e| gql_error(&e))?;
+
+        Ok(orders.unwrap_or_default().into_iter().map(OrderNode).collect())
+    }
+}
+
+/// Order exposed through GraphQL
+pub struct OrderNode(pub Order);
+
+impl From<Order> for OrderNode {
+    fn from(order: Order) -> Self {
+        Self(order)
+    }
+}
+
+#[Object(name = "Order")]
+impl OrderNode {
+    async fn id(&self) -> Uuid {
+        self.0.id
+    }
+
+    async fn order_number(&self) -> &str {
+        &self.0.order_number
+    }
+
+    async fn status(&self) -> OrderStatusType {
+        (&self.0.status).into()
+    }
+
+    /// Total amount in cents
+    async fn total_amount(&self) -> i64 {
+        self.0.total_amount
+    }
+
+    async fn currency(&self) -> &str {
+        &self.0.currency
+    }
+
+    async fn items(&self) -> &serde_json::Value {
+        &self.0.items
+    }
+
+    async fn shipping_address(&self) -> Option<&serde_json::Value> {
+        self.0.shipping_address.as_ref()
+    }
+
+    async fn billing_address(&self) -> Option<&serde_json::Value> {
+        self.0.billing_address.as_ref()
+    }
+
+    async fn notes(&self) -> Option<&str> {
+        self.0.notes.as_deref()
+    }
+
+    async fn created_at(&self) -> DateTime<Utc> {
+        self.0.created_at
+    }
+
+    async fn updated_at(&self) -> DateTime<Utc> {
+        self.0.updated_at
+    }
+
+    /// User who placed the order
+    async fn user(&self, ctx: &Context<'_>) -> Result<Option<UserNode>> {
+        let user = ctx
+            .data::<Loaders>()?
+            .users
+            .load_one(self.0.user_id)
+            .await
+            .map_err(|e| gql_error(&e))?;
+
+        Ok(user.map(UserNode))
+    }
+
+    /// Payments made for this order
+    #[graphql(complexity = "5 * child_complexity")]
+    async fn payments(&self, ctx: &Context<'_>) -> Result<Vec<PaymentNode>> {
+        let payments = ctx
+            .data::<Loaders>()?
+            .payments_by_order
+            .load_one(self.0.id)
+            .await
+            .map_err(|e| gql_error(&e))?;
+
+        Ok(payments.unwrap_or_default().into_iter().map(PaymentNode).collect())
+    }
+}
+
+/// Payment exposed through GraphQL
+pub struct PaymentNode(pub Payment);
+
+impl From<Payment> for PaymentNode {
+    fn from(payment: Payment) -> Self {
+        Self(payment)
+    }
+}
+
+#[Object(name = "Payment")]
+impl PaymentNode {
+    async fn id(&self) -> Uuid {
+        self.0.id
+    }
+
+    async fn payment_method(&self) -> PaymentMethodType {
+        (&self.0.payment_method).into()
+    }
+
+    async fn status(&self) -> PaymentStatusType {
+        (&self.0.status).into()
+    }
+
+    /// Amount in cents
+    async fn amount(&self) -> i64 {
+        self.0.amount
+    }
+
+    async fn currency(&self) -> &str {
+        &self.0.currency
+    }
+
+    async fn external_id(&self) -> Option<&str> {
+        self.0.external_id.as_deref()
+    }
+
+    async fn failure_reason(&self) -> Option<&str> {
+        self.0.failure_reason.as_deref()
+    }
+
+    async fn processed_at(&self) -> Option<DateTime<Utc>> {
+        self.0.processed_at
+    }
+
+    async fn created_at(&self) -> DateTime<Utc> {
+        self.0.created_at
+    }
+
+    async fn updated_at(&self) -> DateTime<Utc> {
+        self.0.updated_at
+    }
+
+    /// Order this payment belongs to
+    async fn order(&self, ctx: &Context<'_>) -> Result<Option<OrderNode>> {
+        let order = ctx
+            .data::<Loaders>()?
+            .orders
+            .load_one(self.0.order_id)
+            .await
+            .map_err(|e| gql_error(&e))?;
+
+        Ok(order.map(OrderNode))
+    }
+
+    /// User who made the payment
+    async fn user(&self, ctx: &Context<'_>) -> Result<Option<UserNode>> {
+        let user = ctx
+            .data::<Loaders>()?
+            .users
+            .load_one(self.0.user_id)
+            .await
+            .map_err(|e| gql_error(&e))?;
+
+        Ok(user.map(UserNode))
+    }
+}
+
+/// Order status
+#[derive(Enum, Debug, Copy, Clone, Eq, PartialEq)]
+#[graphql(name = "OrderStatus")]
+pub enum OrderStatusType {
+    Pending,
+    Confirmed,
+    Processing,
+    Shipped,
+    Delivered,
+    Cancelled,
+    Refunded,
+}
+
+impl From<&OrderStatus> for OrderStatusType {
+    fn from(status: &OrderStatus) -> Self {
+        match status {
+            OrderStatus::Pending => Self::Pending,
+            OrderStatus::Confirmed => Self::Confirmed,
+            OrderStatus::Processing => Self::Processing,
+            OrderStatus::Shipped => Self::Shipped,
+            OrderStatus::Delivered => Self::Delivered,
+            OrderStatus::Cancelled => Self::Cancelled,
+            OrderStatus::Refunded => Self::Refunded,
+        }
+    }
+}
+
+/// Payment method
+#[derive(Enum, Debug, Copy, Clone, Eq, PartialEq)]
+#[graphql(name = "PaymentMethod")]
+pub enum PaymentMethodType {
+    CreditCard,
+    DebitCard,
+    PayPal,
+    BankTransfer,
+    Cryptocurrency,
+    Cash,
+}
+
+impl From<&PaymentMethod> for PaymentMethodType {
+    fn from(method: &PaymentMethod) -> Self {
+        match method {
+            PaymentMethod::CreditCard => Self::CreditCard,
+            PaymentMethod::DebitCard => Self::DebitCard,
+            PaymentMethod::PayPal => Self::PayPal,
+            PaymentMethod::BankTransfer => Self::BankTransfer,
+            PaymentMethod::Cryptocurrency => Self::Cryptocurrency,
+            PaymentMethod::Cash => Self::Cash,
+        }
+    }
+}
+
+impl From<PaymentMethodType> for PaymentMethod {
+    fn from(method: PaymentMethodType) -> Self {
+        match method {
+            PaymentMethodType::CreditCard => Self::CreditCard,
+            PaymentMethodType::DebitCard => Self::DebitCard,
+            PaymentMethodType::PayPal => Self::PayPal,
+            PaymentMethodType::BankTransfer => Self::BankTransfer,
+            PaymentMethodType::Cryptocurrency => Self::Cryptocurrency,
+            PaymentMethodType::Cash => Self::Cash,
+        }
+    }
+}
+
+/// Payment status
+#[derive(Enum, Debug, Copy, Clone, Eq, PartialEq)]
+#[graphql(name = "PaymentStatus")]
+pub enum PaymentStatusType {
+    Pending,
+    Processing,
+    Completed,
+    Failed,
+    Cancelled,
+    Refunded,
+}
+
+impl From<&PaymentStatus> for PaymentStatusType {
+    fn from(status: &PaymentStatus) -> Self {
+        match status {
+            PaymentStatus::Pending => Self::Pending,
+            PaymentStatus::Processing => Self::Processing,
+            PaymentStatus::Completed => Self::Completed,
+            PaymentStatus::Failed => Self::Failed,
+            PaymentStatus::Cancelled => Self::Cancelled,
+            PaymentStatus::Refunded => Self::Refunded,
+        }
+    }
+}
+
+/// Create user input, checked like the REST `CreateUserRequest`
+#[derive(InputObject, Validate)]
+pub struct CreateUserInput {
+    #[validate(email, length(max = "validation::MAX_EMAIL_LENGTH"))]
+    pub email: String,
+    #[validate(length(min = "validation::MIN_USERNAME_LENGTH", max = "validation::MAX_USERNAME_LENGTH"))]
+    pub username: String,
+    #[validate(length(min = "validation::MIN_PASSWORD_LENGTH", max = "validation::MAX_PASSWORD_LENGTH"))]
+    pub password: String,
+    #[validate(length(max = "validation::MAX_NAME_LENGTH"))]
+    pub first_name: Option<String>,
+    #[validate(length(max = "validation::MAX_NAME_LENGTH"))]
+    pub last_name: Option<String>,
+}
+
+/// Update user input, checked like the REST `UpdateUserRequest`
+#[derive(InputObject, Validate)]
+pub struct UpdateUserInput {
+    #[validate(email, length(max = "validation::MAX_EMAIL_LENGTH"))]
+    pub email: Option<String>,
+    #[validate(length(min = "validation::MIN_USERNAME_LENGTH", max = "validation::MAX_USERNAME_LENGTH"))]
+    pub username: Option<String>,
+    #[validate(length(max = "validation::MAX_NAME_LENGTH"))]
+    pub first_name: Option<String>,
+    #[validate(length(max = "validation::MAX_NAME_LENGTH"))]
+    pub last_name: Option<String>,
+    pub is_active: Option<bool>,
+}
+
+/// Create order input
+#[derive(InputObject)]
+pub struct CreateOrderInput {
+    /// Defaults to the authenticated user
+    pub user_id: Option<Uuid>,
+    pub items: serde_json::Value,
+    /// Total amount in cents
+    pub total_amount: i64,
+    pub currency: String,
+    pub shipping_address: Option<serde_json::Value>,
+    pub billing_address: Option<serde_json::Value>,
+    pub notes: Option<String>,
+}
+
+/// Resolve a Relay-style connection over an offset-paginated listing.
+///
+/// Cursors are item offsets; at most `MAX_PAGE_SIZE` items are fetched per page.
+pub async fn offset_connection<T, N, F, Fut>(
+    after: Option<String>,
+    before: Option<String>,
+    first: Option<i32>,
+    last: Option<i32>,
+    fetch: F,
+) -> Result<Connection<usize, N>>
+where
+    N: OutputType + From<T>,
+    F: Fn(PaginationParams) -> Fut,
+    Fut: Future<Output = AppResult<PaginatedResponse<T>>>,
+{
+    connection::query(after, before, first, last, |after, before, first, last| async move {
+        let (start, end) = match page_window(after, before, first, last) {
+            Some(window) => window,
+            None => {
+                // `last` without `before` counts back from the end of the listing
+                let total = fetch(page_params(0, 1)).await.map_err(|e| gql_error(&e))?.pagination.total.unwrap_or(0) as usize;
+                page_window(after, Some(before.unwrap_or(total).min(total)), first, last)
+                    .unwrap_or((0, 0))
+            }
+        };
+
+        let page = fetch(page_params(start, end - start)).await.map_err(|e| gql_error(&e))?;
+        let has_next = match page.pagination.total {
+            Some(total) => end < total as usize,
+            None => page.pagination.has_next,
+        };
+
+        let mut connection = Connection::new(start > 0, has_next);
+        connection.edges.extend(
+            page.data
+                .into_iter()
+                .enumerate()
+                .map(|(index, item)| Edge::new(start + index, N::from(item))),
+        );
+
+        Ok::<_, async_graphql::Error>(connection)
+    })
+    .await
+}
+
+/// Compute the `[start, end)` offset window for connection arguments.
+///
+/// Returns `None` when the end of the window depends on the total item count.
+fn page_window(after: Option<usize>, before: Option<usize>, first: Option<usize>, last: Option<usize>) -> Option<(usize, usize)> {
+    let mut start = after.map(|after| after + 1).unwrap_or(0);
+    let mut end = before;
+
+    if let Some(first) = first {
+        end = Some(end.map_or(start + first, |end| end.min(start + first)));
+    }
+
+    let end = match (end, last) {
+        (Some(end), _) => end,
+        (None, Some(_)) => return None,
+        (None, None) => start + DEFAULT_PAGE_SIZE as usize,
+    };
+
+    if let Some(last) = last {
+        start = start.max(end.saturating_sub(last));
+    }
+
+    let end = end.max(start).min(start + MAX_PAGE_SIZE as usize);
+    Some((start, end))
+}
+
+/// Complexity of a connection field, scaled by the requested page size
+pub fn connection_complexity(first: Option<i32>, last: Option<i32>, child_complexity: usize) -> usize {
+    let page_size = first
+        .or(last)
+        .map_or(DEFAULT_PAGE_SIZE as usize, |size| size.max(0) as usize)
+        .min(MAX_PAGE_SIZE as usize);
+
+    page_size.max(1) * child_complexity
+}
+
+fn page_params(offset: usize, limit: usize) -> PaginationParams {
+    PaginationParams {
+        limit: Some(limit as u32),
+        offset: Some(offset as u32),
+        cursor: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_page_window_defaults() {
+        assert_eq!(page_window(None, None, None, None), Some((0, DEFAULT_PAGE_SIZE as usize)));
+        assert_eq!(page_window(Some(9), None, Some(5), None), Some((10, 15)));
+    }
+
+    #[test]
+    fn test_page_window_backwards() {
+        assert_eq!(page_window(None, Some(10), None, Some(3)), Some((7, 10)));
+        assert_eq!(page_window(None, None, None, Some(3)), None);
+        assert_eq!(page_window(Some(8), Some(10), None, Some(5)), Some((9, 10)));
+    }
+
+    #[test]
+    fn test_connection_complexity() {
+        assert_eq!(connection_complexity(Some(5), None, 3), 15);
+        assert_eq!(connection_complexity(None, None, 1), DEFAULT_PAGE_SIZE as usize);
+        assert_eq!(connection_complexity(Some(i32::MAX), None, 1), MAX_PAGE_SIZE as usize);
+    }
+
+    #[test]
+    fn test_user_input_validation() {
+        let input = CreateUserInput {
+            email: "not-an-email".to_string(),
+            username: "jo".to_string(),
+            password: "secret".to_string(),
+            first_name: None,
+            last_name: Some("x".repeat(validation::MAX_NAME_LENGTH as usize + 1)),
+        };
+        let errors = input.validate().unwrap_err();
+        let mut fields: Vec<_> = errors.field_errors().into_keys().collect();
+        fields.sort_unstable();
+        assert_eq!(fields, ["email", "last_name", "password", "username"]);
+
+        let input = UpdateUserInput {
+            email: None,
+            username: Some("jo".to_string()),
+            first_name: None,
+            last_name: None,
+            is_active: Some(false),
+        };
+        assert!(input.validate().unwrap_err().field_errors().contains_key("username"));
+    }
+
+    #[test]
+    fn test_page_window_is_capped() {
+        let (start, end) = page_window(None, None, Some(100_000), None).unwrap();
+        assert_eq!(end - start, MAX_PAGE_SIZE as usize);
+    }
+}
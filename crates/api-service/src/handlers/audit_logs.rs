@@ -0,0 +1,204 @@
+//! Audit log query and export handlers
+
+use axum::{
+    body::Body,
+    extract::State,
+    http::header::{CONTENT_DISPOSITION, CONTENT_TYPE},
+    response::{IntoResponse, Json, Response},
+};
+use chrono::{DateTime, Utc};
+use database::{AuditLog, AuditLogFilter};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use shared::{AppResult, PaginatedResponse};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    config::ApiErrorResponse,
+    extractors::ValidatedQuery,
+    middleware::auth::AuthContext,
+    services::{AuditService, ExportFormat},
+    state::AppState,
+};
+
+/// Audit log query parameters
+#[derive(Debug, Deserialize, IntoParams, Validate)]
+#[into_params(parameter_in = Query)]
+pub struct AuditLogQuery {
+    /// Only changes made by this user
+    pub user_id: Option<Uuid>,
+
+    /// Only this action, e.g. `create`, `update`, `delete` or `export`
+    pub action: Option<String>,
+
+    /// Only this resource type, e.g. `user`, `order` or `payment`
+    pub resource_type: Option<String>,
+
+    /// Only changes to this resource
+    pub resource_id: Option<Uuid>,
+
+    /// Entries recorded at or after this time
+    pub from: Option<DateTime<Utc>>,
+
+    /// Entries recorded before this time
+    pub to: Option<DateTime<Utc>>,
+
+    /// Maximum entries per page
+    #[validate(range(min = 1, max = "shared::MAX_PAGE_SIZE"))]
+    pub limit: Option<u32>,
+
+    /// `next_cursor` of the previous page
+    pub cursor: Option<String>,
+}
+
+/// Audit log export parameters
+#[derive(Debug, Deserialize, IntoParams, Validate)]
+#[into_params(parameter_in = Query)]
+pub struct AuditLogExportQuery {
+    /// File format, `csv` by default
+    #[serde(default)]
+    pub format: ExportFormat,
+
+    /// Only changes made by this user
+    pub user_id: Option<Uuid>,
+
+    /// Only this action
+    pub action: Option<String>,
+
+    /// Only this resource type
+    pub resource_type: Option<String>,
+
+    /// Only changes to this resource
+    pub resource_id: Option<Uuid>,
+
+    /// Entries recorded at or after this time
+    pub from: DateTime<Utc>,
+
+    /// Entries recorded before this time
+    pub to: DateTime<Utc>,
+}
+
+/// Audit log entry returned to API clients
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct AuditLogResponse {
+    pub id: Uuid,
+    pub user_id: Option<Uuid>,
+    pub action: String,
+    pub resource_type: String,
+    pub resource_id: Option<Uuid>,
+    /// Changed fields before the change, with sensitive values redacted
+    pub old_values: Option<Value>,
+    /// Changed fields after the change, with sensitive values redacted
+    pub new_values: Option<Value>,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    pub correlation_id: Uuid,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<AuditLog> for AuditLogResponse {
+    fn from(entry: AuditLog) -> Self {
+        Self {
+            id: entry.id,
+            user_id: entry.user_id,
+            action: entry.action,
+            resource_type: entry.resource_type,
+            resource_id: entry.resource_id,
+            old_values: entry.old_values,
+            new_values: entry.new_values,
+            ip_address: entry.ip_address,
+            user_agent: entry.user_agent,
+            correlation_id: entry.correlation_id,
+            created_at: entry.created_at,
+        }
+    }
+}
+
+/// List the tenant's audit log, newest first
+#[utoipa::path(
+    get,
+    path = "/api/v1/audit-logs",
+    tag = "audit",
+    params(AuditLogQuery),
+    responses(
+        (status = 200, description = "Page of audit entries; pass `next_cursor` as `cursor` for the next page", body = PaginatedResponse<AuditLogResponse>),
+        (status = 400, description = "Invalid filter or cursor", body = ApiErrorResponse),
+        (status = 401, description = "Missing or invalid credentials", body = ApiErrorResponse),
+        (status = 403, description = "Missing the audit:read permission", body = ApiErrorResponse)
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn list_audit_logs(
+    State(state): State<AppState>,
+    auth: AuthContext,
+    ValidatedQuery(query): ValidatedQuery<AuditLogQuery>,
+) -> AppResult<Json<PaginatedResponse<AuditLogResponse>>> {
+    let limit = query
+        .limit
+        .unwrap_or_else(|| state.config().api.pagination.default_page_size);
+    let filter = AuditLogFilter {
+        user_id: query.user_id,
+        action: query.action,
+        resource_type: query.resource_type,
+        resource_id: query.resource_id,
+        from: query.from,
+        to: query.to,
+    };
+
+    let page = AuditService::new(&state)
+        .list(&auth, &filter, query.cursor.as_deref(), limit)
+        .await?;
+
+    Ok(Json(PaginatedResponse {
+        data: page.data.into_iter().map(AuditLogResponse::from).collect(),
+        pagination: page.pagination,
+    }))
+}
+
+/// Download the tenant's audit entries in a time range as CSV or NDJSON, oldest first.
+///
+/// The export is recorded in the audit log.
+#[utoipa::path(
+    get,
+    path = "/api/v1/audit-logs/export",
+    tag = "audit",
+    params(AuditLogExportQuery),
+    responses(
+        (status = 200, description = "Audit entries as CSV (`format=csv`) or NDJSON (`format=ndjson`)", content_type = "text/csv"),
+        (status = 400, description = "Invalid filter or time range", body = ApiErrorResponse),
+        (status = 401, description = "Missing or invalid credentials", body = ApiErrorResponse),
+        (status = 403, description = "Missing the audit:read permission", body = ApiErrorResponse)
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn export_audit_logs(
+    State(state): State<AppState>,
+    auth: AuthContext,
+    ValidatedQuery(query): ValidatedQuery<AuditLogExportQuery>,
+) -> AppResult<Response> {
+    let format = query.format;
+    let file_name = format!(
+        "audit-log-{}-{}.{}",
+        query.from.format("%Y%m%dT%H%M%SZ"),
+        query.to.format("%Y%m%dT%H%M%SZ"),
+        format.extension()
+    );
+    let filter = AuditLogFilter {
+        user_id: query.user_id,
+        action: query.action,
+        resource_type: query.resource_type,
+        resource_id: query.resource_id,
+        from: Some(query.from),
+        to: Some(query.to),
+    };
+
+    let entries = AuditService::new(&state).export(&auth, filter, format).await?;
+
+    let headers = [
+        (CONTENT_TYPE, format.content_type().to_string()),
+        (CONTENT_DISPOSITION, format!("attachment; filename=\"{}\"", file_name)),
+    ];
+    Ok((headers, Body::from_stream(entries)).into_response())
+}
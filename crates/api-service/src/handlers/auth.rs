@@ -1,20 +1,25 @@
 //! Authentication handlers
 
-use axum::{extract::State, http::StatusCode, response::Json};
+use axum::{extract::State, response::Json};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use shared::{validation, AppError, AppResult};
+use utoipa::ToSchema;
+use validator::Validate;
 
-use crate::state::AppState;
+use crate::{config::ApiErrorResponse, extractors::ValidatedJson, state::AppState};
 
 /// Login request
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema, Validate)]
 pub struct LoginRequest {
+    #[validate(email)]
     pub email: String,
+    #[validate(length(min = 1, max = "validation::MAX_PASSWORD_LENGTH"))]
     pub password: String,
 }
 
 /// Login response
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct LoginResponse {
     pub access_token: String,
     pub refresh_token: Option<String>,
@@ -22,52 +27,99 @@ pub struct LoginResponse {
 }
 
 /// Register request
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema, Validate)]
 pub struct RegisterRequest {
+    #[validate(email, length(max = "validation::MAX_EMAIL_LENGTH"))]
     pub email: String,
+    #[validate(length(min = "validation::MIN_USERNAME_LENGTH", max = "validation::MAX_USERNAME_LENGTH"))]
     pub username: String,
+    #[validate(length(min = "validation::MIN_PASSWORD_LENGTH", max = "validation::MAX_PASSWORD_LENGTH"))]
     pub password: String,
+    #[validate(length(max = "validation::MAX_NAME_LENGTH"))]
     pub first_name: Option<String>,
+    #[validate(length(max = "validation::MAX_NAME_LENGTH"))]
     pub last_name: Option<String>,
 }
 
 /// Refresh token request
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema, Validate)]
 pub struct RefreshTokenRequest {
+    #[validate(length(min = 1))]
     pub refresh_token: String,
 }
 
 /// Login handler
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/login",
+    tag = "auth",
+    request_body = LoginRequest,
+    responses(
+        (status = 200, description = "Authenticated successfully", body = LoginResponse),
+        (status = 400, description = "Invalid login data", body = ApiErrorResponse),
+        (status = 401, description = "Invalid credentials", body = ApiErrorResponse)
+    )
+)]
 pub async fn login(
     State(_state): State<AppState>,
-    Json(_payload): Json<LoginRequest>,
-) -> Result<Json<LoginResponse>, StatusCode> {
+    ValidatedJson(_payload): ValidatedJson<LoginRequest>,
+) -> AppResult<Json<LoginResponse>> {
     // TODO: Implement authentication logic
-    Err(StatusCode::NOT_IMPLEMENTED)
+    Err(AppError::NotImplemented("Login is not available yet".to_string()))
 }
 
 /// Register handler
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/register",
+    tag = "auth",
+    request_body = RegisterRequest,
+    responses(
+        (status = 200, description = "User registered", body = Object),
+        (status = 400, description = "Invalid registration data", body = ApiErrorResponse),
+        (status = 409, description = "Email or username already taken", body = ApiErrorResponse)
+    )
+)]
 pub async fn register(
     State(_state): State<AppState>,
-    Json(_payload): Json<RegisterRequest>,
-) -> Result<Json<Value>, StatusCode> {
+    ValidatedJson(_payload): ValidatedJson<RegisterRequest>,
+) -> AppResult<Json<Value>> {
     // TODO: Implement user registration logic
-    Err(StatusCode::NOT_IMPLEMENTED)
+    Err(AppError::NotImplemented("User registration is not available yet".to_string()))
 }
 
 /// Refresh token handler
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/refresh",
+    tag = "auth",
+    request_body = RefreshTokenRequest,
+    responses(
+        (status = 200, description = "Token refreshed", body = LoginResponse),
+        (status = 401, description = "Invalid or expired refresh token", body = ApiErrorResponse)
+    )
+)]
 pub async fn refresh_token(
     State(_state): State<AppState>,
-    Json(_payload): Json<RefreshTokenRequest>,
-) -> Result<Json<LoginResponse>, StatusCode> {
+    ValidatedJson(_payload): ValidatedJson<RefreshTokenRequest>,
+) -> AppResult<Json<LoginResponse>> {
     // TODO: Implement token refresh logic
-    Err(StatusCode::NOT_IMPLEMENTED)
+    Err(AppError::NotImplemented("Token refresh is not available yet".to_string()))
 }
 
 /// Logout handler
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/logout",
+    tag = "auth",
+    responses(
+        (status = 200, description = "Session terminated", body = Object)
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn logout(
     State(_state): State<AppState>,
-) -> Result<Json<Value>, StatusCode> {
+) -> AppResult<Json<Value>> {
     // TODO: Implement logout logic
-    Err(StatusCode::NOT_IMPLEMENTED)
-}
\ No newline at end of file
+    Err(AppError::NotImplemented("Logout is not available yet".to_string()))
+}
@@ -0,0 +1,344 @@
+//! Domain event stream handlers
+
+use axum::{
+    extract::{Query, State},
+    http::HeaderMap,
+    response::sse::{Event as SseEvent, KeepAlive, Sse},
+};
+use chrono::{DateTime, Utc};
+use database::{Event, EventRepository};
+use futures::{stream, Stream};
+use serde::Deserialize;
+use shared::{runtime::CancellationToken, AppError, AppResult, TenantId, UserId};
+use std::{collections::VecDeque, convert::Infallible, time::Duration};
+use utoipa::IntoParams;
+use uuid::Uuid;
+
+use crate::{config::ApiErrorResponse, middleware::auth::AuthContext, state::AppState};
+
+/// Event type prefixes exposed through the stream
+pub const STREAMED_EVENT_PREFIXES: [&str; 3] = ["user.", "order.", "payment."];
+
+/// Header sent by reconnecting `EventSource` clients
+const LAST_EVENT_ID_HEADER: &str = "Last-Event-ID";
+
+/// Event stream query parameters
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct EventStreamQuery {
+    /// Comma separated event types; a trailing `*` matches a prefix, e.g. `order.*,user.created`
+    pub types: Option<String>,
+
+    /// Resume after this event; takes precedence over the `Last-Event-ID` header
+    pub last_event_id: Option<Uuid>,
+}
+
+/// Stream the tenant's domain events as Server-Sent Events
+#[utoipa::path(
+    get,
+    path = "/api/v1/events/stream",
+    tag = "events",
+    params(
+        EventStreamQuery,
+        ("Last-Event-ID" = Option<Uuid>, Header, description = "Resume after this event")
+    ),
+    responses(
+        (status = 200, description = "Stream of domain events", content_type = "text/event-stream"),
+        (status = 400, description = "Invalid event type filter or unknown Last-Event-ID", body = ApiErrorResponse),
+        (status = 401, description = "Missing or invalid credentials", body = ApiErrorResponse)
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn stream_events(
+    State(state): State<AppState>,
+    auth: AuthContext,
+    Query(query): Query<EventStreamQuery>,
+    headers: HeaderMap,
+) -> AppResult<Sse<impl Stream<Item = Result<SseEvent, Infallible>>>> {
+    let patterns = type_patterns(query.types.as_deref()).ok_or_else(|| {
+        AppError::BadRequest(format!(
+            "Event types must start with one of: {}",
+            STREAMED_EVENT_PREFIXES.join(", ")
+        ))
+    })?;
+
+    let last_event_id = match query.last_event_id {
+        Some(id) => Some(id),
+        None => headers
+            .get(LAST_EVENT_ID_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .map(|value| {
+                value
+                    .trim()
+                    .parse::<Uuid>()
+                    .map_err(|_| AppError::BadRequest("Last-Event-ID must be an event id".to_string()))
+            })
+            .transpose()?,
+    };
+
+    let repository = EventRepository::new(state.database().pool().clone());
+
+    // Resume after the last delivered event, or start with events created from now on,
+    // by the database clock that assigns `created_at`
+    let start = match last_event_id {
+        Some(id) => {
+            let event = repository
+                .find_by_id(&id)
+                .await?
+                .filter(|event| event.tenant_id == Some(auth.tenant_id))
+                .ok_or_else(|| AppError::BadRequest(format!("Unknown Last-Event-ID: {}", id)))?;
+            (event.created_at, event.id)
+        }
+        None => (repository.current_time().await?, Uuid::nil()),
+    };
+
+    let config = state.config();
+    let settings = &config.api.event_stream;
+    let cursor = EventCursor {
+        repository,
+        tenant_id: auth.tenant_id,
+        visible_to: (!auth.is_admin()).then_some(auth.user_id),
+        patterns,
+        window: ReorderWindow::new(start, chrono::Duration::milliseconds(settings.reorder_window as i64)),
+        batch_size: settings.batch_size as i64,
+        poll_interval: Duration::from_millis(settings.poll_interval),
+        buffer: VecDeque::new(),
+        shutdown: state.shutdown_token().clone(),
+    };
+
+    let keep_alive = KeepAlive::new().interval(Duration::from_secs(settings.keep_alive_interval));
+    Ok(Sse::new(cursor.into_stream()).keep_alive(keep_alive))
+}
+
+/// Positions still worth re-reading while tailing the events table.
+///
+/// `created_at` is assigned when the inserting transaction starts, not when it commits, so an
+/// event can become visible after later-timestamped events were streamed. Instead of moving
+/// strictly forward, the cursor re-reads `width` behind the newest streamed event and skips the
+/// ids it already delivered there.
+struct ReorderWindow {
+    /// Events at or before this position predate the stream and are never delivered
+    start: (DateTime<Utc>, Uuid),
+    width: chrono::Duration,
+    /// Newest `created_at` delivered so far
+    newest: Option<DateTime<Utc>>,
+    /// Events delivered inside the window
+    delivered: Vec<(DateTime<Utc>, Uuid)>,
+}
+
+impl ReorderWindow {
+    fn new(start: (DateTime<Utc>, Uuid), width: chrono::Duration) -> Self {
+        Self {
+            start,
+            width,
+            newest: None,
+            delivered: Vec::new(),
+        }
+    }
+
+    /// Position to read after on the next poll
+    fn lower_bound(&self) -> (DateTime<Utc>, Uuid) {
+        match self.newest {
+            Some(newest) => self.start.max((newest - self.width, Uuid::nil())),
+            None => self.start,
+        }
+    }
+
+    /// Ids already delivered at or after the lower bound
+    fn delivered_ids(&self) -> Vec<Uuid> {
+        self.delivered.iter().map(|(_, id)| *id).collect()
+    }
+
+    fn record(&mut self, event: &Event) {
+        self.newest = Some(self.newest.map_or(event.created_at, |newest| newest.max(event.created_at)));
+        self.delivered.push((event.created_at, event.id));
+
+        let lower_bound = self.lower_bound();
+        self.delivered.retain(|position| *position > lower_bound);
+    }
+}
+
+/// Tails the events table in `(created_at, id)` order
+struct EventCursor {
+    repository: EventRepository,
+    tenant_id: TenantId,
+    visible_to: Option<UserId>,
+    patterns: Vec<String>,
+    window: ReorderWindow,
+    batch_size: i64,
+    poll_interval: Duration,
+    buffer: VecDeque<Event>,
+    /// Ends the stream when the service shuts down
+    shutdown: CancellationToken,
+}
+
+impl EventCursor {
+    fn into_stream(self) -> impl Stream<Item = Result<SseEvent, Infallible>> {
+        stream::unfold(self, |mut cursor| async move {
+            let shutdown = cursor.shutdown.clone();
+            let event = tokio::select! {
+                _ = shutdown.cancelled() => return None,
+                event = cursor.next_event() => event,
+            };
+            Some((Ok(to_sse_event(&event)), cursor))
+        })
+    }
+
+    /// Wait for the next event, polling the database while none are buffered
+    async fn next_event(&mut self) -> Event {
+        loop {
+            if let Some(event) = self.buffer.pop_front() {
+                self.window.record(&event);
+                return event;
+            }
+
+            match self
+                .repository
+                .find_after(
+                    &self.tenant_id,
+                    self.window.lower_bound(),
+                    &self.window.delivered_ids(),
+                    &self.patterns,
+                    self.visible_to.as_ref(),
+                    self.batch_size,
+                )
+                .await
+            {
+                Ok(events) if !events.is_empty() => {
+                    self.buffer.extend(events);
+                    continue;
+                }
+                Ok(_) => {}
+                Err(e) => tracing::warn!("Failed to poll events for tenant {}: {}", self.tenant_id, e),
+            }
+
+            tokio::time::sleep(self.poll_interval).await;
+        }
+    }
+}
+
+fn to_sse_event(event: &Event) -> SseEvent {
+    let data = serde_json::json!({
+        "id": event.id,
+        "type": event.event_type,
+        "aggregate_id": event.aggregate_id,
+        "aggregate_type": event.aggregate_type,
+        "version": event.version,
+        "payload": event.payload,
+        "correlation_id": event.correlation_id,
+        "user_id": event.user_id,
+        "created_at": event.created_at,
+    });
+
+    SseEvent::default()
+        .id(event.id.to_string())
+        .event(event.event_type.clone())
+        .data(data.to_string())
+}
+
+/// Translate a comma separated type filter into SQL `LIKE` patterns.
+///
+/// Returns `None` if any requested type is outside the streamed prefixes.
+fn type_patterns(types: Option<&str>) -> Option<Vec<String>> {
+    let requested: Vec<&str> = types
+        .map(|types| types.split(',').map(str::trim).filter(|t| !t.is_empty()).collect())
+        .unwrap_or_default();
+
+    if requested.is_empty() {
+        return Some(STREAMED_EVENT_PREFIXES.iter().map(|prefix| format!("{}%", escape_like(prefix))).collect());
+    }
+
+    requested
+        .into_iter()
+        .map(|event_type| {
+            let (literal, wildcard) = match event_type.strip_suffix('*') {
+                Some(prefix) => (prefix, "%"),
+                None => (event_type, ""),
+            };
+
+            STREAMED_EVENT_PREFIXES
+                .iter()
+                .any(|prefix| literal.starts_with(prefix) || (wildcard == "%" && prefix.starts_with(literal) && !literal.is_empty()))
+                .then(|| format!("{}{}", escape_like(literal), wildcard))
+        })
+        .collect()
+}
+
+fn escape_like(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_type_patterns() {
+        assert_eq!(type_patterns(None).unwrap(), vec!["user.%", "order.%", "payment.%"]);
+        assert_eq!(type_patterns(Some(" , ")).unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_type_patterns_with_wildcards() {
+        assert_eq!(
+            type_patterns(Some("order.*,user.created")).unwrap(),
+            vec!["order.%".to_string(), "user.created".to_string()]
+        );
+        assert_eq!(type_patterns(Some("payment*")).unwrap(), vec!["payment%".to_string()]);
+    }
+
+    #[test]
+    fn test_type_patterns_reject_other_events() {
+        assert!(type_patterns(Some("job.*")).is_none());
+        assert!(type_patterns(Some("*")).is_none());
+        assert!(type_patterns(Some("order.*,session.created")).is_none());
+    }
+
+    #[test]
+    fn test_escape_like() {
+        assert_eq!(escape_like("order.status_changed"), "order.status\\_changed");
+    }
+
+    fn event_at(created_at: DateTime<Utc>) -> Event {
+        Event {
+            id: Uuid::new_v4(),
+            tenant_id: None,
+            event_type: "order.created".to_string(),
+            aggregate_id: Uuid::new_v4(),
+            aggregate_type: "order".to_string(),
+            version: 1,
+            payload: serde_json::json!({}),
+            metadata: serde_json::json!({}),
+            correlation_id: Uuid::new_v4(),
+            causation_id: None,
+            user_id: None,
+            created_at,
+        }
+    }
+
+    #[test]
+    fn test_reorder_window_rereads_behind_newest_event() {
+        let start = Utc::now();
+        let width = chrono::Duration::seconds(5);
+        let mut window = ReorderWindow::new((start, Uuid::nil()), width);
+        assert_eq!(window.lower_bound(), (start, Uuid::nil()));
+
+        // Early events never move the bound before the start of the stream
+        let first = event_at(start + chrono::Duration::seconds(1));
+        window.record(&first);
+        assert_eq!(window.lower_bound(), (start, Uuid::nil()));
+        assert_eq!(window.delivered_ids(), vec![first.id]);
+
+        // A late commit with an older timestamp is still above the bound, and is not
+        // pruned until the window has moved past it
+        let newest = event_at(start + chrono::Duration::seconds(10));
+        window.record(&newest);
+        assert_eq!(window.lower_bound(), (newest.created_at - width, Uuid::nil()));
+        assert_eq!(window.delivered_ids(), vec![newest.id]);
+
+        let late = event_at(start + chrono::Duration::seconds(7));
+        assert!((late.created_at, late.id) > window.lower_bound());
+        window.record(&late);
+        assert_eq!(window.delivered_ids(), vec![newest.id, late.id]);
+    }
+}
@@ -0,0 +1,252 @@
+//! File upload and download handlers
+
+use axum::{
+    extract::{multipart::MultipartError, Multipart, Path, State},
+    http::{
+        header::{CACHE_CONTROL, CONTENT_DISPOSITION, CONTENT_TYPE, X_CONTENT_TYPE_OPTIONS},
+        StatusCode,
+    },
+    response::{IntoResponse, Json, Response},
+};
+use bytes::BytesMut;
+use chrono::{DateTime, TimeZone, Utc};
+use database::StoredFile;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use shared::{AppError, AppResult, API_BASE_PATH};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    config::ApiErrorResponse,
+    extractors::ValidatedQuery,
+    middleware::auth::AuthContext,
+    services::FileService,
+    state::AppState,
+};
+
+/// Multipart field carrying the uploaded file
+const FILE_FIELD: &str = "file";
+
+/// Multipart upload form
+#[derive(ToSchema)]
+#[allow(dead_code)]
+pub struct FileUploadForm {
+    /// File contents; the part's file name is kept as the file's name
+    #[schema(value_type = String, format = Binary)]
+    pub file: Vec<u8>,
+}
+
+/// Signed download link parameters
+#[derive(Debug, Deserialize, IntoParams, Validate)]
+#[into_params(parameter_in = Query)]
+pub struct DownloadQuery {
+    /// Unix time the link expires at
+    pub expires: i64,
+
+    /// Link signature
+    #[validate(length(max = 128))]
+    pub signature: String,
+}
+
+/// File metadata returned to API clients
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct FileResponse {
+    pub id: Uuid,
+    pub file_name: String,
+    /// Content type detected from the contents
+    pub content_type: String,
+    pub size_bytes: i64,
+    /// Hex-encoded SHA-256 of the contents
+    pub checksum_sha256: String,
+    pub uploaded_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    /// Link downloading the file without credentials until `download_url_expires_at`
+    pub download_url: String,
+    pub download_url_expires_at: DateTime<Utc>,
+}
+
+impl FileResponse {
+    fn new(file: StoredFile, service: &FileService) -> Self {
+        let link = service.sign_download(&file);
+        Self {
+            download_url: format!(
+                "{}/files/{}/download?expires={}&signature={}",
+                API_BASE_PATH, file.id, link.expires, link.signature
+            ),
+            download_url_expires_at: Utc.timestamp_opt(link.expires, 0).single().unwrap_or_default(),
+            id: file.id,
+            file_name: file.file_name,
+            content_type: file.content_type,
+            size_bytes: file.size_bytes,
+            checksum_sha256: file.checksum_sha256,
+            uploaded_by: file.uploaded_by,
+            created_at: file.created_at,
+        }
+    }
+}
+
+/// Upload a file to the caller's tenant
+#[utoipa::path(
+    post,
+    path = "/api/v1/files",
+    tag = "files",
+    request_body(content = FileUploadForm, content_type = "multipart/form-data"),
+    responses(
+        (status = 200, description = "File stored", body = FileResponse),
+        (status = 400, description = "Missing file, or a type that is not accepted", body = ApiErrorResponse),
+        (status = 401, description = "Missing or invalid credentials", body = ApiErrorResponse),
+        (status = 413, description = "File larger than the upload limit", body = ApiErrorResponse)
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn upload_file(
+    State(state): State<AppState>,
+    auth: AuthContext,
+    mut multipart: Multipart,
+) -> AppResult<Json<FileResponse>> {
+    let max_size = state.config().app.storage.max_upload_size;
+
+    while let Some(mut field) = multipart.next_field().await.map_err(multipart_error)? {
+        if field.name() != Some(FILE_FIELD) {
+            continue;
+        }
+        let file_name = field.file_name().unwrap_or_default().to_string();
+
+        // Stop reading as soon as the file is too large
+        let mut data = BytesMut::new();
+        while let Some(chunk) = field.chunk().await.map_err(multipart_error)? {
+            if data.len() + chunk.len() > max_size {
+                return Err(AppError::PayloadTooLarge(format!("Files cannot be larger than {} bytes", max_size)));
+            }
+            data.extend_from_slice(&chunk);
+        }
+
+        let service = FileService::new(&state);
+        let file = service.upload(&auth, &file_name, data.freeze()).await?;
+        return Ok(Json(FileResponse::new(file, &service)));
+    }
+
+    Err(AppError::BadRequest(format!("Expected a multipart field named '{}'", FILE_FIELD)))
+}
+
+/// Get a file's metadata and a fresh download link
+#[utoipa::path(
+    get,
+    path = "/api/v1/files/{id}",
+    tag = "files",
+    params(("id" = Uuid, Path, description = "File identifier")),
+    responses(
+        (status = 200, description = "File found", body = FileResponse),
+        (status = 404, description = "File not found", body = ApiErrorResponse)
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn get_file(
+    State(state): State<AppState>,
+    auth: AuthContext,
+    Path(file_id): Path<Uuid>,
+) -> AppResult<Json<FileResponse>> {
+    let service = FileService::new(&state);
+    let file = service.get(&auth, &file_id).await?;
+
+    Ok(Json(FileResponse::new(file, &service)))
+}
+
+/// Delete a file; only its uploader or an administrator may
+#[utoipa::path(
+    delete,
+    path = "/api/v1/files/{id}",
+    tag = "files",
+    params(("id" = Uuid, Path, description = "File identifier")),
+    responses(
+        (status = 200, description = "File deleted", body = Object),
+        (status = 403, description = "Not the uploader or an administrator", body = ApiErrorResponse),
+        (status = 404, description = "File not found", body = ApiErrorResponse)
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn delete_file(
+    State(state): State<AppState>,
+    auth: AuthContext,
+    Path(file_id): Path<Uuid>,
+) -> AppResult<Json<Value>> {
+    let deleted = FileService::new(&state)
+        .delete(&auth, &file_id)
+        .await?;
+
+    Ok(Json(json!({ "id": file_id, "deleted": deleted })))
+}
+
+/// Download a file through a signed link; no credentials are needed
+#[utoipa::path(
+    get,
+    path = "/api/v1/files/{id}/download",
+    tag = "files",
+    params(("id" = Uuid, Path, description = "File identifier"), DownloadQuery),
+    responses(
+        (status = 200, description = "File contents, with the detected content type", content_type = "application/octet-stream"),
+        (status = 403, description = "Invalid or expired link", body = ApiErrorResponse),
+        (status = 404, description = "File not found", body = ApiErrorResponse)
+    )
+)]
+pub async fn download_file(
+    State(state): State<AppState>,
+    Path(file_id): Path<Uuid>,
+    ValidatedQuery(query): ValidatedQuery<DownloadQuery>,
+) -> AppResult<Response> {
+    let (file, data) = FileService::new(&state)
+        .download(&file_id, query.expires, &query.signature)
+        .await?;
+
+    let headers = [
+        (CONTENT_TYPE, file.content_type.clone()),
+        (CONTENT_DISPOSITION, content_disposition(&file.file_name)),
+        (X_CONTENT_TYPE_OPTIONS, "nosniff".to_string()),
+        (CACHE_CONTROL, "private, no-store".to_string()),
+    ];
+    Ok((headers, data).into_response())
+}
+
+fn multipart_error(error: MultipartError) -> AppError {
+    if error.status() == StatusCode::PAYLOAD_TOO_LARGE {
+        AppError::PayloadTooLarge(error.body_text())
+    } else {
+        AppError::BadRequest(error.body_text())
+    }
+}
+
+/// `attachment` disposition with an ASCII fallback name and the exact name in RFC 5987 form
+fn content_disposition(file_name: &str) -> String {
+    let fallback: String = file_name
+        .chars()
+        .map(|c| if (c.is_ascii_graphic() && c != '"' && c != '\\') || c == ' ' { c } else { '_' })
+        .collect();
+    let encoded: String = file_name
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => (byte as char).to_string(),
+            _ => format!("%{:02X}", byte),
+        })
+        .collect();
+
+    format!("attachment; filename=\"{}\"; filename*=UTF-8''{}", fallback, encoded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_content_disposition() {
+        assert_eq!(
+            content_disposition("report.pdf"),
+            "attachment; filename=\"report.pdf\"; filename*=UTF-8''report.pdf"
+        );
+        assert_eq!(
+            content_disposition("naïve \"quote\".txt"),
+            "attachment; filename=\"na_ve _quote_.txt\"; filename*=UTF-8''na%C3%AFve%20%22quote%22.txt"
+        );
+    }
+}
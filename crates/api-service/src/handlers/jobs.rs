@@ -0,0 +1,295 @@
+//! Background job management handlers
+
+use axum::{
+    extract::{Path, State},
+    response::Json,
+};
+use chrono::{DateTime, Utc};
+use database::{Job, JobFilter, JobStatus};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use shared::{AppResult, PaginatedResponse, PaginationParams};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    config::ApiErrorResponse,
+    extractors::{ValidatedJson, ValidatedQuery},
+    middleware::auth::AuthContext,
+    services::{EnqueueJob, JobService},
+    state::AppState,
+};
+
+/// Most retries a job can be enqueued with
+const MAX_JOB_RETRIES: u32 = 10;
+
+/// Job lifecycle state
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum JobState {
+    Pending,
+    Running,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+impl From<JobStatus> for JobState {
+    fn from(status: JobStatus) -> Self {
+        match status {
+            JobStatus::Pending => Self::Pending,
+            JobStatus::Running => Self::Running,
+            JobStatus::Completed => Self::Completed,
+            JobStatus::Failed => Self::Failed,
+            JobStatus::Cancelled => Self::Cancelled,
+        }
+    }
+}
+
+impl From<JobState> for JobStatus {
+    fn from(state: JobState) -> Self {
+        match state {
+            JobState::Pending => Self::Pending,
+            JobState::Running => Self::Running,
+            JobState::Completed => Self::Completed,
+            JobState::Failed => Self::Failed,
+            JobState::Cancelled => Self::Cancelled,
+        }
+    }
+}
+
+/// Job list query parameters
+#[derive(Debug, Deserialize, IntoParams, Validate)]
+#[into_params(parameter_in = Query)]
+pub struct JobQuery {
+    /// Only jobs in this state
+    pub status: Option<JobState>,
+
+    /// Only jobs of this type, e.g. `send_email`
+    pub job_type: Option<String>,
+
+    /// Only jobs of this tenant; other tenants need the jobs:all-tenants permission
+    pub tenant_id: Option<Uuid>,
+
+    /// Maximum jobs per page
+    #[validate(range(min = 1, max = "shared::MAX_PAGE_SIZE"))]
+    pub limit: Option<u32>,
+
+    /// Jobs to skip
+    pub offset: Option<u32>,
+}
+
+/// Enqueue job request
+#[derive(Debug, Deserialize, ToSchema, Validate)]
+pub struct EnqueueJobRequest {
+    /// Registered job type, e.g. `send_email` or `generate_report`
+    #[validate(length(min = 1, max = 100))]
+    pub job_type: String,
+
+    /// Input passed to the job's processor
+    #[serde(default = "empty_payload")]
+    pub payload: Value,
+
+    /// Tenant the job runs for; the caller's tenant when omitted
+    pub tenant_id: Option<Uuid>,
+
+    /// Retries after a failure before the job is marked failed
+    #[validate(range(max = "MAX_JOB_RETRIES"))]
+    pub max_retries: Option<u32>,
+
+    /// Earliest time the job runs; immediately when omitted
+    pub scheduled_at: Option<DateTime<Utc>>,
+}
+
+fn empty_payload() -> Value {
+    Value::Object(Default::default())
+}
+
+/// Job representation returned to API clients
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct JobResponse {
+    pub id: Uuid,
+    pub tenant_id: Option<Uuid>,
+    pub job_type: String,
+    pub status: JobState,
+    pub payload: Value,
+    /// Output of a completed job
+    pub result: Option<Value>,
+    /// Last failure of the job
+    pub error: Option<String>,
+    pub retry_count: i32,
+    pub max_retries: i32,
+    /// Correlation id of the request that enqueued the job
+    pub correlation_id: Option<Uuid>,
+    pub scheduled_at: DateTime<Utc>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<Job> for JobResponse {
+    fn from(job: Job) -> Self {
+        Self {
+            id: job.id,
+            tenant_id: job.tenant_id,
+            job_type: job.job_type,
+            status: job.status.into(),
+            payload: job.payload,
+            result: job.result,
+            error: job.error,
+            retry_count: job.retry_count,
+            max_retries: job.max_retries,
+            correlation_id: job.correlation_id,
+            scheduled_at: job.scheduled_at,
+            started_at: job.started_at,
+            completed_at: job.completed_at,
+            created_at: job.created_at,
+            updated_at: job.updated_at,
+        }
+    }
+}
+
+/// Enqueue a background job
+#[utoipa::path(
+    post,
+    path = "/api/v1/jobs",
+    tag = "jobs",
+    request_body = EnqueueJobRequest,
+    responses(
+        (status = 200, description = "Job enqueued", body = JobResponse),
+        (status = 400, description = "Unknown job type or invalid request", body = ApiErrorResponse),
+        (status = 401, description = "Missing or invalid credentials", body = ApiErrorResponse),
+        (status = 403, description = "Not an administrator, or another tenant without the jobs:all-tenants permission", body = ApiErrorResponse)
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn enqueue_job(
+    State(state): State<AppState>,
+    auth: AuthContext,
+    ValidatedJson(request): ValidatedJson<EnqueueJobRequest>,
+) -> AppResult<Json<JobResponse>> {
+    let job = EnqueueJob {
+        job_type: request.job_type,
+        payload: request.payload,
+        tenant_id: request.tenant_id,
+        max_retries: request.max_retries,
+        scheduled_at: request.scheduled_at,
+    };
+
+    let job = JobService::new(&state).enqueue(&auth, job).await?;
+
+    Ok(Json(job.into()))
+}
+
+/// List jobs, newest first
+#[utoipa::path(
+    get,
+    path = "/api/v1/jobs",
+    tag = "jobs",
+    params(JobQuery),
+    responses(
+        (status = 200, description = "Page of jobs", body = PaginatedResponse<JobResponse>),
+        (status = 400, description = "Invalid filter or pagination parameters", body = ApiErrorResponse),
+        (status = 401, description = "Missing or invalid credentials", body = ApiErrorResponse),
+        (status = 403, description = "Not an administrator, or another tenant without the jobs:all-tenants permission", body = ApiErrorResponse)
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn list_jobs(
+    State(state): State<AppState>,
+    auth: AuthContext,
+    ValidatedQuery(query): ValidatedQuery<JobQuery>,
+) -> AppResult<Json<PaginatedResponse<JobResponse>>> {
+    let filter = JobFilter {
+        status: query.status.map(JobStatus::from),
+        job_type: query.job_type,
+        tenant_id: query.tenant_id,
+    };
+    let params = PaginationParams {
+        limit: Some(query.limit.unwrap_or(state.config().api.pagination.default_page_size)),
+        offset: Some(query.offset.unwrap_or(0)),
+        cursor: None,
+    };
+
+    let page = JobService::new(&state)
+        .list(&auth, filter, &params)
+        .await?;
+
+    Ok(Json(PaginatedResponse {
+        data: page.data.into_iter().map(JobResponse::from).collect(),
+        pagination: page.pagination,
+    }))
+}
+
+/// Get a job with its payload, result and error
+#[utoipa::path(
+    get,
+    path = "/api/v1/jobs/{id}",
+    tag = "jobs",
+    params(("id" = Uuid, Path, description = "Job identifier")),
+    responses(
+        (status = 200, description = "Job found", body = JobResponse),
+        (status = 403, description = "Not an administrator", body = ApiErrorResponse),
+        (status = 404, description = "Job not found", body = ApiErrorResponse)
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn get_job(
+    State(state): State<AppState>,
+    auth: AuthContext,
+    Path(job_id): Path<Uuid>,
+) -> AppResult<Json<JobResponse>> {
+    let job = JobService::new(&state).get(&auth, &job_id).await?;
+
+    Ok(Json(job.into()))
+}
+
+/// Cancel a pending or running job
+#[utoipa::path(
+    post,
+    path = "/api/v1/jobs/{id}/cancel",
+    tag = "jobs",
+    params(("id" = Uuid, Path, description = "Job identifier")),
+    responses(
+        (status = 200, description = "Job cancelled", body = JobResponse),
+        (status = 403, description = "Not an administrator", body = ApiErrorResponse),
+        (status = 404, description = "Job not found", body = ApiErrorResponse),
+        (status = 409, description = "Job already finished", body = ApiErrorResponse)
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn cancel_job(
+    State(state): State<AppState>,
+    auth: AuthContext,
+    Path(job_id): Path<Uuid>,
+) -> AppResult<Json<JobResponse>> {
+    let job = JobService::new(&state).cancel(&auth, &job_id).await?;
+
+    Ok(Json(job.into()))
+}
+
+/// Run a failed job again, with its retries reset
+#[utoipa::path(
+    post,
+    path = "/api/v1/jobs/{id}/retry",
+    tag = "jobs",
+    params(("id" = Uuid, Path, description = "Job identifier")),
+    responses(
+        (status = 200, description = "Job queued again", body = JobResponse),
+        (status = 403, description = "Not an administrator", body = ApiErrorResponse),
+        (status = 404, description = "Job not found", body = ApiErrorResponse),
+        (status = 409, description = "Job has not failed", body = ApiErrorResponse)
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn retry_job(
+    State(state): State<AppState>,
+    auth: AuthContext,
+    Path(job_id): Path<Uuid>,
+) -> AppResult<Json<JobResponse>> {
+    let job = JobService::new(&state).retry(&auth, &job_id).await?;
+
+    Ok(Json(job.into()))
+}
@@ -1,10 +1,18 @@
 //! API handlers
 
+pub mod audit_logs;
 pub mod auth;
+pub mod events;
+pub mod files;
 pub mod health;
+pub mod jobs;
 pub mod users;
 
 // Re-export handler modules
+pub use audit_logs::*;
 pub use auth::*;
+pub use events::*;
+pub use files::*;
 pub use health::*;
-pub use users::*;
\ No newline at end of file
+pub use jobs::*;
+pub use users::*;
@@ -1,101 +1,295 @@
 //! User management handlers
 
-use axum::{extract::{Path, Query, State}, http::StatusCode, response::Json};
+use axum::{extract::{Path, State}, response::Json};
+use chrono::{DateTime, Utc};
+use database::{CreateUserDto, UpdateUserDto};
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
-use shared::PaginationParams;
+use serde_json::{json, Value};
+use shared::{validation, AppResult, PaginatedResponse, PaginationParams};
+use utoipa::ToSchema;
 use uuid::Uuid;
+use validator::Validate;
 
-use crate::state::AppState;
+use crate::{
+    config::ApiErrorResponse,
+    extractors::{ValidatedJson, ValidatedQuery},
+    middleware::auth::AuthContext,
+    services::UserService,
+    state::AppState,
+};
 
 /// Create user request
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema, Validate)]
 pub struct CreateUserRequest {
+    #[validate(email, length(max = "validation::MAX_EMAIL_LENGTH"))]
     pub email: String,
+    #[validate(length(min = "validation::MIN_USERNAME_LENGTH", max = "validation::MAX_USERNAME_LENGTH"))]
     pub username: String,
+    #[validate(length(min = "validation::MIN_PASSWORD_LENGTH", max = "validation::MAX_PASSWORD_LENGTH"))]
     pub password: String,
+    #[validate(length(max = "validation::MAX_NAME_LENGTH"))]
     pub first_name: Option<String>,
+    #[validate(length(max = "validation::MAX_NAME_LENGTH"))]
     pub last_name: Option<String>,
 }
 
 /// Update user request
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema, Validate)]
 pub struct UpdateUserRequest {
+    #[validate(email, length(max = "validation::MAX_EMAIL_LENGTH"))]
     pub email: Option<String>,
+    #[validate(length(min = "validation::MIN_USERNAME_LENGTH", max = "validation::MAX_USERNAME_LENGTH"))]
     pub username: Option<String>,
+    #[validate(length(max = "validation::MAX_NAME_LENGTH"))]
     pub first_name: Option<String>,
+    #[validate(length(max = "validation::MAX_NAME_LENGTH"))]
     pub last_name: Option<String>,
     pub is_active: Option<bool>,
 }
 
 /// User profile request
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema, Validate)]
 pub struct UpdateUserProfileRequest {
+    #[validate(length(max = "validation::MAX_NAME_LENGTH"))]
     pub first_name: Option<String>,
+    #[validate(length(max = "validation::MAX_NAME_LENGTH"))]
     pub last_name: Option<String>,
 }
 
+/// User representation returned to API clients
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct UserResponse {
+    pub id: Uuid,
+    pub tenant_id: Uuid,
+    pub email: String,
+    pub username: String,
+    pub first_name: Option<String>,
+    pub last_name: Option<String>,
+    pub is_active: bool,
+    pub is_verified: bool,
+    pub last_login_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<database::User> for UserResponse {
+    fn from(user: database::User) -> Self {
+        Self {
+            id: user.id,
+            tenant_id: user.tenant_id,
+            email: user.email,
+            username: user.username,
+            first_name: user.first_name,
+            last_name: user.last_name,
+            is_active: user.is_active,
+            is_verified: user.is_verified,
+            last_login_at: user.last_login_at,
+            created_at: user.created_at,
+            updated_at: user.updated_at,
+        }
+    }
+}
+
 /// List users handler
+#[utoipa::path(
+    get,
+    path = "/api/v1/users",
+    tag = "users",
+    params(PaginationParams),
+    responses(
+        (status = 200, description = "Page of users", body = PaginatedResponse<UserResponse>),
+        (status = 400, description = "Invalid pagination parameters", body = ApiErrorResponse),
+        (status = 401, description = "Missing or invalid credentials", body = ApiErrorResponse)
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn list_users(
-    State(_state): State<AppState>,
-    Query(_params): Query<PaginationParams>,
-) -> Result<Json<Value>, StatusCode> {
-    // TODO: Implement user listing logic
-    Err(StatusCode::NOT_IMPLEMENTED)
+    State(state): State<AppState>,
+    auth: AuthContext,
+    ValidatedQuery(params): ValidatedQuery<PaginationParams>,
+) -> AppResult<Json<PaginatedResponse<UserResponse>>> {
+    let page = UserService::new(&state)
+        .list(&auth, &params)
+        .await?;
+
+    Ok(Json(PaginatedResponse {
+        data: page.data.into_iter().map(UserResponse::from).collect(),
+        pagination: page.pagination,
+    }))
 }
 
 /// Get user handler
+#[utoipa::path(
+    get,
+    path = "/api/v1/users/{id}",
+    tag = "users",
+    params(("id" = Uuid, Path, description = "User identifier")),
+    responses(
+        (status = 200, description = "User found", body = UserResponse),
+        (status = 404, description = "User not found", body = ApiErrorResponse)
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn get_user(
-    State(_state): State<AppState>,
-    Path(_user_id): Path<Uuid>,
-) -> Result<Json<Value>, StatusCode> {
-    // TODO: Implement get user logic
-    Err(StatusCode::NOT_IMPLEMENTED)
+    State(state): State<AppState>,
+    auth: AuthContext,
+    Path(user_id): Path<Uuid>,
+) -> AppResult<Json<UserResponse>> {
+    let user = UserService::new(&state)
+        .get(&auth, &user_id)
+        .await?;
+
+    Ok(Json(user.into()))
 }
 
 /// Create user handler
+#[utoipa::path(
+    post,
+    path = "/api/v1/users",
+    tag = "users",
+    request_body = CreateUserRequest,
+    responses(
+        (status = 200, description = "User created", body = UserResponse),
+        (status = 400, description = "Invalid user data", body = ApiErrorResponse),
+        (status = 409, description = "Email or username already taken", body = ApiErrorResponse)
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn create_user(
-    State(_state): State<AppState>,
-    Json(_payload): Json<CreateUserRequest>,
-) -> Result<Json<Value>, StatusCode> {
-    // TODO: Implement user creation logic
-    Err(StatusCode::NOT_IMPLEMENTED)
+    State(state): State<AppState>,
+    auth: AuthContext,
+    ValidatedJson(payload): ValidatedJson<CreateUserRequest>,
+) -> AppResult<Json<UserResponse>> {
+    let dto = CreateUserDto {
+        tenant_id: auth.tenant_id,
+        email: payload.email,
+        username: payload.username,
+        password: payload.password,
+        first_name: payload.first_name,
+        last_name: payload.last_name,
+    };
+
+    let user = UserService::new(&state)
+        .create(&auth, dto)
+        .await?;
+
+    Ok(Json(user.into()))
 }
 
 /// Update user handler
+#[utoipa::path(
+    put,
+    path = "/api/v1/users/{id}",
+    tag = "users",
+    params(("id" = Uuid, Path, description = "User identifier")),
+    request_body = UpdateUserRequest,
+    responses(
+        (status = 200, description = "User updated", body = UserResponse),
+        (status = 404, description = "User not found", body = ApiErrorResponse)
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn update_user(
-    State(_state): State<AppState>,
-    Path(_user_id): Path<Uuid>,
-    Json(_payload): Json<UpdateUserRequest>,
-) -> Result<Json<Value>, StatusCode> {
-    // TODO: Implement user update logic
-    Err(StatusCode::NOT_IMPLEMENTED)
+    State(state): State<AppState>,
+    auth: AuthContext,
+    Path(user_id): Path<Uuid>,
+    ValidatedJson(payload): ValidatedJson<UpdateUserRequest>,
+) -> AppResult<Json<UserResponse>> {
+    let dto = UpdateUserDto {
+        email: payload.email,
+        username: payload.username,
+        first_name: payload.first_name,
+        last_name: payload.last_name,
+        is_active: payload.is_active,
+        is_verified: None,
+    };
+
+    let user = UserService::new(&state)
+        .update(&auth, &user_id, dto)
+        .await?;
+
+    Ok(Json(user.into()))
 }
 
 /// Delete user handler
+#[utoipa::path(
+    delete,
+    path = "/api/v1/users/{id}",
+    tag = "users",
+    params(("id" = Uuid, Path, description = "User identifier")),
+    responses(
+        (status = 200, description = "User deleted", body = Object),
+        (status = 404, description = "User not found", body = ApiErrorResponse)
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn delete_user(
-    State(_state): State<AppState>,
-    Path(_user_id): Path<Uuid>,
-) -> Result<Json<Value>, StatusCode> {
-    // TODO: Implement user deletion logic
-    Err(StatusCode::NOT_IMPLEMENTED)
+    State(state): State<AppState>,
+    auth: AuthContext,
+    Path(user_id): Path<Uuid>,
+) -> AppResult<Json<Value>> {
+    let deleted = UserService::new(&state)
+        .delete(&auth, &user_id)
+        .await?;
+
+    Ok(Json(json!({ "id": user_id, "deleted": deleted })))
 }
 
 /// Get user profile handler
+#[utoipa::path(
+    get,
+    path = "/api/v1/users/{id}/profile",
+    tag = "users",
+    params(("id" = Uuid, Path, description = "User identifier")),
+    responses(
+        (status = 200, description = "User profile", body = UserResponse),
+        (status = 404, description = "User not found", body = ApiErrorResponse)
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn get_user_profile(
-    State(_state): State<AppState>,
-    Path(_user_id): Path<Uuid>,
-) -> Result<Json<Value>, StatusCode> {
-    // TODO: Implement get user profile logic
-    Err(StatusCode::NOT_IMPLEMENTED)
+    State(state): State<AppState>,
+    auth: AuthContext,
+    Path(user_id): Path<Uuid>,
+) -> AppResult<Json<UserResponse>> {
+    let user = UserService::new(&state)
+        .get(&auth, &user_id)
+        .await?;
+
+    Ok(Json(user.into()))
 }
 
 /// Update user profile handler
+#[utoipa::path(
+    put,
+    path = "/api/v1/users/{id}/profile",
+    tag = "users",
+    params(("id" = Uuid, Path, description = "User identifier")),
+    request_body = UpdateUserProfileRequest,
+    responses(
+        (status = 200, description = "User profile updated", body = UserResponse),
+        (status = 404, description = "User not found", body = ApiErrorResponse)
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn update_user_profile(
-    State(_state): State<AppState>,
-    Path(_user_id): Path<Uuid>,
-    Json(_payload): Json<UpdateUserProfileRequest>,
-) -> Result<Json<Value>, StatusCode> {
-    // TODO: Implement user profile update logic
-    Err(StatusCode::NOT_IMPLEMENTED)
-}
\ No newline at end of file
+    State(state): State<AppState>,
+    auth: AuthContext,
+    Path(user_id): Path<Uuid>,
+    ValidatedJson(payload): ValidatedJson<UpdateUserProfileRequest>,
+) -> AppResult<Json<UserResponse>> {
+    let dto = UpdateUserDto {
+        email: None,
+        username: None,
+        first_name: payload.first_name,
+        last_name: payload.last_name,
+        is_active: None,
+        is_verified: None,
+    };
+
+    let user = UserService::new(&state)
+        .update(&auth, &user_id, dto)
+        .await?;
+
+    Ok(Json(user.into()))
+}
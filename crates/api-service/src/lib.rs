@@ -1,16 +1,23 @@
 //! API Service library
 
 pub mod config;
+pub mod domain_events;
+pub mod extractors;
+pub mod graphql;
 pub mod handlers;
 pub mod middleware;
+pub mod openapi;
+pub mod realtime;
 pub mod routes;
 pub mod services;
 pub mod state;
 
 // Re-export commonly used items
 pub use config::*;
+pub use extractors::*;
 pub use handlers::*;
 pub use middleware::*;
+pub use openapi::*;
 pub use routes::*;
 pub use services::*;
 pub use state::*;
\ No newline at end of file
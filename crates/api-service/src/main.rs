@@ -1,14 +1,29 @@
 //! API Service - REST API microservice with Axum
 
 use anyhow::Result;
-use clap::Parser;
-use shared::{AppConfig, ValidateConfig};
-use std::net::SocketAddr;
+use axum::{extract::State, routing::get, Router};
+use clap::{Parser, Subcommand};
+use figment::Figment;
+use metrics_exporter_prometheus::{Matcher, PrometheusBuilder, PrometheusHandle};
+use shared::{
+    configure_error_responses,
+    metrics::{with_namespace, HTTP_REQUEST_DURATION, HTTP_REQUEST_SIZE, HTTP_RESPONSE_SIZE},
+    describe_config, logging,
+    runtime::{shutdown_signal, CancellationToken, RestartPolicy, Shutdown, ShutdownPhase, Supervisor},
+    watch_config, AppError, AppResult, CliOverrides, ConfigHandle, ErrorResponseSettings, MetricsConfig,
+    ValidateConfig,
+};
+use std::{net::SocketAddr, time::Duration};
 use tracing::{info, warn};
 
 mod config;
+mod domain_events;
+mod extractors;
+mod graphql;
 mod handlers;
 mod middleware;
+mod openapi;
+mod realtime;
 mod routes;
 mod services;
 mod state;
@@ -17,7 +32,7 @@ use config::ApiConfig;
 use state::AppState;
 
 /// API Service CLI arguments
-#[derive(Parser, Debug)]
+#[derive(Parser, Debug, Clone)]
 #[command(name = "api-service")]
 #[command(about = "REST API microservice")]
 struct Args {
@@ -36,6 +51,24 @@ struct Args {
     /// Environment
     #[arg(short, long, env = "ENVIRONMENT")]
     environment: Option<String>,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug, Clone)]
+enum Command {
+    /// Inspect the service configuration
+    #[command(subcommand)]
+    Config(ConfigCommand),
+}
+
+#[derive(Subcommand, Debug, Clone)]
+enum ConfigCommand {
+    /// Load and validate the configuration as startup does, then exit
+    Check,
+    /// Print the effective configuration and where each value came from, with secrets masked
+    Print,
 }
 
 #[tokio::main]
@@ -46,36 +79,53 @@ async fn main() -> Result<()> {
     // Parse CLI arguments
     let args = Args::parse();
 
-    // Initialize configuration
-    let mut config = if args.config == "config" {
-        AppConfig::load()?
-    } else {
-        AppConfig::load_from_path(&args.config)?
-    };
-
-    // Override config with CLI arguments
-    if let Some(host) = args.host {
-        config.server.host = host;
-    }
-    if let Some(port) = args.port {
-        config.server.port = port;
-    }
-    if let Some(environment) = args.environment {
-        config.environment = environment;
+    if let Some(Command::Config(command)) = &args.command {
+        return run_config_command(&args, command);
     }
 
+    // Initialize configuration
+    let api_config = load_config(&args).map_err(anyhow::Error::msg)?;
+
     // Validate configuration
-    config.validate()?;
+    api_config.validate().map_err(anyhow::Error::msg)?;
+    let config = api_config.app.clone();
 
     // Initialize logging
-    init_logging(&config)?;
+    let logging = logging::init(&config)?;
+
+    // Reload configuration on file changes and SIGHUP
+    let config_handle = ConfigHandle::new(api_config);
+    let reload_args = args.clone();
+    watch_config(config_handle.clone(), &args.config, move || load_config(&reload_args))?;
+    logging.watch_level(&config_handle, |config| config.app.logging.level.clone());
 
     info!("Starting API service");
     info!("Environment: {}", config.environment);
     info!("Version: {}", config.version);
 
-    // Initialize application state
-    let app_state = AppState::new(config.clone()).await?;
+    // Error details are only exposed to clients outside production
+    configure_error_responses(ErrorResponseSettings {
+        service: config.service_name.clone(),
+        version: config.version.clone(),
+        expose_internal_errors: !config.is_production(),
+    });
+
+    // Initialize application state; streaming responses end once `stop_accepting` is cancelled
+    let stop_accepting = CancellationToken::new();
+    let app_state = AppState::new(config_handle, stop_accepting.clone()).await?;
+
+    let mut supervisor = Supervisor::new();
+    let restart = RestartPolicy::OnFailure {
+        max_restarts: 5,
+        backoff: Duration::from_secs(1),
+    };
+
+    // Forward notifications published by other instances to this instance's WebSockets
+    let hub = app_state.notifications().clone();
+    supervisor.spawn("notification bridge", restart, move |token| {
+        let hub = hub.clone();
+        async move { hub.run_bridge(token).await }
+    });
 
     // Run database migrations if enabled
     if config.database.migrate_on_start {
@@ -84,7 +134,7 @@ async fn main() -> Result<()> {
     }
 
     // Build application routes
-    let app = routes::create_routes(app_state.clone());
+    let app = routes::create_routes(app_state.clone())?;
 
     // Create server address
     let addr: SocketAddr = config.server_address().parse()?;
@@ -93,114 +143,146 @@ async fn main() -> Result<()> {
     // Start metrics server if enabled
     if config.metrics.enabled {
         let metrics_addr: SocketAddr = config.metrics_address().parse()?;
-        tokio::spawn(start_metrics_server(metrics_addr));
-        info!("Metrics server listening on {}", metrics_addr);
+        let handle = install_metrics_recorder(&config.metrics)?;
+        let path = config.metrics.path.clone();
+        supervisor.spawn("metrics server", restart, move |token| {
+            start_metrics_server(metrics_addr, path.clone(), handle.clone(), token)
+        });
+        info!("Metrics server listening on {}{}", metrics_addr, config.metrics.path);
     }
 
-    // Start the server
+    // Start the server; it stops accepting connections once `stop_accepting` is cancelled
     let listener = tokio::net::TcpListener::bind(addr).await?;
-    axum::serve(listener, app)
-        .with_graceful_shutdown(shutdown_signal())
-        .await?;
+    let stopped = stop_accepting.clone();
+    let mut server = tokio::spawn(async move {
+        axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>())
+            .with_graceful_shutdown(async move { stopped.cancelled().await })
+            .await
+    });
 
+    tokio::select! {
+        // The server only returns early when it fails
+        result = &mut server => result??,
+        _ = shutdown_signal() => {}
+    }
+
+    // Report not-ready for `drain_period` so load balancers stop routing traffic here before
+    // the server stops accepting connections and closes event streams and WebSockets, then
+    // wait for in-flight requests
+    let health = app_state.health().clone();
+    let drain_period = Duration::from_secs(config.health.drain_period);
+    let deadline = config.shutdown_timeout();
+    let audit = app_state.audit().clone();
+    let domain_events = app_state.domain_events().clone();
+    let database = app_state.database().clone();
+    Shutdown::new(deadline)
+        .step(ShutdownPhase::StopIntake, "http listener", async move {
+            health.start_draining();
+            info!("Draining for {} seconds", drain_period.as_secs());
+            tokio::time::sleep(drain_period).await;
+            stop_accepting.cancel();
+        })
+        .step(ShutdownPhase::Drain, "http requests", async move {
+            match server.await {
+                Ok(Ok(())) => {}
+                Ok(Err(e)) => warn!("HTTP server failed while stopping: {}", e),
+                Err(e) => warn!("HTTP server task ended abnormally: {}", e),
+            }
+        })
+        .step(ShutdownPhase::Drain, "background tasks", supervisor.shutdown())
+        .step(ShutdownPhase::FlushProducers, "audit log", async move { audit.flush().await })
+        .step(ShutdownPhase::FlushProducers, "domain events", async move { domain_events.flush(deadline).await })
+        .step(ShutdownPhase::ClosePools, "database", async move { database.close().await })
+        .run()
+        .await;
+
+    logging.shutdown();
     info!("API service stopped");
     Ok(())
 }
 
-/// Initialize logging and tracing
-fn init_logging(config: &AppConfig) -> Result<()> {
-    use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
+/// Configuration sources, with CLI overrides taking precedence
+fn config_figment(args: &Args) -> Figment {
+    let mut overrides = CliOverrides::default();
+    if let Some(host) = &args.host {
+        overrides.set("server.host", host);
+    }
+    if let Some(port) = args.port {
+        overrides.set("server.port", port);
+    }
+    if let Some(environment) = &args.environment {
+        overrides.set("environment", environment);
+    }
+
+    ApiConfig::figment(&args.config).merge(overrides)
+}
 
-    let env_filter = EnvFilter::try_from_default_env()
-        .unwrap_or_else(|_| EnvFilter::new(&config.logging.level));
+/// Load configuration from files, the environment and CLI flags
+fn load_config(args: &Args) -> Result<ApiConfig, String> {
+    config_figment(args).extract().map_err(|e| e.to_string())
+}
 
-    let subscriber = tracing_subscriber::registry().with(env_filter);
+/// Run a `config` subcommand
+fn run_config_command(args: &Args, command: &ConfigCommand) -> Result<()> {
+    let figment = config_figment(args);
+    let config: ApiConfig = figment.extract()?;
 
-    match config.logging.format.as_str() {
-        "json" => {
-            let json_layer = tracing_subscriber::fmt::layer()
-                .json()
-                .with_current_span(false)
-                .with_span_list(true);
-            subscriber.with(json_layer).init();
-        }
-        _ => {
-            let fmt_layer = tracing_subscriber::fmt::layer()
-                .with_target(true)
-                .with_thread_ids(true)
-                .with_file(true)
-                .with_line_number(true);
-            subscriber.with(fmt_layer).init();
+    match command {
+        ConfigCommand::Check => {
+            config.validate().map_err(anyhow::Error::msg)?;
+            println!("Configuration is valid");
         }
+        ConfigCommand::Print => print!("{}", describe_config(&config, &figment)?),
     }
 
     Ok(())
 }
 
-/// Start metrics server
-async fn start_metrics_server(addr: SocketAddr) -> Result<()> {
-    use axum::{extract::State, http::StatusCode, response::IntoResponse, routing::get, Router};
-    use metrics_exporter_prometheus::{Matcher, PrometheusBuilder, PrometheusHandle};
+/// Install the Prometheus recorder with the configured histogram buckets
+fn install_metrics_recorder(config: &MetricsConfig) -> Result<PrometheusHandle> {
+    let histograms = [
+        (HTTP_REQUEST_DURATION, &config.duration_buckets),
+        (HTTP_REQUEST_SIZE, &config.size_buckets),
+        (HTTP_RESPONSE_SIZE, &config.size_buckets),
+    ];
+
+    let mut builder = PrometheusBuilder::new();
+    for (name, buckets) in histograms {
+        builder = builder.set_buckets_for_metric(Matcher::Full(with_namespace(&config.namespace, name)), buckets)?;
+    }
 
-    let builder = PrometheusBuilder::new();
-    let handle = builder
-        .install_recorder()
-        .expect("Failed to install Prometheus recorder");
+    Ok(builder.install_recorder()?)
+}
 
+/// Serve metrics until cancelled
+async fn start_metrics_server(
+    addr: SocketAddr,
+    path: String,
+    handle: PrometheusHandle,
+    token: CancellationToken,
+) -> AppResult<()> {
     let app = Router::new()
-        .route("/metrics", get(metrics_handler))
+        .route(&path, get(metrics_handler))
         .with_state(handle);
 
-    let listener = tokio::net::TcpListener::bind(addr).await?;
-    axum::serve(listener, app).await?;
-
-    Ok(())
+    let listener = tokio::net::TcpListener::bind(addr)
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to bind metrics server to {}: {}", addr, e)))?;
+    axum::serve(listener, app)
+        .with_graceful_shutdown(async move { token.cancelled().await })
+        .await
+        .map_err(|e| AppError::Internal(format!("Metrics server failed: {}", e)))
 }
 
 /// Metrics endpoint handler
-async fn metrics_handler(State(handle): State<metrics_exporter_prometheus::PrometheusHandle>) -> impl IntoResponse {
-    match handle.render() {
-        Ok(metrics) => (StatusCode::OK, metrics),
-        Err(_) => (StatusCode::INTERNAL_SERVER_ERROR, String::new()),
-    }
-}
-
-/// Graceful shutdown signal
-async fn shutdown_signal() {
-    use tokio::signal;
-
-    let ctrl_c = async {
-        signal::ctrl_c()
-            .await
-            .expect("Failed to install Ctrl+C handler");
-    };
-
-    #[cfg(unix)]
-    let terminate = async {
-        signal::unix::signal(signal::unix::SignalKind::terminate())
-            .expect("Failed to install signal handler")
-            .recv()
-            .await;
-    };
-
-    #[cfg(not(unix))]
-    let terminate = std::future::pending::<()>();
-
-    tokio::select! {
-        _ = ctrl_c => {
-            info!("Received Ctrl+C signal");
-        },
-        _ = terminate => {
-            info!("Received terminate signal");
-        },
-    }
-
-    info!("Starting graceful shutdown");
+async fn metrics_handler(State(handle): State<PrometheusHandle>) -> String {
+    handle.render()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use shared::AppConfig;
 
     #[tokio::test]
     async fn test_config_loading() {
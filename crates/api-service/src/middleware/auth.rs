@@ -1,14 +1,129 @@
 //! Authentication middleware
 
 use axum::{
-    extract::{Request, State},
-    http::StatusCode,
+    async_trait,
+    extract::{FromRequestParts, Request, State},
+    http::{
+        header::{AUTHORIZATION, USER_AGENT},
+        request::Parts,
+        HeaderMap,
+    },
     middleware::Next,
-    response::Response,
+    response::{IntoResponse, Response},
+};
+use jsonwebtoken::{decode, DecodingKey, Validation};
+use serde::{Deserialize, Serialize};
+use shared::{
+    context::{self, Actor},
+    roles, AppError, AppResult, TenantId, UserId, JWT_PREFIX,
 };
 use tower::{Layer, Service};
 
-use crate::state::AppState;
+use crate::{middleware::rate_limit::request_client_ip, state::AppState};
+
+/// JWT claims issued by the auth endpoints
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Claims {
+    /// Subject (user ID)
+    pub sub: UserId,
+    /// Tenant the user belongs to
+    pub tenant_id: TenantId,
+    /// Roles granted to the user
+    #[serde(default)]
+    pub roles: Vec<String>,
+    /// Expiration time (seconds since epoch)
+    pub exp: usize,
+    /// Issued at (seconds since epoch)
+    pub iat: usize,
+}
+
+/// Authenticated caller, inserted into request extensions by [`AuthMiddleware`]
+#[derive(Debug, Clone)]
+pub struct AuthContext {
+    pub user_id: UserId,
+    pub tenant_id: TenantId,
+    pub roles: Vec<String>,
+}
+
+impl AuthContext {
+    /// Validate a bearer token and build the caller context from its claims
+    pub fn from_token(token: &str, secret: &str) -> AppResult<Self> {
+        let data = decode::<Claims>(
+            token,
+            &DecodingKey::from_secret(secret.as_bytes()),
+            &Validation::default(),
+        )
+        .map_err(|e| AppError::Authentication(format!("Invalid token: {}", e)))?;
+
+        Ok(data.claims.into())
+    }
+
+    /// Check whether the caller has the given role
+    pub fn has_role(&self, role: &str) -> bool {
+        self.roles.iter().any(|r| r == role)
+    }
+
+    /// Check whether the caller is a tenant administrator
+    pub fn is_admin(&self) -> bool {
+        self.has_role(roles::ADMIN)
+    }
+
+    /// Ensure the caller was granted a permission (see [`shared::permissions`])
+    pub fn ensure_permission(&self, permission: &str) -> AppResult<()> {
+        if self.has_role(permission) {
+            Ok(())
+        } else {
+            Err(AppError::Authorization(format!("Missing permission: {}", permission)))
+        }
+    }
+
+    /// Ensure a resource belongs to the caller's tenant.
+    ///
+    /// Resources of other tenants are reported as not found so their existence is not leaked.
+    pub fn ensure_tenant(&self, tenant_id: &TenantId, resource: &str) -> AppResult<()> {
+        if &self.tenant_id == tenant_id {
+            Ok(())
+        } else {
+            Err(AppError::NotFound(format!("{} not found", resource)))
+        }
+    }
+}
+
+impl From<Claims> for AuthContext {
+    fn from(claims: Claims) -> Self {
+        Self {
+            user_id: claims.sub,
+            tenant_id: claims.tenant_id,
+            roles: claims.roles,
+        }
+    }
+}
+
+#[async_trait]
+impl<S> FromRequestParts<S> for AuthContext
+where
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        parts
+            .extensions
+            .get::<AuthContext>()
+            .cloned()
+            .ok_or_else(|| AppError::Authentication("Authentication required".to_string()))
+    }
+}
+
+/// Extract the bearer token from the `Authorization` header
+pub fn bearer_token(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix(JWT_PREFIX))
+        .map(str::trim)
+        .filter(|token| !token.is_empty())
+}
 
 /// Authentication middleware
 #[derive(Clone)]
@@ -52,27 +167,116 @@ where
         self.inner.poll_ready(cx)
     }
 
-    fn call(&mut self, request: Request) -> Self::Future {
+    fn call(&mut self, mut request: Request) -> Self::Future {
         let mut inner = self.inner.clone();
         let state = self.state.clone();
 
         Box::pin(async move {
-            // TODO: Implement JWT token validation
-            // For now, just pass through
-            inner.call(request).await
+            let context = bearer_token(request.headers())
+                .ok_or_else(|| AppError::Authentication("Missing bearer token".to_string()))
+                .and_then(|token| AuthContext::from_token(token, state.config().app.security.jwt_secret.expose()));
+
+            match context {
+                Ok(context) => {
+                    let actor = actor(&request, &context, &state);
+                    request.extensions_mut().insert(context);
+                    context::with_actor(actor, inner.call(request)).await
+                }
+                Err(e) => Ok(e.into_response()),
+            }
         })
     }
 }
 
 /// Authentication handler function (alternative approach)
 pub async fn auth_middleware(
-    State(_state): State<AppState>,
-    request: Request,
+    State(state): State<AppState>,
+    mut request: Request,
     next: Next,
-) -> Result<Response, StatusCode> {
-    // TODO: Implement authentication logic
-    // Extract and validate JWT token from Authorization header
-    // Add user context to request extensions
-    
-    Ok(next.run(request).await)
-}
\ No newline at end of file
+) -> AppResult<Response> {
+    let token = bearer_token(request.headers())
+        .ok_or_else(|| AppError::Authentication("Missing bearer token".to_string()))?;
+    let context = AuthContext::from_token(token, state.config().app.security.jwt_secret.expose())?;
+
+    let actor = actor(&request, &context, &state);
+    request.extensions_mut().insert(context);
+    Ok(context::with_actor(actor, next.run(request)).await)
+}
+
+/// Caller details recorded with the audit entries of changes made by the request
+fn actor(request: &Request, context: &AuthContext, state: &AppState) -> Actor {
+    Actor {
+        user_id: Some(context.user_id),
+        tenant_id: Some(context.tenant_id),
+        ip_address: request_client_ip(request, &state.config().api.rate_limit.trusted_proxies),
+        user_agent: request
+            .headers()
+            .get(USER_AGENT)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jsonwebtoken::{encode, EncodingKey, Header};
+
+    fn token(claims: &Claims, secret: &str) -> String {
+        encode(&Header::default(), claims, &EncodingKey::from_secret(secret.as_bytes())).unwrap()
+    }
+
+    fn claims() -> Claims {
+        let now = chrono::Utc::now().timestamp() as usize;
+        Claims {
+            sub: uuid::Uuid::new_v4(),
+            tenant_id: uuid::Uuid::new_v4(),
+            roles: vec![roles::ADMIN.to_string()],
+            exp: now + 60,
+            iat: now,
+        }
+    }
+
+    #[test]
+    fn test_auth_context_from_token() {
+        let claims = claims();
+        let context = AuthContext::from_token(&token(&claims, "secret"), "secret").unwrap();
+
+        assert_eq!(context.user_id, claims.sub);
+        assert_eq!(context.tenant_id, claims.tenant_id);
+        assert!(context.is_admin());
+        assert!(AuthContext::from_token(&token(&claims, "secret"), "other").is_err());
+    }
+
+    #[test]
+    fn test_ensure_tenant() {
+        let context: AuthContext = claims().into();
+
+        assert!(context.ensure_tenant(&context.tenant_id.clone(), "User").is_ok());
+        assert!(matches!(
+            context.ensure_tenant(&uuid::Uuid::new_v4(), "User"),
+            Err(AppError::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_ensure_permission() {
+        let mut context: AuthContext = claims().into();
+        assert!(matches!(
+            context.ensure_permission(shared::permissions::AUDIT_READ),
+            Err(AppError::Authorization(_))
+        ));
+
+        context.roles.push(shared::permissions::AUDIT_READ.to_string());
+        assert!(context.ensure_permission(shared::permissions::AUDIT_READ).is_ok());
+    }
+
+    #[test]
+    fn test_bearer_token() {
+        let mut headers = HeaderMap::new();
+        assert!(bearer_token(&headers).is_none());
+
+        headers.insert(AUTHORIZATION, "Bearer abc".parse().unwrap());
+        assert_eq!(bearer_token(&headers), Some("abc"));
+    }
+}
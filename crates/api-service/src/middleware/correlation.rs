@@ -0,0 +1,147 @@
+//! Correlation ID middleware
+
+use axum::{
+    extract::Request,
+    http::{HeaderMap, HeaderValue},
+    response::Response,
+};
+use shared::{
+    context, generate_correlation_id,
+    telemetry::{self, TraceContext, TRACE_CONTEXT_HEADERS},
+    CorrelationId, CORRELATION_ID_HEADER, REQUEST_ID_HEADER,
+};
+use tower::{Layer, Service};
+use tracing::Instrument;
+use uuid::Uuid;
+
+/// Identifiers of the request being handled, available from request extensions
+#[derive(Debug, Clone, Copy)]
+pub struct RequestIds {
+    /// Follows the request through every service it touches
+    pub correlation_id: CorrelationId,
+    /// Unique to this hop
+    pub request_id: Uuid,
+}
+
+/// Accepts or generates the request's correlation id.
+///
+/// The id is taken from the `X-Correlation-ID` header, or generated when missing or malformed.
+/// It is recorded on the request span, put in scope for handlers (see [`shared::context`])
+/// and echoed back together with a per-request `X-Request-ID`. A W3C `traceparent` sent by the
+/// client makes the request span part of the caller's trace.
+#[derive(Clone, Default)]
+pub struct CorrelationIdMiddleware;
+
+impl CorrelationIdMiddleware {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl<S> Layer<S> for CorrelationIdMiddleware {
+    type Service = CorrelationIdMiddlewareService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        CorrelationIdMiddlewareService { inner }
+    }
+}
+
+#[derive(Clone)]
+pub struct CorrelationIdMiddlewareService<S> {
+    inner: S,
+}
+
+impl<S> Service<Request> for CorrelationIdMiddlewareService<S>
+where
+    S: Service<Request, Response = Response> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = std::pin::Pin<Box<dyn std::future::Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut std::task::Context<'_>) -> std::task::Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut request: Request) -> Self::Future {
+        let mut inner = self.inner.clone();
+        let ids = RequestIds {
+            correlation_id: correlation_id_from(request.headers()),
+            request_id: Uuid::new_v4(),
+        };
+        request.extensions_mut().insert(ids);
+
+        let span = tracing::info_span!(
+            "request",
+            correlation_id = %ids.correlation_id,
+            request_id = %ids.request_id,
+        );
+        telemetry::set_remote_parent(&span, &trace_context_from(request.headers()));
+
+        let future = async move {
+            let mut response = inner.call(request).await?;
+            let headers = response.headers_mut();
+            headers.insert(CORRELATION_ID_HEADER, header_value(&ids.correlation_id));
+            headers.insert(REQUEST_ID_HEADER, header_value(&ids.request_id));
+            Ok(response)
+        };
+
+        Box::pin(context::with_correlation_id(
+            ids.correlation_id,
+            context::with_request_id(ids.request_id, future.instrument(span)),
+        ))
+    }
+}
+
+/// Read the correlation id sent by the client, generating one if absent or malformed
+pub fn correlation_id_from(headers: &HeaderMap) -> CorrelationId {
+    headers
+        .get(CORRELATION_ID_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(context::parse_correlation_id)
+        .unwrap_or_else(generate_correlation_id)
+}
+
+/// W3C trace context headers sent by the client
+pub fn trace_context_from(headers: &HeaderMap) -> TraceContext {
+    TRACE_CONTEXT_HEADERS
+        .iter()
+        .filter_map(|name| {
+            let value = headers.get(*name)?.to_str().ok()?;
+            Some((name.to_string(), value.to_string()))
+        })
+        .collect()
+}
+
+fn header_value(id: &Uuid) -> HeaderValue {
+    HeaderValue::from_str(&id.to_string()).expect("UUIDs are valid header values")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_correlation_id_from_headers() {
+        let id = Uuid::new_v4();
+        let mut headers = HeaderMap::new();
+        headers.insert(CORRELATION_ID_HEADER, id.to_string().parse().unwrap());
+        assert_eq!(correlation_id_from(&headers), id);
+
+        headers.insert(CORRELATION_ID_HEADER, "not-a-uuid".parse().unwrap());
+        assert_ne!(correlation_id_from(&headers), id);
+        assert!(!correlation_id_from(&HeaderMap::new()).is_nil());
+    }
+
+    #[test]
+    fn test_trace_context_from_headers() {
+        let traceparent = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+        let mut headers = HeaderMap::new();
+        headers.insert("traceparent", traceparent.parse().unwrap());
+
+        let context = trace_context_from(&headers);
+        assert_eq!(context.len(), 1);
+        assert_eq!(context["traceparent"], traceparent);
+    }
+}
@@ -0,0 +1,152 @@
+//! CORS configuration
+
+use arc_swap::ArcSwapOption;
+use axum::{
+    extract::Request,
+    http::{HeaderName, HeaderValue, Method},
+    response::Response,
+};
+use shared::{AppError, AppResult, ConfigHandle, SecurityConfig};
+use std::sync::Arc;
+use tower::{Layer, Service};
+use tower_http::cors::{AllowHeaders, AllowMethods, AllowOrigin, CorsLayer};
+
+use crate::config::ApiConfig;
+
+/// CORS handling that follows configuration reloads.
+///
+/// The CORS layer is rebuilt whenever `api.enable_cors` or the security settings change, and
+/// applied to each request as it arrives. Requests pass through untouched while CORS is disabled.
+#[derive(Clone)]
+pub struct ReloadableCorsLayer {
+    current: Arc<ArcSwapOption<CorsLayer>>,
+}
+
+impl ReloadableCorsLayer {
+    pub fn new(config: &ConfigHandle<ApiConfig>) -> AppResult<Self> {
+        let snapshot = config.load();
+        let current = Arc::new(ArcSwapOption::new(
+            build(snapshot.api.enable_cors, &snapshot.app.security)?.map(Arc::new),
+        ));
+
+        let updates = current.clone();
+        config.on_change(
+            |config| (config.api.enable_cors, config.app.security.clone()),
+            move |(enabled, security)| match build(*enabled, security) {
+                Ok(layer) => updates.store(layer.map(Arc::new)),
+                Err(e) => tracing::warn!("Keeping previous CORS settings: {}", e),
+            },
+        );
+
+        Ok(Self { current })
+    }
+}
+
+fn build(enabled: bool, security: &SecurityConfig) -> AppResult<Option<CorsLayer>> {
+    enabled.then(|| cors_layer(security)).transpose()
+}
+
+impl<S> Layer<S> for ReloadableCorsLayer {
+    type Service = ReloadableCorsService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        ReloadableCorsService {
+            inner,
+            current: self.current.clone(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct ReloadableCorsService<S> {
+    inner: S,
+    current: Arc<ArcSwapOption<CorsLayer>>,
+}
+
+impl<S> Service<Request> for ReloadableCorsService<S>
+where
+    S: Service<Request, Response = Response> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = std::pin::Pin<Box<dyn std::future::Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut std::task::Context<'_>) -> std::task::Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request) -> Self::Future {
+        let mut inner = self.inner.clone();
+
+        match self.current.load_full() {
+            Some(cors) => Box::pin(cors.layer(inner).call(request)),
+            None => Box::pin(inner.call(request)),
+        }
+    }
+}
+
+/// Build a CORS layer from `security.cors_*`; a `*` entry allows any value
+pub fn cors_layer(security: &SecurityConfig) -> AppResult<CorsLayer> {
+    let origins = if is_wildcard(&security.cors_origins) {
+        AllowOrigin::any()
+    } else {
+        AllowOrigin::list(parse_all(&security.cors_origins, "origin", |value| {
+            HeaderValue::from_str(value).ok()
+        })?)
+    };
+
+    let methods = if is_wildcard(&security.cors_methods) {
+        AllowMethods::any()
+    } else {
+        AllowMethods::list(parse_all(&security.cors_methods, "method", |value| {
+            Method::from_bytes(value.to_uppercase().as_bytes()).ok()
+        })?)
+    };
+
+    let headers = if is_wildcard(&security.cors_headers) {
+        AllowHeaders::any()
+    } else {
+        AllowHeaders::list(parse_all(&security.cors_headers, "header", |value| {
+            HeaderName::from_bytes(value.as_bytes()).ok()
+        })?)
+    };
+
+    Ok(CorsLayer::new()
+        .allow_origin(origins)
+        .allow_methods(methods)
+        .allow_headers(headers))
+}
+
+fn is_wildcard(values: &[String]) -> bool {
+    values.iter().any(|value| value == "*")
+}
+
+fn parse_all<T>(values: &[String], kind: &str, parse: impl Fn(&str) -> Option<T>) -> AppResult<Vec<T>> {
+    values
+        .iter()
+        .map(|value| {
+            parse(value.trim())
+                .ok_or_else(|| AppError::Configuration(format!("Invalid CORS {}: {}", kind, value)))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cors_layer() {
+        let mut security = SecurityConfig::default();
+        assert!(cors_layer(&security).is_ok());
+
+        security.cors_origins = vec!["https://app.example.com".to_string()];
+        security.cors_methods = vec!["get".to_string(), "POST".to_string()];
+        security.cors_headers = vec!["content-type".to_string(), "x-api-key".to_string()];
+        assert!(cors_layer(&security).is_ok());
+
+        security.cors_headers = vec!["not a header".to_string()];
+        assert!(matches!(cors_layer(&security), Err(AppError::Configuration(_))));
+    }
+}
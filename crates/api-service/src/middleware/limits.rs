@@ -0,0 +1,185 @@
+//! Request body size and timeout limits
+
+use axum::{
+    extract::{DefaultBodyLimit, Request},
+    http::header::CONTENT_LENGTH,
+    response::{IntoResponse, Response},
+};
+use shared::{file_limits::MULTIPART_OVERHEAD, AppError, API_BASE_PATH};
+use std::{sync::Arc, time::Duration};
+use tower::{Layer, Service};
+
+use super::versioning::matches_path_prefix;
+use crate::config::{ApiConfig, ApiSettings, RouteOverride};
+
+/// Limits applied to a single request
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RouteLimits {
+    pub max_request_size: usize,
+    pub timeout: Duration,
+}
+
+/// Default limits plus per-route overrides, resolved by path prefix
+#[derive(Debug, Clone)]
+pub struct RequestLimits {
+    defaults: RouteLimits,
+    overrides: Vec<RouteOverride>,
+}
+
+impl RequestLimits {
+    pub fn from_settings(settings: &ApiSettings) -> Self {
+        Self {
+            defaults: RouteLimits {
+                max_request_size: settings.max_request_size,
+                timeout: Duration::from_secs(settings.request_timeout),
+            },
+            overrides: settings.route_overrides.clone(),
+        }
+    }
+
+    /// Limits from the API settings, plus a body limit for file uploads in every API version
+    /// sized to `storage.max_upload_size`, unless a configured override covers them
+    pub fn from_config(config: &ApiConfig) -> Self {
+        let mut limits = Self::from_settings(&config.api);
+        limits.overrides.push(RouteOverride {
+            path_prefix: format!("{}/files", API_BASE_PATH),
+            max_request_size: Some(config.app.storage.max_upload_size + MULTIPART_OVERHEAD),
+            request_timeout: None,
+        });
+        limits
+    }
+
+    /// Limits for a path; the first override whose prefix matches replaces the defaults it sets
+    pub fn resolve(&self, path: &str) -> RouteLimits {
+        match self.overrides.iter().find(|route| matches_path_prefix(path, &route.path_prefix)) {
+            Some(route) => RouteLimits {
+                max_request_size: route.max_request_size.unwrap_or(self.defaults.max_request_size),
+                timeout: route
+                    .request_timeout
+                    .map(Duration::from_secs)
+                    .unwrap_or(self.defaults.timeout),
+            },
+            None => self.defaults,
+        }
+    }
+}
+
+/// Enforces the body size limit and timeout configured for each route.
+///
+/// Requests declaring a larger `Content-Length` are rejected up front; streamed bodies are
+/// capped through axum's body limit, so extractors fail with `413 Payload Too Large`.
+#[derive(Clone)]
+pub struct RequestLimitsLayer {
+    limits: Arc<RequestLimits>,
+}
+
+impl RequestLimitsLayer {
+    pub fn new(config: &ApiConfig) -> Self {
+        Self {
+            limits: Arc::new(RequestLimits::from_config(config)),
+        }
+    }
+}
+
+impl<S> Layer<S> for RequestLimitsLayer {
+    type Service = RequestLimitsService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RequestLimitsService {
+            inner,
+            limits: self.limits.clone(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct RequestLimitsService<S> {
+    inner: S,
+    limits: Arc<RequestLimits>,
+}
+
+impl<S> Service<Request> for RequestLimitsService<S>
+where
+    S: Service<Request, Response = Response> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = std::pin::Pin<Box<dyn std::future::Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut std::task::Context<'_>) -> std::task::Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request) -> Self::Future {
+        let limits = self.limits.resolve(request.uri().path());
+        let mut inner = DefaultBodyLimit::max(limits.max_request_size).layer(self.inner.clone());
+
+        Box::pin(async move {
+            if content_length(&request).is_some_and(|length| length > limits.max_request_size as u64) {
+                return Ok(AppError::PayloadTooLarge(format!(
+                    "Request body exceeds {} bytes",
+                    limits.max_request_size
+                ))
+                .into_response());
+            }
+
+            match tokio::time::timeout(limits.timeout, inner.call(request)).await {
+                Ok(response) => response,
+                Err(_) => Ok(AppError::Timeout(format!(
+                    "Request did not complete within {} seconds",
+                    limits.timeout.as_secs()
+                ))
+                .into_response()),
+            }
+        })
+    }
+}
+
+fn content_length(request: &Request) -> Option<u64> {
+    request
+        .headers()
+        .get(CONTENT_LENGTH)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse().ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_route_limits() {
+        let mut settings = ApiSettings::default();
+        settings.route_overrides = vec![RouteOverride {
+            path_prefix: "/api/v1/files".to_string(),
+            max_request_size: Some(100 * 1024 * 1024),
+            request_timeout: None,
+        }];
+        let limits = RequestLimits::from_settings(&settings);
+
+        let upload = limits.resolve("/api/v1/files/upload");
+        assert_eq!(upload.max_request_size, 100 * 1024 * 1024);
+        assert_eq!(upload.timeout, Duration::from_secs(settings.request_timeout));
+
+        let users = limits.resolve("/api/v1/users");
+        assert_eq!(users.max_request_size, settings.max_request_size);
+    }
+
+    #[test]
+    fn test_upload_limit_follows_storage_settings() {
+        let mut config = ApiConfig::default();
+        config.app.storage.max_upload_size = 50 * 1024 * 1024;
+        let limits = RequestLimits::from_config(&config);
+
+        let upload = limits.resolve("/api/v1/files");
+        assert_eq!(upload.max_request_size, 50 * 1024 * 1024 + MULTIPART_OVERHEAD);
+        assert_eq!(limits.resolve("/api/v1/users").max_request_size, config.api.max_request_size);
+
+        // Every version, and unversioned paths routed by header, get the upload limit
+        for path in ["/api/v2/files", "/api/files/upload"] {
+            assert_eq!(limits.resolve(path).max_request_size, upload.max_request_size, "{}", path);
+        }
+        assert_eq!(limits.resolve("/files").max_request_size, config.api.max_request_size);
+    }
+}
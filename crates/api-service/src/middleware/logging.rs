@@ -1,9 +1,30 @@
 //! Logging middleware
 
-use axum::{extract::Request, response::Response};
+use axum::{extract::Request, http::Uri, response::Response};
 use tower::{Layer, Service};
 use tracing::{info, warn};
 
+/// Query parameters carrying credentials, whose values are never logged
+const SENSITIVE_QUERY_PARAMS: &[&str] = &["token", "access_token", "signature"];
+
+/// Path and query of a URI for logging, with credential values replaced by `[REDACTED]`
+pub fn redacted_uri(uri: &Uri) -> String {
+    let Some(query) = uri.query() else {
+        return uri.path().to_string();
+    };
+
+    let query = query
+        .split('&')
+        .map(|pair| match pair.split_once('=') {
+            Some((name, _)) if SENSITIVE_QUERY_PARAMS.contains(&name) => format!("{}=[REDACTED]", name),
+            _ => pair.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("&");
+
+    format!("{}?{}", uri.path(), query)
+}
+
 /// Logging middleware
 #[derive(Clone)]
 pub struct LoggingMiddleware;
@@ -43,7 +64,7 @@ where
     fn call(&mut self, request: Request) -> Self::Future {
         let mut inner = self.inner.clone();
         let method = request.method().clone();
-        let uri = request.uri().clone();
+        let uri = redacted_uri(request.uri());
         let start = std::time::Instant::now();
 
         Box::pin(async move {
@@ -78,4 +99,18 @@ impl Default for LoggingMiddleware {
     fn default() -> Self {
         Self::new()
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redacted_uri() {
+        let uri: Uri = "/api/v1/ws?token=secret&page=2".parse().unwrap();
+        assert_eq!(redacted_uri(&uri), "/api/v1/ws?token=[REDACTED]&page=2");
+
+        let uri: Uri = "/api/v1/users?page=2".parse().unwrap();
+        assert_eq!(redacted_uri(&uri), "/api/v1/users?page=2");
+    }
 }
\ No newline at end of file
@@ -1,16 +1,56 @@
 //! Metrics middleware
 
-use axum::{extract::Request, response::Response};
-use metrics::{counter, histogram};
+use axum::{
+    body::HttpBody,
+    extract::{MatchedPath, Request},
+    http::{header::CONTENT_LENGTH, HeaderMap},
+    response::Response,
+};
+use metrics::{counter, gauge, histogram, Gauge};
+use shared::{
+    metrics::{self as metric_names, with_namespace, UNMATCHED_PATH},
+    MetricsConfig,
+};
+use std::sync::Arc;
 use tower::{Layer, Service};
 
-/// Metrics middleware
+/// Metric names with the configured namespace applied
+#[derive(Debug)]
+struct MetricNames {
+    requests_total: String,
+    request_duration: String,
+    requests_in_flight: String,
+    request_size: String,
+    response_size: String,
+}
+
+impl MetricNames {
+    fn new(namespace: &str) -> Self {
+        Self {
+            requests_total: with_namespace(namespace, metric_names::HTTP_REQUESTS_TOTAL),
+            request_duration: with_namespace(namespace, metric_names::HTTP_REQUEST_DURATION),
+            requests_in_flight: with_namespace(namespace, metric_names::HTTP_REQUESTS_IN_FLIGHT),
+            request_size: with_namespace(namespace, metric_names::HTTP_REQUEST_SIZE),
+            response_size: with_namespace(namespace, metric_names::HTTP_RESPONSE_SIZE),
+        }
+    }
+}
+
+/// Records request counts, durations, sizes and in-flight requests.
+///
+/// Requests are labelled by their route template (e.g. `/api/v1/users/:id`) rather than the raw
+/// path, so label cardinality stays bounded. Must be applied with `Router::layer` so the
+/// matched route is known.
 #[derive(Clone)]
-pub struct MetricsMiddleware;
+pub struct MetricsMiddleware {
+    names: Arc<MetricNames>,
+}
 
 impl MetricsMiddleware {
-    pub fn new() -> Self {
-        Self
+    pub fn new(config: &MetricsConfig) -> Self {
+        Self {
+            names: Arc::new(MetricNames::new(&config.namespace)),
+        }
     }
 }
 
@@ -18,13 +58,17 @@ impl<S> Layer<S> for MetricsMiddleware {
     type Service = MetricsMiddlewareService<S>;
 
     fn layer(&self, inner: S) -> Self::Service {
-        MetricsMiddlewareService { inner }
+        MetricsMiddlewareService {
+            inner,
+            names: self.names.clone(),
+        }
     }
 }
 
 #[derive(Clone)]
 pub struct MetricsMiddlewareService<S> {
     inner: S,
+    names: Arc<MetricNames>,
 }
 
 impl<S> Service<Request> for MetricsMiddlewareService<S>
@@ -42,35 +86,115 @@ where
 
     fn call(&mut self, request: Request) -> Self::Future {
         let mut inner = self.inner.clone();
+        let names = self.names.clone();
         let method = request.method().to_string();
-        let path = request.uri().path().to_string();
+        let path = route_label(&request);
+        let request_size = content_length(request.headers());
         let start = std::time::Instant::now();
 
         Box::pin(async move {
+            let _in_flight = InFlight::start(gauge!(names.requests_in_flight.clone(),
+                "method" => method.clone(),
+                "path" => path.clone()
+            ));
+
+            if let Some(size) = request_size {
+                histogram!(names.request_size.clone(),
+                    "method" => method.clone(),
+                    "path" => path.clone()
+                ).record(size as f64);
+            }
+
             let response = inner.call(request).await?;
             let duration = start.elapsed();
             let status = response.status().as_u16().to_string();
 
             // Record metrics
-            counter!("http_requests_total", 
-                "method" => method.clone(), 
-                "path" => path.clone(), 
+            counter!(names.requests_total.clone(),
+                "method" => method.clone(),
+                "path" => path.clone(),
                 "status" => status.clone()
             ).increment(1);
 
-            histogram!("http_request_duration_seconds",
-                "method" => method,
-                "path" => path,
-                "status" => status
+            histogram!(names.request_duration.clone(),
+                "method" => method.clone(),
+                "path" => path.clone(),
+                "status" => status.clone()
             ).record(duration.as_secs_f64());
 
+            // Streaming bodies have no known size until they are sent
+            let response_size = response
+                .body()
+                .size_hint()
+                .exact()
+                .or_else(|| content_length(response.headers()));
+            if let Some(size) = response_size {
+                histogram!(names.response_size.clone(),
+                    "method" => method,
+                    "path" => path,
+                    "status" => status
+                ).record(size as f64);
+            }
+
             Ok(response)
         })
     }
 }
 
+/// Route template the request matched, or a fixed label for unmatched requests
+fn route_label(request: &Request) -> String {
+    request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|path| path.as_str().to_string())
+        .unwrap_or_else(|| UNMATCHED_PATH.to_string())
+}
+
+fn content_length(headers: &HeaderMap) -> Option<u64> {
+    headers
+        .get(CONTENT_LENGTH)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse().ok())
+}
+
+/// Counts a request as in flight until dropped, including when the request is cancelled
+struct InFlight(Gauge);
+
+impl InFlight {
+    fn start(gauge: Gauge) -> Self {
+        gauge.increment(1.0);
+        Self(gauge)
+    }
+}
+
+impl Drop for InFlight {
+    fn drop(&mut self) {
+        self.0.decrement(1.0);
+    }
+}
+
 impl Default for MetricsMiddleware {
     fn default() -> Self {
-        Self::new()
+        Self::new(&MetricsConfig::default())
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+
+    #[test]
+    fn test_metric_names_use_namespace() {
+        let names = MetricNames::new("api");
+        assert_eq!(names.requests_total, "api_http_requests_total");
+        assert_eq!(names.requests_in_flight, "api_http_requests_in_flight");
+        assert_eq!(MetricNames::new("").request_size, "http_request_size_bytes");
+    }
+
+    #[test]
+    fn test_unmatched_route_label() {
+        let request = Request::builder().uri("/users/42").body(Body::empty()).unwrap();
+        assert_eq!(route_label(&request), UNMATCHED_PATH);
+    }
+}
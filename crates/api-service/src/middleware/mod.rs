@@ -1,10 +1,16 @@
 //! API middleware
 
 pub mod auth;
+pub mod correlation;
+pub mod cors;
+pub mod limits;
 pub mod logging;
 pub mod metrics;
+pub mod rate_limit;
+pub mod versioning;
 
 // Re-export middleware modules
 pub use auth::*;
+pub use correlation::*;
 pub use logging::*;
 pub use metrics::*;
\ No newline at end of file
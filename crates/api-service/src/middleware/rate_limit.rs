@@ -0,0 +1,359 @@
+//! Rate limiting middleware
+
+use axum::{
+    extract::{ConnectInfo, Request},
+    http::{HeaderMap, HeaderName, HeaderValue},
+    response::{IntoResponse, Response},
+};
+use cache::{RateLimitAlgorithm, RateLimitDecision, RateLimiter};
+use ipnet::IpNet;
+use sha2::{Digest, Sha256};
+use shared::{cache_keys, AppError, API_PREFIX};
+use std::{
+    net::{IpAddr, SocketAddr},
+    sync::Arc,
+};
+use tower::{Layer, Service};
+
+use crate::{
+    config::{RateLimitFailureMode, RateLimitSettings},
+    middleware::{
+        auth::{bearer_token, AuthContext},
+        versioning::matches_path_prefix,
+    },
+    state::AppState,
+};
+
+pub const RATE_LIMIT_LIMIT_HEADER: HeaderName = HeaderName::from_static("x-ratelimit-limit");
+pub const RATE_LIMIT_REMAINING_HEADER: HeaderName = HeaderName::from_static("x-ratelimit-remaining");
+pub const RATE_LIMIT_RESET_HEADER: HeaderName = HeaderName::from_static("x-ratelimit-reset");
+
+/// Limit resolved for a request
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RateLimitPolicy {
+    pub tier: String,
+    pub requests: u32,
+    pub window: u64,
+    pub algorithm: RateLimitAlgorithm,
+}
+
+/// Rate limits API routes per API key, user or client IP
+#[derive(Clone)]
+pub struct RateLimitLayer {
+    state: AppState,
+    limiter: Arc<RateLimiter>,
+}
+
+impl RateLimitLayer {
+    pub fn new(state: AppState) -> Self {
+        let limiter = RateLimiter::new(state.cache().clone(), cache_keys::RATE_LIMIT.to_string());
+        Self {
+            state,
+            limiter: Arc::new(limiter),
+        }
+    }
+}
+
+impl<S> Layer<S> for RateLimitLayer {
+    type Service = RateLimitService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RateLimitService {
+            inner,
+            state: self.state.clone(),
+            limiter: self.limiter.clone(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct RateLimitService<S> {
+    inner: S,
+    state: AppState,
+    limiter: Arc<RateLimiter>,
+}
+
+impl<S> Service<Request> for RateLimitService<S>
+where
+    S: Service<Request, Response = Response> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = std::pin::Pin<Box<dyn std::future::Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut std::task::Context<'_>) -> std::task::Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request) -> Self::Future {
+        let mut inner = self.inner.clone();
+        let state = self.state.clone();
+        let limiter = self.limiter.clone();
+
+        Box::pin(async move {
+            let config = state.config();
+            let settings = &config.api.rate_limit;
+            if !settings.enabled || !request.uri().path().starts_with(API_PREFIX) {
+                return inner.call(request).await;
+            }
+
+            let security = &config.app.security;
+            let policy = resolve_policy(
+                settings,
+                request.uri().path(),
+                security.rate_limit_requests,
+                security.rate_limit_window,
+            );
+            let identity = client_identity(&request, settings, security.jwt_secret.expose());
+            let key = format!("{}:{}", policy.tier, identity);
+
+            match limiter
+                .check_with(policy.algorithm, &key, policy.requests, policy.window)
+                .await
+            {
+                Ok(decision) if decision.allowed => {
+                    let mut response = inner.call(request).await?;
+                    apply_headers(response.headers_mut(), &decision);
+                    Ok(response)
+                }
+                Ok(decision) => {
+                    tracing::debug!(tier = %policy.tier, %identity, "Rate limit exceeded");
+                    let mut response = AppError::RateLimited(format!(
+                        "Too many requests, limit is {} per {} seconds",
+                        policy.requests, policy.window
+                    ))
+                    .into_response();
+                    apply_headers(response.headers_mut(), &decision);
+                    Ok(response)
+                }
+                Err(e) => match settings.failure_mode {
+                    RateLimitFailureMode::Open => {
+                        tracing::warn!("Rate limiter unavailable, allowing request: {}", e);
+                        inner.call(request).await
+                    }
+                    RateLimitFailureMode::Closed => {
+                        tracing::warn!("Rate limiter unavailable, rejecting request: {}", e);
+                        Ok(AppError::ServiceUnavailable("Rate limiter unavailable".to_string()).into_response())
+                    }
+                },
+            }
+        })
+    }
+}
+
+/// Pick the first tier matching the path, falling back to the default limit
+pub fn resolve_policy(settings: &RateLimitSettings, path: &str, default_requests: u32, default_window: u64) -> RateLimitPolicy {
+    settings
+        .tiers
+        .iter()
+        .find(|tier| matches_path_prefix(path, &tier.path_prefix))
+        .map(|tier| RateLimitPolicy {
+            tier: tier.name.clone(),
+            requests: tier.requests,
+            window: tier.window,
+            algorithm: tier.algorithm,
+        })
+        .unwrap_or_else(|| RateLimitPolicy {
+            tier: "default".to_string(),
+            requests: default_requests,
+            window: default_window,
+            algorithm: settings.algorithm,
+        })
+}
+
+/// Identify the client by a known API key, then authenticated user, then IP address.
+///
+/// Unknown keys are ignored rather than given their own bucket, so sending a fresh key with
+/// every request cannot reset the limit.
+fn client_identity(request: &Request, settings: &RateLimitSettings, jwt_secret: &str) -> String {
+    let headers = request.headers();
+
+    if let Some(name) = headers
+        .get(settings.api_key_header.as_str())
+        .and_then(|value| value.to_str().ok())
+        .and_then(|key| api_key_name(settings, key))
+    {
+        return format!("key:{}", name);
+    }
+
+    if let Some(auth) = bearer_token(headers).and_then(|token| AuthContext::from_token(token, jwt_secret).ok()) {
+        return format!("user:{}", auth.user_id);
+    }
+
+    match request_client_ip(request, &settings.trusted_proxies) {
+        Some(ip) => format!("ip:{}", ip),
+        None => "ip:unknown".to_string(),
+    }
+}
+
+/// Name of a configured API key matching `key`, compared by SHA-256 digest
+fn api_key_name<'a>(settings: &'a RateLimitSettings, key: &str) -> Option<&'a str> {
+    if key.is_empty() {
+        return None;
+    }
+
+    let digest = hex::encode(Sha256::digest(key.as_bytes()));
+    settings
+        .api_keys
+        .iter()
+        .find(|known| known.sha256.eq_ignore_ascii_case(&digest))
+        .map(|known| known.name.as_str())
+}
+
+/// Parse a trusted proxy entry, either a CIDR range or a single address
+pub fn parse_network(value: &str) -> Option<IpNet> {
+    let value = value.trim();
+    value
+        .parse::<IpNet>()
+        .ok()
+        .or_else(|| value.parse::<IpAddr>().ok().map(IpNet::from))
+}
+
+/// IP address of the client that sent `request`
+pub fn request_client_ip(request: &Request, trusted_proxies: &[String]) -> Option<String> {
+    let peer = request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip());
+    let trusted: Vec<IpNet> = trusted_proxies.iter().filter_map(|proxy| parse_network(proxy)).collect();
+
+    client_ip(request.headers(), peer, &trusted).map(|ip| ip.to_string())
+}
+
+/// Client IP: the right-most address in the peer and `X-Forwarded-For` chain that is not a
+/// trusted proxy.
+///
+/// Entries left of the first untrusted hop were written by the client and are ignored. If every
+/// hop is trusted, the left-most one is used; an unparseable hop ends the walk at the trusted
+/// proxy that forwarded it.
+fn client_ip(headers: &HeaderMap, peer: Option<IpAddr>, trusted: &[IpNet]) -> Option<IpAddr> {
+    let is_trusted = |ip: &IpAddr| trusted.iter().any(|network| network.contains(ip));
+
+    let mut client = peer?;
+    if !is_trusted(&client) {
+        return Some(client);
+    }
+
+    let forwarded = headers
+        .get_all("x-forwarded-for")
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(str::trim)
+        .filter(|hop| !hop.is_empty())
+        .collect::<Vec<_>>();
+
+    for hop in forwarded.into_iter().rev() {
+        let Ok(ip) = hop.parse::<IpAddr>() else {
+            break;
+        };
+        client = ip;
+        if !is_trusted(&ip) {
+            break;
+        }
+    }
+
+    Some(client)
+}
+
+fn apply_headers(headers: &mut HeaderMap, decision: &RateLimitDecision) {
+    headers.insert(RATE_LIMIT_LIMIT_HEADER, HeaderValue::from(decision.limit));
+    headers.insert(RATE_LIMIT_REMAINING_HEADER, HeaderValue::from(decision.remaining));
+    headers.insert(RATE_LIMIT_RESET_HEADER, HeaderValue::from(decision.reset_after));
+    if let Some(retry_after) = decision.retry_after {
+        headers.insert(axum::http::header::RETRY_AFTER, HeaderValue::from(retry_after));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_policy() {
+        let settings = RateLimitSettings::default();
+
+        let auth = resolve_policy(&settings, "/api/v1/auth/login", 100, 60);
+        assert_eq!(auth.tier, "auth");
+        assert_eq!(auth.requests, shared::rate_limits::AUTH_REQUESTS_PER_MINUTE);
+
+        // The tier covers the auth routes of every version
+        assert_eq!(resolve_policy(&settings, "/api/v2/auth/login", 100, 60), auth);
+        assert_eq!(resolve_policy(&settings, "/api/auth/login", 100, 60), auth);
+
+        let users = resolve_policy(&settings, "/api/v1/users", 100, 60);
+        assert_eq!(
+            users,
+            RateLimitPolicy {
+                tier: "default".to_string(),
+                requests: 100,
+                window: 60,
+                algorithm: RateLimitAlgorithm::SlidingWindow,
+            }
+        );
+    }
+
+    #[test]
+    fn test_resolve_policy_algorithm() {
+        let mut settings = RateLimitSettings::default();
+        settings.algorithm = RateLimitAlgorithm::Gcra;
+        settings.tiers[0].algorithm = RateLimitAlgorithm::TokenBucket;
+
+        assert_eq!(resolve_policy(&settings, "/api/v1/auth/login", 100, 60).algorithm, RateLimitAlgorithm::TokenBucket);
+        assert_eq!(resolve_policy(&settings, "/api/v1/users", 100, 60).algorithm, RateLimitAlgorithm::Gcra);
+    }
+
+    #[test]
+    fn test_client_ip() {
+        let ip = |value: &str| value.parse::<IpAddr>().unwrap();
+        let trusted = vec![parse_network("10.0.0.0/8").unwrap()];
+        let peer = Some(ip("10.0.0.1"));
+
+        // The left-most entry is client supplied; the right-most untrusted hop is the client
+        let mut headers = HeaderMap::new();
+        headers.insert("x-forwarded-for", "198.51.100.9, 203.0.113.7, 10.0.0.2".parse().unwrap());
+        assert_eq!(client_ip(&headers, peer, &trusted), Some(ip("203.0.113.7")));
+
+        // Without trusted proxies, or from an untrusted peer, the header is ignored
+        assert_eq!(client_ip(&headers, peer, &[]), peer);
+        assert_eq!(client_ip(&headers, Some(ip("192.0.2.1")), &trusted), Some(ip("192.0.2.1")));
+
+        // Garbage stops the walk at the proxy that forwarded it
+        headers.insert("x-forwarded-for", "203.0.113.7, not-an-ip, 10.0.0.2".parse().unwrap());
+        assert_eq!(client_ip(&headers, peer, &trusted), Some(ip("10.0.0.2")));
+    }
+
+    #[test]
+    fn test_only_known_api_keys_identify_clients() {
+        let mut settings = RateLimitSettings::default();
+        settings.api_keys.push(crate::config::ApiKeySettings {
+            name: "partner".to_string(),
+            sha256: hex::encode(Sha256::digest(b"secret-key")),
+        });
+
+        assert_eq!(api_key_name(&settings, "secret-key"), Some("partner"));
+        assert_eq!(api_key_name(&settings, "random-key"), None);
+        assert_eq!(api_key_name(&settings, ""), None);
+    }
+
+    #[test]
+    fn test_parse_network() {
+        assert!(parse_network("10.0.0.0/8").is_some());
+        assert!(parse_network(" 192.0.2.1 ").unwrap().contains(&"192.0.2.1".parse::<IpAddr>().unwrap()));
+        assert!(parse_network("::1").is_some());
+        assert!(parse_network("proxy.internal").is_none());
+    }
+
+    #[test]
+    fn test_rejection_headers() {
+        let decision = RateLimitDecision { allowed: false, limit: 10, remaining: 0, reset_after: 60, retry_after: Some(42) };
+        let mut headers = HeaderMap::new();
+        apply_headers(&mut headers, &decision);
+
+        assert_eq!(headers[RATE_LIMIT_LIMIT_HEADER], "10");
+        assert_eq!(headers[RATE_LIMIT_REMAINING_HEADER], "0");
+        assert_eq!(headers[RATE_LIMIT_RESET_HEADER], "60");
+        assert_eq!(headers[axum::http::header::RETRY_AFTER], "42");
+    }
+}
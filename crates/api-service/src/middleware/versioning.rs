@@ -0,0 +1,295 @@
+//! API versioning middleware
+
+use axum::{
+    extract::Request,
+    http::{HeaderMap, HeaderValue, Uri},
+    response::{IntoResponse, Response},
+};
+use metrics::counter;
+use shared::{
+    metrics::{with_namespace, API_VERSION_REQUESTS},
+    AppError, AppResult, API_PREFIX, API_VERSION_HEADER, DEPRECATION_HEADER, SUNSET_HEADER,
+};
+use std::sync::Arc;
+use tower::{Layer, Service};
+
+use crate::config::{is_version_name, ApiSettings, ApiVersionSettings};
+
+/// API version a request resolved to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResolvedVersion {
+    pub version: String,
+    /// Versioned path to route to, when the request path did not name a version
+    pub rewritten_path: Option<String>,
+}
+
+/// Routes API requests to a version.
+///
+/// A version in the path (`/api/v2/...`) always wins. Unversioned paths (`/api/users`) are
+/// rewritten to the version named by `X-API-Version`, or the default version. Responses carry
+/// the resolved version and, for deprecated versions, `Deprecation`, `Sunset` and `Link` headers.
+///
+/// The URI is rewritten before routing, so this layer must wrap the routed application.
+#[derive(Clone)]
+pub struct ApiVersionLayer {
+    settings: Arc<ApiSettings>,
+    requests_metric: Arc<str>,
+}
+
+impl ApiVersionLayer {
+    pub fn new(settings: &ApiSettings, metrics_namespace: &str) -> Self {
+        Self {
+            settings: Arc::new(settings.clone()),
+            requests_metric: with_namespace(metrics_namespace, API_VERSION_REQUESTS).into(),
+        }
+    }
+}
+
+impl<S> Layer<S> for ApiVersionLayer {
+    type Service = ApiVersionService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        ApiVersionService {
+            inner,
+            settings: self.settings.clone(),
+            requests_metric: self.requests_metric.clone(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct ApiVersionService<S> {
+    inner: S,
+    settings: Arc<ApiSettings>,
+    requests_metric: Arc<str>,
+}
+
+impl<S> Service<Request> for ApiVersionService<S>
+where
+    S: Service<Request, Response = Response> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = std::pin::Pin<Box<dyn std::future::Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut std::task::Context<'_>) -> std::task::Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut request: Request) -> Self::Future {
+        let mut inner = self.inner.clone();
+        let settings = self.settings.clone();
+        let requests_metric = self.requests_metric.clone();
+
+        Box::pin(async move {
+            let header = request
+                .headers()
+                .get(API_VERSION_HEADER)
+                .and_then(|value| value.to_str().ok());
+
+            let resolved = match resolve_version(request.uri().path(), header, &settings) {
+                Ok(Some(resolved)) => resolved,
+                Ok(None) => return inner.call(request).await,
+                Err(e) => return Ok(e.into_response()),
+            };
+
+            if let Some(path) = &resolved.rewritten_path {
+                match with_path(request.uri(), path) {
+                    Ok(uri) => *request.uri_mut() = uri,
+                    Err(e) => return Ok(e.into_response()),
+                }
+            }
+
+            let policy = settings.versioning.find(&resolved.version).cloned();
+            let deprecated = policy.as_ref().is_some_and(|policy| policy.deprecated_at.is_some());
+            counter!(requests_metric.to_string(),
+                "version" => resolved.version.clone(),
+                "deprecated" => deprecated.to_string()
+            ).increment(1);
+
+            let mut response = inner.call(request).await?;
+            let headers = response.headers_mut();
+            if let Ok(value) = HeaderValue::from_str(&resolved.version) {
+                headers.insert(API_VERSION_HEADER, value);
+            }
+            if let Some(policy) = &policy {
+                apply_lifecycle_headers(headers, policy);
+            }
+
+            Ok(response)
+        })
+    }
+}
+
+/// Resolve the version of an API request; `None` for paths outside the API
+pub fn resolve_version(path: &str, header: Option<&str>, settings: &ApiSettings) -> AppResult<Option<ResolvedVersion>> {
+    let Some(rest) = path.strip_prefix(API_PREFIX).and_then(|rest| rest.strip_prefix('/')) else {
+        return Ok(None);
+    };
+
+    let segment = rest.split('/').next().unwrap_or_default();
+    if is_version_name(segment) {
+        return match settings.versioning.find(segment) {
+            Some(_) => Ok(Some(ResolvedVersion {
+                version: segment.to_string(),
+                rewritten_path: None,
+            })),
+            None => Err(unsupported_version(segment)),
+        };
+    }
+
+    let version = match header.map(str::trim).filter(|value| !value.is_empty()) {
+        Some(requested) => {
+            // Accept both `2` and `v2`
+            let name = if requested.starts_with('v') {
+                requested.to_string()
+            } else {
+                format!("v{}", requested)
+            };
+            if settings.versioning.find(&name).is_none() {
+                return Err(unsupported_version(requested));
+            }
+            name
+        }
+        None => settings.version.clone(),
+    };
+
+    Ok(Some(ResolvedVersion {
+        rewritten_path: Some(format!("{}/{}/{}", API_PREFIX, version, rest)),
+        version,
+    }))
+}
+
+/// Path of an API request below its version segment, e.g. `/auth/login` for
+/// `/api/v2/auth/login` and for the unversioned `/api/auth/login`; `None` outside the API
+pub fn path_within_version(path: &str) -> Option<&str> {
+    let rest = path.strip_prefix(API_PREFIX).filter(|rest| rest.starts_with('/'))?;
+    let segment = rest[1..].split('/').next().unwrap_or_default();
+    if is_version_name(segment) {
+        Some(&rest[1 + segment.len()..])
+    } else {
+        Some(rest)
+    }
+}
+
+/// Whether `path` falls under a configured path prefix.
+///
+/// API paths are compared below their version, so `/api/v1/files` covers the same routes in
+/// every served version.
+pub fn matches_path_prefix(path: &str, prefix: &str) -> bool {
+    match (path_within_version(path), path_within_version(prefix)) {
+        (Some(path), Some(prefix)) => path.starts_with(prefix),
+        _ => path.starts_with(prefix),
+    }
+}
+
+fn unsupported_version(version: &str) -> AppError {
+    AppError::BadRequest(format!("Unsupported API version: {}", version))
+}
+
+/// Replace the path of a URI, keeping its query string
+fn with_path(uri: &Uri, path: &str) -> AppResult<Uri> {
+    let path_and_query = match uri.query() {
+        Some(query) => format!("{}?{}", path, query),
+        None => path.to_string(),
+    };
+
+    let mut parts = uri.clone().into_parts();
+    parts.path_and_query = Some(
+        path_and_query
+            .parse()
+            .map_err(|_| AppError::BadRequest(format!("Invalid request path: {}", path)))?,
+    );
+    Uri::from_parts(parts).map_err(|_| AppError::BadRequest(format!("Invalid request path: {}", path)))
+}
+
+fn apply_lifecycle_headers(headers: &mut HeaderMap, policy: &ApiVersionSettings) {
+    if let Some(deprecated_at) = policy.deprecated_at {
+        if let Ok(value) = HeaderValue::from_str(&format!("@{}", deprecated_at.timestamp())) {
+            headers.insert(DEPRECATION_HEADER, value);
+        }
+    }
+
+    if let Some(sunset_at) = policy.sunset_at {
+        if let Ok(value) = HeaderValue::from_str(&sunset_at.format("%a, %d %b %Y %H:%M:%S GMT").to_string()) {
+            headers.insert(SUNSET_HEADER, value);
+        }
+    }
+
+    if let Some(link) = &policy.deprecation_link {
+        if let Ok(value) = HeaderValue::from_str(&format!("<{}>; rel=\"deprecation\"", link)) {
+            headers.append(axum::http::header::LINK, value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    fn settings() -> ApiSettings {
+        let mut settings = ApiSettings::default();
+        settings.versioning.versions[0].deprecated_at = Some(Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap());
+        settings.versioning.versions[0].sunset_at = Some(Utc.with_ymd_and_hms(2026, 7, 1, 0, 0, 0).unwrap());
+        settings.versioning.versions.push(ApiVersionSettings {
+            name: "v2".to_string(),
+            deprecated_at: None,
+            sunset_at: None,
+            deprecation_link: None,
+        });
+        settings
+    }
+
+    #[test]
+    fn test_resolve_version() {
+        let settings = settings();
+
+        // Paths outside the API are left alone
+        assert_eq!(resolve_version("/health", None, &settings).unwrap(), None);
+
+        // The path wins over the header
+        let resolved = resolve_version("/api/v1/users", Some("v2"), &settings).unwrap().unwrap();
+        assert_eq!(resolved, ResolvedVersion { version: "v1".to_string(), rewritten_path: None });
+
+        // Unversioned paths follow the header, then the default
+        let resolved = resolve_version("/api/users/42", Some("2"), &settings).unwrap().unwrap();
+        assert_eq!(resolved.rewritten_path.as_deref(), Some("/api/v2/users/42"));
+        let resolved = resolve_version("/api/users", None, &settings).unwrap().unwrap();
+        assert_eq!(resolved.rewritten_path.as_deref(), Some("/api/v1/users"));
+
+        assert!(resolve_version("/api/v9/users", None, &settings).is_err());
+        assert!(resolve_version("/api/users", Some("v9"), &settings).is_err());
+    }
+
+    #[test]
+    fn test_prefixes_match_every_version() {
+        assert_eq!(path_within_version("/api/v2/auth/login"), Some("/auth/login"));
+        assert_eq!(path_within_version("/api/auth/login"), Some("/auth/login"));
+        assert_eq!(path_within_version("/apis/v1"), None);
+
+        assert!(matches_path_prefix("/api/v2/files/upload", "/api/v1/files"));
+        assert!(matches_path_prefix("/api/files", "/api/v1/files"));
+        assert!(!matches_path_prefix("/api/v2/users", "/api/v1/files"));
+        assert!(!matches_path_prefix("/files", "/api/v1/files"));
+        assert!(matches_path_prefix("/graphql", "/graphql"));
+    }
+
+    #[test]
+    fn test_with_path_keeps_query() {
+        let uri: Uri = "/api/users?page=2".parse().unwrap();
+        assert_eq!(with_path(&uri, "/api/v1/users").unwrap(), "/api/v1/users?page=2");
+    }
+
+    #[test]
+    fn test_lifecycle_headers() {
+        let settings = settings();
+        let mut headers = HeaderMap::new();
+        apply_lifecycle_headers(&mut headers, &settings.versioning.versions[0]);
+
+        assert_eq!(headers[DEPRECATION_HEADER], "@1767225600");
+        assert_eq!(headers[SUNSET_HEADER], "Wed, 01 Jul 2026 00:00:00 GMT");
+        assert!(headers.get(axum::http::header::LINK).is_none());
+    }
+}
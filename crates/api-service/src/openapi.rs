@@ -0,0 +1,175 @@
+//! OpenAPI document generation
+
+use shared::{DependencyHealth, HealthStatus, PaginationInfo, ServiceStatus};
+use utoipa::{
+    openapi::security::{HttpAuthScheme, HttpBuilder, SecurityScheme},
+    Modify, OpenApi,
+};
+
+use crate::{
+    config::{ApiErrorResponse, ApiMetadata, ApiSettings},
+    handlers::{audit_logs, auth, events, files, health, jobs, users},
+    services::ExportFormat,
+};
+
+/// OpenAPI document for the REST API
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        health::health_check,
+        health::readiness_check,
+        health::liveness_check,
+        auth::login,
+        auth::register,
+        auth::refresh_token,
+        auth::logout,
+        users::list_users,
+        users::get_user,
+        users::create_user,
+        users::update_user,
+        users::delete_user,
+        users::get_user_profile,
+        users::update_user_profile,
+        events::stream_events,
+        audit_logs::list_audit_logs,
+        audit_logs::export_audit_logs,
+        files::upload_file,
+        files::get_file,
+        files::delete_file,
+        files::download_file,
+        jobs::enqueue_job,
+        jobs::list_jobs,
+        jobs::get_job,
+        jobs::cancel_job,
+        jobs::retry_job,
+    ),
+    components(schemas(
+        auth::LoginRequest,
+        auth::LoginResponse,
+        auth::RegisterRequest,
+        auth::RefreshTokenRequest,
+        users::CreateUserRequest,
+        users::UpdateUserRequest,
+        users::UpdateUserProfileRequest,
+        users::UserResponse,
+        audit_logs::AuditLogResponse,
+        ExportFormat,
+        files::FileUploadForm,
+        files::FileResponse,
+        jobs::JobState,
+        jobs::EnqueueJobRequest,
+        jobs::JobResponse,
+        PaginationInfo,
+        HealthStatus,
+        ServiceStatus,
+        DependencyHealth,
+        ApiErrorResponse,
+        ApiMetadata,
+    )),
+    modifiers(&SecurityAddon),
+    tags(
+        (name = "health", description = "Service health probes"),
+        (name = "auth", description = "Authentication and session management"),
+        (name = "users", description = "User management"),
+        (name = "events", description = "Domain event streaming"),
+        (name = "audit", description = "Audit log queries and exports"),
+        (name = "files", description = "File uploads and signed downloads"),
+        (name = "jobs", description = "Background job management"),
+    )
+)]
+pub struct ApiDoc;
+
+/// Registers the bearer token security scheme referenced by protected operations
+struct SecurityAddon;
+
+impl Modify for SecurityAddon {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            "bearer_auth",
+            SecurityScheme::Http(
+                HttpBuilder::new()
+                    .scheme(HttpAuthScheme::Bearer)
+                    .bearer_format("JWT")
+                    .build(),
+            ),
+        );
+    }
+}
+
+/// Build the OpenAPI document with title, description and version taken from the API settings
+pub fn api_doc(settings: &ApiSettings) -> utoipa::openapi::OpenApi {
+    let mut doc = ApiDoc::openapi();
+    doc.info.title = settings.title.clone();
+    doc.info.description = Some(settings.description.clone());
+    doc.info.version = settings.version.clone();
+    doc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::routes;
+    use std::collections::BTreeSet;
+
+    /// Collect `(METHOD, path)` pairs for every operation in the document
+    fn documented_operations(doc: &utoipa::openapi::OpenApi) -> BTreeSet<(String, String)> {
+        let mut operations = BTreeSet::new();
+
+        for (path, item) in doc.paths.paths.iter() {
+            let methods = [
+                ("GET", item.get.is_some()),
+                ("PUT", item.put.is_some()),
+                ("POST", item.post.is_some()),
+                ("DELETE", item.delete.is_some()),
+                ("OPTIONS", item.options.is_some()),
+                ("HEAD", item.head.is_some()),
+                ("PATCH", item.patch.is_some()),
+                ("TRACE", item.trace.is_some()),
+            ];
+
+            for (method, present) in methods {
+                if present {
+                    operations.insert((method.to_string(), path.clone()));
+                }
+            }
+        }
+
+        operations
+    }
+
+    #[test]
+    fn test_openapi_matches_routes() {
+        // Every optional route is enabled by default, so this covers the full route table
+        let (routed, excluded): (Vec<_>, Vec<_>) = routes::route_definitions(&ApiSettings::default())
+            .into_iter()
+            .partition(|route| route.documented);
+        let routed: BTreeSet<(String, String)> = routed
+            .into_iter()
+            .map(|route| (route.method, route.openapi_path()))
+            .collect();
+        let excluded: BTreeSet<(String, String)> = excluded
+            .into_iter()
+            .map(|route| (route.method, route.openapi_path()))
+            .collect();
+        let documented = documented_operations(&ApiDoc::openapi());
+
+        let undocumented: Vec<_> = routed.difference(&documented).collect();
+        let unrouted: Vec<_> = documented.difference(&routed).collect();
+        let unexpected: Vec<_> = excluded.intersection(&documented).collect();
+
+        assert!(undocumented.is_empty(), "routes missing from the OpenAPI document: {:?}", undocumented);
+        assert!(unrouted.is_empty(), "documented operations without a route: {:?}", unrouted);
+        assert!(unexpected.is_empty(), "routes registered as undocumented appear in the document: {:?}", unexpected);
+    }
+
+    #[test]
+    fn test_api_doc_uses_settings() {
+        let settings = ApiSettings::default();
+        let doc = api_doc(&settings);
+
+        assert_eq!(doc.info.title, settings.title);
+        assert_eq!(doc.info.version, settings.version);
+        assert!(doc.components.unwrap().security_schemes.contains_key("bearer_auth"));
+    }
+}
@@ -0,0 +1,122 @@
+//! In-process fan-out of notifications received through Redis pub/sub
+
+use cache::{Notification, NotificationChannel, NotificationPublisher, RedisManager};
+use database::{Order, Payment};
+use futures::StreamExt;
+use shared::{runtime::CancellationToken, AppResult};
+use std::{sync::Arc, time::Duration};
+use tokio::sync::broadcast;
+use tracing::{debug, info, warn};
+
+/// Delay before re-subscribing after the Redis subscription is lost
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+/// Distributes notifications to every WebSocket connection of this instance.
+///
+/// Notifications are published to Redis, and a bridge task re-broadcasts everything
+/// received from Redis locally, so updates fan out across all api-service instances.
+#[derive(Debug, Clone)]
+pub struct NotificationHub {
+    sender: broadcast::Sender<Arc<Notification>>,
+    publisher: NotificationPublisher,
+}
+
+impl NotificationHub {
+    pub fn new(redis: RedisManager, capacity: usize) -> Self {
+        let (sender, _) = broadcast::channel(capacity);
+
+        Self {
+            sender,
+            publisher: NotificationPublisher::new(redis),
+        }
+    }
+
+    /// Receive notifications broadcast on this instance
+    pub fn subscribe(&self) -> broadcast::Receiver<Arc<Notification>> {
+        self.sender.subscribe()
+    }
+
+    /// Publish a notification to all instances.
+    ///
+    /// If Redis is unavailable the notification is still delivered to local clients.
+    pub async fn publish(&self, notification: Notification) {
+        if let Err(e) = self.publisher.publish(&notification).await {
+            warn!("Failed to publish notification {} to Redis: {}", notification.id, e);
+            self.broadcast(notification);
+        }
+    }
+
+    /// Forward notifications from Redis to local subscribers until cancelled
+    pub async fn run_bridge(&self, token: CancellationToken) -> AppResult<()> {
+        loop {
+            let subscribed = tokio::select! {
+                _ = token.cancelled() => return Ok(()),
+                subscribed = self.publisher.subscribe_all() => subscribed,
+            };
+            match subscribed {
+                Ok(stream) => {
+                    info!("Subscribed to notification channels");
+                    let mut stream = Box::pin(stream);
+                    loop {
+                        let notification = tokio::select! {
+                            _ = token.cancelled() => return Ok(()),
+                            notification = stream.next() => notification,
+                        };
+                        match notification {
+                            Some(notification) => self.broadcast(notification),
+                            None => break,
+                        }
+                    }
+                    warn!("Notification subscription closed, reconnecting");
+                }
+                Err(e) => warn!("Failed to subscribe to notification channels: {}", e),
+            }
+
+            tokio::select! {
+                _ = token.cancelled() => return Ok(()),
+                _ = tokio::time::sleep(RECONNECT_DELAY) => {}
+            }
+        }
+    }
+
+    fn broadcast(&self, notification: Notification) {
+        // Sending only fails when no connection is currently subscribed
+        if self.sender.send(Arc::new(notification)).is_err() {
+            debug!("No local subscribers for notification");
+        }
+    }
+}
+
+/// Notification about a change to an order
+pub fn order_notification(order: &Order, event: &str) -> Notification {
+    Notification::new(
+        order.tenant_id,
+        NotificationChannel::Orders,
+        event,
+        serde_json::json!({
+            "order_number": order.order_number,
+            "status": order.status,
+            "total_amount": order.total_amount,
+            "currency": order.currency,
+        }),
+    )
+    .with_user(order.user_id)
+    .with_resource(order.id)
+}
+
+/// Notification about a change to a payment
+pub fn payment_notification(payment: &Payment, event: &str) -> Notification {
+    Notification::new(
+        payment.tenant_id,
+        NotificationChannel::Payments,
+        event,
+        serde_json::json!({
+            "order_id": payment.order_id,
+            "status": payment.status,
+            "amount": payment.amount,
+            "currency": payment.currency,
+        }),
+    )
+    .with_user(payment.user_id)
+    .with_resource(payment.id)
+}
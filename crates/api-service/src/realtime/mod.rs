@@ -0,0 +1,7 @@
+//! Real-time notifications delivered to connected clients
+
+pub mod hub;
+pub mod websocket;
+
+pub use hub::*;
+pub use websocket::*;
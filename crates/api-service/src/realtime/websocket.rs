@@ -0,0 +1,344 @@
+//! WebSocket endpoint streaming notifications to subscribed clients
+//!
+//! Clients authenticate with a bearer token, either in the `Authorization` header or, since
+//! browsers cannot set headers on WebSocket requests, as a subprotocol offered after
+//! [`BEARER_PROTOCOL`] (`new WebSocket(url, ["bearer", token])`). Tokens are never read from
+//! the query string, which would put them in access and proxy logs. Clients then exchange
+//! JSON messages:
+//!
+//! ```json
+//! { "type": "subscribe", "channels": ["orders", "payments"] }
+//! { "type": "unsubscribe", "channels": ["payments"] }
+//! ```
+
+use axum::{
+    extract::{
+        ws::{close_code, CloseFrame, Message, WebSocket, WebSocketUpgrade},
+        State,
+    },
+    http::{header::SEC_WEBSOCKET_PROTOCOL, HeaderMap},
+    response::{IntoResponse, Response},
+};
+use cache::{Notification, NotificationChannel};
+use futures::{SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use shared::{runtime::CancellationToken, AppError};
+use std::{collections::HashSet, sync::Arc, time::Duration};
+use tokio::{
+    sync::{broadcast::error::RecvError, mpsc},
+    task::JoinHandle,
+    time::{interval, Instant},
+};
+use tracing::{debug, warn};
+
+use crate::{
+    config::WebSocketSettings,
+    middleware::auth::{bearer_token, AuthContext},
+    state::AppState,
+};
+
+/// Subprotocol selected for clients that pass their token in `Sec-WebSocket-Protocol`
+pub const BEARER_PROTOCOL: &str = "bearer";
+
+/// Messages sent by clients
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ClientMessage {
+    Subscribe { channels: Vec<NotificationChannel> },
+    Unsubscribe { channels: Vec<NotificationChannel> },
+    Ping,
+}
+
+/// Messages sent to clients
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ServerMessage<'a> {
+    Subscribed { channels: Vec<NotificationChannel> },
+    Notification { notification: &'a Notification },
+    /// Notifications were dropped because the connection fell behind
+    Lagged { missed: u64 },
+    Pong,
+    Error { message: String },
+}
+
+impl ServerMessage<'_> {
+    fn to_message(&self) -> Message {
+        Message::Text(serde_json::to_string(self).unwrap_or_default())
+    }
+}
+
+/// Channels a connection is subscribed to, and who it belongs to
+pub struct Subscriptions {
+    auth: AuthContext,
+    channels: HashSet<NotificationChannel>,
+}
+
+impl Subscriptions {
+    pub fn new(auth: AuthContext) -> Self {
+        Self {
+            auth,
+            channels: HashSet::new(),
+        }
+    }
+
+    /// Whether a notification should be delivered on this connection.
+    ///
+    /// Only notifications of the caller's tenant are delivered; non-admin users only
+    /// receive tenant-wide notifications and those about their own resources.
+    pub fn accepts(&self, notification: &Notification) -> bool {
+        notification.tenant_id == self.auth.tenant_id
+            && self.channels.contains(&notification.channel)
+            && (self.auth.is_admin() || notification.user_id.map_or(true, |user_id| user_id == self.auth.user_id))
+    }
+
+    fn current(&self) -> Vec<NotificationChannel> {
+        let mut channels: Vec<_> = self.channels.iter().copied().collect();
+        channels.sort_by_key(|channel| channel.as_str());
+        channels
+    }
+}
+
+/// Upgrade an authenticated request to a notification WebSocket
+pub async fn ws_handler(
+    ws: WebSocketUpgrade,
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Response {
+    let auth = bearer_token(&headers)
+        .or_else(|| protocol_token(&headers))
+        .ok_or_else(|| AppError::Authentication("Missing bearer token".to_string()))
+        .and_then(|token| AuthContext::from_token(token, state.config().app.security.jwt_secret.expose()));
+    let auth = match auth {
+        Ok(auth) => auth,
+        Err(e) => return e.into_response(),
+    };
+
+    let settings = state.config().api.websocket.clone();
+    let notifications = state.notifications().subscribe();
+    let shutdown = state.shutdown_token().clone();
+
+    ws.protocols([BEARER_PROTOCOL])
+        .on_upgrade(move |socket| handle_socket(socket, auth, notifications, settings, shutdown))
+}
+
+/// Token offered in `Sec-WebSocket-Protocol` as the protocol following [`BEARER_PROTOCOL`]
+pub fn protocol_token(headers: &HeaderMap) -> Option<&str> {
+    let mut protocols = headers
+        .get_all(SEC_WEBSOCKET_PROTOCOL)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(str::trim);
+
+    protocols.find(|protocol| *protocol == BEARER_PROTOCOL)?;
+    protocols.next().filter(|token| !token.is_empty())
+}
+
+/// Why a connection loop ended
+enum Disconnect {
+    Client,
+    TimedOut,
+    SlowConsumer,
+    ShuttingDown,
+}
+
+async fn handle_socket(
+    socket: WebSocket,
+    auth: AuthContext,
+    mut notifications: tokio::sync::broadcast::Receiver<Arc<Notification>>,
+    settings: WebSocketSettings,
+    shutdown: CancellationToken,
+) {
+    let (mut sink, mut stream) = socket.split();
+
+    // Outbound messages go through a bounded queue so a slow client cannot grow memory unbounded
+    let (outbound, mut queue) = mpsc::channel::<Message>(settings.send_queue_size);
+    let writer = tokio::spawn(async move {
+        while let Some(message) = queue.recv().await {
+            if sink.send(message).await.is_err() {
+                break;
+            }
+        }
+    });
+
+    let mut subscriptions = Subscriptions::new(auth);
+    let mut heartbeat = interval(Duration::from_secs(settings.heartbeat_interval));
+    let client_timeout = Duration::from_secs(settings.client_timeout);
+    let mut last_seen = Instant::now();
+
+    let reason = loop {
+        tokio::select! {
+            incoming = stream.next() => {
+                let message = match incoming {
+                    Some(Ok(message)) => message,
+                    _ => break Disconnect::Client,
+                };
+                last_seen = Instant::now();
+
+                let reply = match message {
+                    Message::Text(text) => handle_client_message(&mut subscriptions, &text),
+                    Message::Close(_) => break Disconnect::Client,
+                    _ => None,
+                };
+
+                if let Some(reply) = reply {
+                    if outbound.try_send(reply).is_err() {
+                        break Disconnect::SlowConsumer;
+                    }
+                }
+            }
+            received = notifications.recv() => {
+                let message = match received {
+                    Ok(notification) if subscriptions.accepts(&notification) => {
+                        ServerMessage::Notification { notification: notification.as_ref() }.to_message()
+                    }
+                    Ok(_) => continue,
+                    Err(RecvError::Lagged(missed)) => ServerMessage::Lagged { missed }.to_message(),
+                    Err(RecvError::Closed) => break Disconnect::Client,
+                };
+
+                if outbound.try_send(message).is_err() {
+                    break Disconnect::SlowConsumer;
+                }
+            }
+            _ = shutdown.cancelled() => break Disconnect::ShuttingDown,
+            _ = heartbeat.tick() => {
+                if last_seen.elapsed() > client_timeout {
+                    break Disconnect::TimedOut;
+                }
+                if outbound.try_send(Message::Ping(Vec::new())).is_err() {
+                    break Disconnect::SlowConsumer;
+                }
+            }
+        }
+    };
+
+    match reason {
+        Disconnect::Client => {
+            debug!("WebSocket client disconnected");
+            drop(outbound);
+            let _ = writer.await;
+        }
+        Disconnect::TimedOut => {
+            debug!("WebSocket client timed out");
+            close_away(outbound, writer, "heartbeat timeout").await;
+        }
+        Disconnect::ShuttingDown => {
+            debug!("Closing WebSocket connection for shutdown");
+            close_away(outbound, writer, "server shutting down").await;
+        }
+        Disconnect::SlowConsumer => {
+            warn!("Dropping WebSocket client that is not keeping up");
+            writer.abort();
+        }
+    }
+}
+
+/// Send a "going away" close frame, giving the writer a moment to deliver it
+async fn close_away(outbound: mpsc::Sender<Message>, mut writer: JoinHandle<()>, reason: &'static str) {
+    let _ = outbound.try_send(close_message(close_code::AWAY, reason));
+    drop(outbound);
+    let _ = tokio::time::timeout(Duration::from_secs(1), &mut writer).await;
+    writer.abort();
+}
+
+/// Apply a client message and build the reply, if any
+fn handle_client_message(subscriptions: &mut Subscriptions, text: &str) -> Option<Message> {
+    let reply = match serde_json::from_str::<ClientMessage>(text) {
+        Ok(ClientMessage::Subscribe { channels }) => {
+            subscriptions.channels.extend(channels);
+            ServerMessage::Subscribed { channels: subscriptions.current() }
+        }
+        Ok(ClientMessage::Unsubscribe { channels }) => {
+            for channel in &channels {
+                subscriptions.channels.remove(channel);
+            }
+            ServerMessage::Subscribed { channels: subscriptions.current() }
+        }
+        Ok(ClientMessage::Ping) => ServerMessage::Pong,
+        Err(e) => ServerMessage::Error { message: format!("Invalid message: {}", e) },
+    };
+
+    Some(reply.to_message())
+}
+
+fn close_message(code: u16, reason: &'static str) -> Message {
+    Message::Close(Some(CloseFrame {
+        code,
+        reason: reason.into(),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::roles;
+    use uuid::Uuid;
+
+    fn auth(roles: &[&str]) -> AuthContext {
+        AuthContext {
+            user_id: Uuid::new_v4(),
+            tenant_id: Uuid::new_v4(),
+            roles: roles.iter().map(|role| role.to_string()).collect(),
+        }
+    }
+
+    fn notification(auth: &AuthContext, channel: NotificationChannel) -> Notification {
+        Notification::new(auth.tenant_id, channel, "order.created", serde_json::json!({}))
+    }
+
+    #[test]
+    fn test_accepts_only_subscribed_channels_of_own_tenant() {
+        let auth = auth(&[roles::USER]);
+        let mut subscriptions = Subscriptions::new(auth.clone());
+        handle_client_message(&mut subscriptions, r#"{"type":"subscribe","channels":["orders"]}"#);
+
+        assert!(subscriptions.accepts(&notification(&auth, NotificationChannel::Orders)));
+        assert!(!subscriptions.accepts(&notification(&auth, NotificationChannel::Payments)));
+
+        let mut other_tenant = notification(&auth, NotificationChannel::Orders);
+        other_tenant.tenant_id = Uuid::new_v4();
+        assert!(!subscriptions.accepts(&other_tenant));
+    }
+
+    #[test]
+    fn test_users_only_receive_their_own_resources() {
+        let user = auth(&[roles::USER]);
+        let mut subscriptions = Subscriptions::new(user.clone());
+        subscriptions.channels.insert(NotificationChannel::Orders);
+
+        let own = notification(&user, NotificationChannel::Orders).with_user(user.user_id);
+        let foreign = notification(&user, NotificationChannel::Orders).with_user(Uuid::new_v4());
+        assert!(subscriptions.accepts(&own));
+        assert!(!subscriptions.accepts(&foreign));
+
+        let admin = AuthContext { roles: vec![roles::ADMIN.to_string()], ..user };
+        let mut subscriptions = Subscriptions::new(admin);
+        subscriptions.channels.insert(NotificationChannel::Orders);
+        assert!(subscriptions.accepts(&foreign));
+    }
+
+    #[test]
+    fn test_client_messages() {
+        let mut subscriptions = Subscriptions::new(auth(&[]));
+
+        handle_client_message(&mut subscriptions, r#"{"type":"subscribe","channels":["orders","jobs"]}"#);
+        handle_client_message(&mut subscriptions, r#"{"type":"unsubscribe","channels":["orders"]}"#);
+        assert_eq!(subscriptions.current(), vec![NotificationChannel::Jobs]);
+
+        let reply = handle_client_message(&mut subscriptions, r#"{"type":"subscribe","channels":["nope"]}"#);
+        assert!(matches!(reply, Some(Message::Text(text)) if text.contains("\"type\":\"error\"")));
+    }
+
+    #[test]
+    fn test_protocol_token() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(protocol_token(&headers), None);
+
+        headers.insert(SEC_WEBSOCKET_PROTOCOL, "bearer, abc.def.ghi".parse().unwrap());
+        assert_eq!(protocol_token(&headers), Some("abc.def.ghi"));
+
+        headers.insert(SEC_WEBSOCKET_PROTOCOL, "abc.def.ghi".parse().unwrap());
+        assert_eq!(protocol_token(&headers), None);
+    }
+}
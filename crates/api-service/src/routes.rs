@@ -1,87 +1,517 @@
 //! API routes configuration
 
 use axum::{
-    routing::{get, post},
-    Router,
+    extract::Request,
+    handler::Handler,
+    http::Method,
+    routing::{get, on, post, MethodFilter},
+    Extension, Router,
 };
+use shared::{AppResult, API_PREFIX};
 use tower::ServiceBuilder;
-use tower_http::{
-    compression::CompressionLayer,
-    cors::CorsLayer,
-    limit::RequestBodyLimitLayer,
-    timeout::TimeoutLayer,
-    trace::TraceLayer,
-};
+use tower_http::{compression::CompressionLayer, trace::TraceLayer};
+use utoipa_swagger_ui::SwaggerUi;
 
 use crate::{
-    handlers::{auth, health, users},
-    middleware::{auth::AuthMiddleware, logging::LoggingMiddleware, metrics::MetricsMiddleware},
+    config::{ApiSettings, VersioningSettings},
+    graphql,
+    handlers::{audit_logs, auth, events, files, health, jobs, users},
+    middleware::{
+        auth::AuthMiddleware, correlation::CorrelationIdMiddleware, cors::ReloadableCorsLayer,
+        limits::RequestLimitsLayer, logging::{redacted_uri, LoggingMiddleware}, metrics::MetricsMiddleware,
+        rate_limit::RateLimitLayer, versioning::ApiVersionLayer,
+    },
+    openapi, realtime,
     state::AppState,
 };
 
-/// Create application routes
-pub fn create_routes(state: AppState) -> Router {
+/// Path the GraphQL endpoint is served from
+pub const GRAPHQL_PATH: &str = "/graphql";
+
+/// Path of the notification WebSocket within each API version
+pub const WS_PATH: &str = "/ws";
+
+/// Versions with route functions; every entry of `api.versioning.versions` must be one of these
+pub const SERVED_VERSIONS: [&str; 1] = ["v1"];
+
+/// Root of an API version, e.g. `/api/v1`
+pub fn version_path(version: &str) -> String {
+    format!("{}/{}", API_PREFIX, version)
+}
+
+/// Path the OpenAPI document of a version is served from
+pub fn openapi_path(version: &str) -> String {
+    format!("{}/openapi.json", version_path(version))
+}
+
+/// Path the Swagger UI of a version is served from
+pub fn docs_path(version: &str) -> String {
+    format!("{}/docs", version_path(version))
+}
+
+/// A route registered through [`ApiRouter`]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct RouteDefinition {
+    /// Upper-case HTTP method
+    pub method: String,
+    /// Full axum path, e.g. `/api/v1/users/:id`
+    pub path: String,
+    /// Whether the route is expected to appear in the OpenAPI document
+    pub documented: bool,
+}
+
+impl RouteDefinition {
+    /// Path in OpenAPI template form, e.g. `/api/v1/users/{id}`
+    pub fn openapi_path(&self) -> String {
+        self.path
+            .split('/')
+            .map(|segment| match segment.strip_prefix(':') {
+                Some(param) => format!("{{{}}}", param),
+                None => segment.to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+}
+
+/// Router wrapper that records every method and path it registers,
+/// so the route table can be compared against the OpenAPI document
+pub struct ApiRouter<S = AppState> {
+    router: Router<S>,
+    routes: Vec<RouteDefinition>,
+}
+
+impl<S> ApiRouter<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    pub fn new() -> Self {
+        Self {
+            router: Router::new(),
+            routes: Vec::new(),
+        }
+    }
+
+    /// Register a handler for a single method on a path
+    pub fn route<H, T>(mut self, method: Method, path: &str, handler: H) -> Self
+    where
+        H: Handler<T, S>,
+        T: 'static,
+    {
+        let filter = MethodFilter::try_from(method.clone())
+            .unwrap_or_else(|_| panic!("Unsupported HTTP method for route {}: {}", path, method));
+
+        self.router = self.router.route(path, on(filter, handler));
+        self.routes.push(RouteDefinition {
+            method: method.as_str().to_string(),
+            path: path.to_string(),
+            documented: true,
+        });
+        self
+    }
+
+    /// Merge a router whose routes are deliberately left out of the OpenAPI document,
+    /// recording the `(method, path)` pairs it serves
+    pub fn merge_undocumented(mut self, routes: &[(Method, &str)], router: Router<S>) -> Self {
+        self.router = self.router.merge(router);
+        self.routes.extend(routes.iter().map(|(method, path)| RouteDefinition {
+            method: method.as_str().to_string(),
+            path: path.to_string(),
+            documented: false,
+        }));
+        self
+    }
+
+    /// Merge another recorded router into this one
+    pub fn merge(mut self, other: ApiRouter<S>) -> Self {
+        self.router = self.router.merge(other.router);
+        self.routes.extend(other.routes);
+        self
+    }
+
+    /// Nest another recorded router under a path prefix
+    pub fn nest(mut self, prefix: &str, other: ApiRouter<S>) -> Self {
+        self.router = self.router.nest(prefix, other.router);
+        self.routes.extend(other.routes.into_iter().map(|route| RouteDefinition {
+            method: route.method,
+            path: format!("{}{}", prefix, route.path),
+            documented: route.documented,
+        }));
+        self
+    }
+
+    /// Transform the underlying router, e.g. to apply a layer to this group of routes
+    pub fn map_router(mut self, f: impl FnOnce(Router<S>) -> Router<S>) -> Self {
+        self.router = f(self.router);
+        self
+    }
+
+    /// Routes registered so far
+    pub fn definitions(&self) -> &[RouteDefinition] {
+        &self.routes
+    }
+
+    /// Split into the axum router and the recorded route table
+    pub fn into_parts(self) -> (Router<S>, Vec<RouteDefinition>) {
+        (self.router, self.routes)
+    }
+}
+
+impl<S> Default for ApiRouter<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Health check routes (no auth required)
+fn health_routes() -> ApiRouter {
+    ApiRouter::new()
+        .route(Method::GET, "/health", health::health_check)
+        .route(Method::GET, "/ready", health::readiness_check)
+        .route(Method::GET, "/live", health::liveness_check)
+}
+
+/// Authentication routes (no auth required)
+fn auth_routes() -> ApiRouter {
+    ApiRouter::new()
+        .route(Method::POST, "/auth/login", auth::login)
+        .route(Method::POST, "/auth/register", auth::register)
+        .route(Method::POST, "/auth/refresh", auth::refresh_token)
+        .route(Method::POST, "/auth/logout", auth::logout)
+}
+
+/// User management routes (auth required)
+fn user_routes() -> ApiRouter {
+    ApiRouter::new()
+        .route(Method::GET, "/users", users::list_users)
+        .route(Method::POST, "/users", users::create_user)
+        .route(Method::GET, "/users/:id", users::get_user)
+        .route(Method::PUT, "/users/:id", users::update_user)
+        .route(Method::DELETE, "/users/:id", users::delete_user)
+        .route(Method::GET, "/users/:id/profile", users::get_user_profile)
+        .route(Method::PUT, "/users/:id/profile", users::update_user_profile)
+}
+
+/// Domain event stream routes (auth required)
+fn event_routes() -> ApiRouter {
+    ApiRouter::new()
+        .route(Method::GET, "/events/stream", events::stream_events)
+}
+
+/// Audit log routes (auth and the audit:read permission required)
+fn audit_routes() -> ApiRouter {
+    ApiRouter::new()
+        .route(Method::GET, "/audit-logs", audit_logs::list_audit_logs)
+        .route(Method::GET, "/audit-logs/export", audit_logs::export_audit_logs)
+}
+
+/// File routes (auth required)
+fn file_routes() -> ApiRouter {
+    ApiRouter::new()
+        .route(Method::POST, "/files", files::upload_file)
+        .route(Method::GET, "/files/:id", files::get_file)
+        .route(Method::DELETE, "/files/:id", files::delete_file)
+}
+
+/// Background job routes (auth and the admin role required)
+fn job_routes() -> ApiRouter {
+    ApiRouter::new()
+        .route(Method::GET, "/jobs", jobs::list_jobs)
+        .route(Method::POST, "/jobs", jobs::enqueue_job)
+        .route(Method::GET, "/jobs/:id", jobs::get_job)
+        .route(Method::POST, "/jobs/:id/cancel", jobs::cancel_job)
+        .route(Method::POST, "/jobs/:id/retry", jobs::retry_job)
+}
+
+/// Signed file download routes (no auth required; the link carries the authorization)
+fn download_routes() -> ApiRouter {
+    ApiRouter::new()
+        .route(Method::GET, "/files/:id/download", files::download_file)
+}
+
+/// API documentation routes of a version (no auth required, not part of the OpenAPI document)
+fn docs_routes(version: &str, doc: utoipa::openapi::OpenApi) -> ApiRouter {
+    let docs_path = docs_path(version);
+    let openapi_path = openapi_path(version);
+    let assets_path = format!("{}/*rest", docs_path);
+
+    ApiRouter::new().merge_undocumented(
+        &[
+            (Method::GET, docs_path.as_str()),
+            (Method::GET, assets_path.as_str()),
+            (Method::GET, openapi_path.as_str()),
+        ],
+        SwaggerUi::new(docs_path.clone()).url(openapi_path.clone(), doc).into(),
+    )
+}
+
+/// GraphQL endpoint (auth required); the playground is served alongside the docs
+fn graphql_routes(settings: &ApiSettings, protect: impl FnOnce(Router<AppState>) -> Router<AppState>) -> ApiRouter {
+    let graphql_settings = &settings.graphql;
+    let mut served = vec![(Method::POST, GRAPHQL_PATH)];
+    let mut router = protect(Router::new().route(GRAPHQL_PATH, post(graphql::graphql_handler)));
+
+    if settings.enable_docs && graphql_settings.enable_playground {
+        served.push((Method::GET, GRAPHQL_PATH));
+        router = router.route(GRAPHQL_PATH, get(graphql::graphiql));
+    }
+
+    ApiRouter::new().merge_undocumented(
+        &served,
+        router.layer(Extension(graphql::build_schema(graphql_settings))),
+    )
+}
+
+/// Real-time notifications; the handler authenticates the upgrade request itself
+fn websocket_routes() -> ApiRouter {
+    ApiRouter::new().merge_undocumented(
+        &[(Method::GET, WS_PATH)],
+        Router::new().route(WS_PATH, get(realtime::ws_handler)),
+    )
+}
+
+/// Version 1 of the API, served under `API_BASE_PATH`
+fn v1_routes(protect: impl FnOnce(Router<AppState>) -> Router<AppState>) -> ApiRouter {
+    let api_routes = ApiRouter::new()
+        .merge(user_routes())
+        .merge(event_routes())
+        .merge(audit_routes())
+        .merge(file_routes())
+        .merge(job_routes())
+        .map_router(protect);
+
+    ApiRouter::new()
+        .merge(auth_routes())
+        .merge(download_routes())
+        .merge(api_routes)
+}
+
+/// Routes of an API version, or `None` for a version this build does not serve.
+///
+/// A new version gets its own route function here, an entry in [`SERVED_VERSIONS`] and an
+/// entry in `api.versioning.versions`; handlers it does not change can be shared with v1.
+fn version_routes(version: &str, protect: impl FnOnce(Router<AppState>) -> Router<AppState>) -> Option<ApiRouter> {
+    match version {
+        "v1" => Some(v1_routes(protect)),
+        _ => None,
+    }
+}
+
+/// OpenAPI document describing an API version, if it has one
+fn version_doc(version: &str, settings: &ApiSettings) -> Option<utoipa::openapi::OpenApi> {
+    match version {
+        "v1" => Some(openapi::api_doc(settings)),
+        _ => None,
+    }
+}
+
+/// Nest the routes of every configured version under `/api/{version}`.
+///
+/// Versions without routes are skipped; configuration validation rejects them.
+fn nest_versions<S>(versioning: &VersioningSettings, mut routes_for: impl FnMut(&str) -> Option<ApiRouter<S>>) -> ApiRouter<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    versioning
+        .versions
+        .iter()
+        .filter_map(|version| Some((version_path(&version.name), routes_for(&version.name)?)))
+        .fold(ApiRouter::new(), |api, (prefix, routes)| api.nest(&prefix, routes))
+}
+
+/// Assemble all routes enabled by `settings`; `protect` is applied to routes that require authentication.
+///
+/// Every version in `api.versioning.versions` is nested under `/api/{version}` with its own
+/// WebSocket endpoint and, when it has an OpenAPI document, its own docs.
+fn build_api(settings: &ApiSettings, protect: impl Fn(Router<AppState>) -> Router<AppState>) -> ApiRouter {
+    let versions = nest_versions(&settings.versioning, |version| {
+        let mut routes = version_routes(version, &protect)?;
+        if settings.websocket.enabled {
+            routes = routes.merge(websocket_routes());
+        }
+        Some(routes)
+    });
+
+    let mut api = ApiRouter::new().merge(health_routes()).merge(versions);
+
+    if settings.enable_docs {
+        for version in &settings.versioning.versions {
+            if let Some(doc) = version_doc(&version.name, settings) {
+                api = api.merge(docs_routes(&version.name, doc));
+            }
+        }
+    }
+    if settings.graphql.enabled {
+        api = api.merge(graphql_routes(settings, &protect));
+    }
+
+    api
+}
+
+/// Every route served by the API with the given settings, as registered with the router
+pub fn route_definitions(settings: &ApiSettings) -> Vec<RouteDefinition> {
+    build_api(settings, |router| router).into_parts().1
+}
+
+/// Create application routes, with the middleware stack built from the API settings.
+///
+/// Rate limits and CORS follow configuration reloads; the rest of the stack is fixed at startup.
+pub fn create_routes(state: AppState) -> AppResult<Router> {
     let config = state.config();
+    let settings = &config.api;
+    let versioning = ApiVersionLayer::new(settings, &config.app.metrics.namespace);
+
+    let auth_layer = AuthMiddleware::new(state.clone());
+    let (mut router, _) = build_api(settings, |router| router.layer(auth_layer.clone())).into_parts();
 
-    // Create middleware stack
-    let middleware = ServiceBuilder::new()
-        .layer(TraceLayer::new_for_http())
-        .layer(CompressionLayer::new())
-        .layer(TimeoutLayer::new(config.server.client_timeout.into()))
-        .layer(RequestBodyLimitLayer::new(10 * 1024 * 1024)) // 10MB limit
-        .layer(MetricsMiddleware::new())
-        .layer(LoggingMiddleware::new())
-        .layer(CorsLayer::permissive()); // Configure CORS as needed
-
-    // Health check routes (no auth required)
-    let health_routes = Router::new()
-        .route("/health", get(health::health_check))
-        .route("/ready", get(health::readiness_check))
-        .route("/live", get(health::liveness_check));
-
-    // Authentication routes (no auth required)
-    let auth_routes = Router::new()
-        .route("/auth/login", post(auth::login))
-        .route("/auth/register", post(auth::register))
-        .route("/auth/refresh", post(auth::refresh_token))
-        .route("/auth/logout", post(auth::logout));
-
-    // Protected API routes (auth required)
-    let api_routes = Router::new()
-        .route("/users", get(users::list_users).post(users::create_user))
-        .route("/users/:id", get(users::get_user).put(users::update_user).delete(users::delete_user))
-        .route("/users/:id/profile", get(users::get_user_profile).put(users::update_user_profile))
-        .layer(AuthMiddleware::new(state.clone()));
-
-    // Combine all routes
-    Router::new()
-        .merge(health_routes)
-        .nest("/api/v1", Router::new()
-            .merge(auth_routes)
-            .merge(api_routes)
-        )
-        .layer(middleware)
-        .with_state(state)
+    // Middleware is applied inside out: each layer wraps everything added before it
+    router = router
+        .layer(RateLimitLayer::new(state.clone()))
+        .layer(ReloadableCorsLayer::new(state.config_handle())?);
+    if settings.enable_request_logging {
+        router = router.layer(LoggingMiddleware::new());
+    }
+    router = router
+        .layer(MetricsMiddleware::new(&config.app.metrics))
+        .layer(RequestLimitsLayer::new(&config));
+    if settings.enable_compression {
+        router = router.layer(CompressionLayer::new());
+    }
+
+    // Versioning rewrites unversioned paths, so it has to run before the request is routed
+    Ok(Router::new()
+        .fallback_service(router.with_state(state))
+        .layer(versioning)
+        .layer(
+            ServiceBuilder::new()
+                .layer(CorrelationIdMiddleware::new())
+                // Same span as the default, with credentials stripped from the logged URI
+                .layer(TraceLayer::new_for_http().make_span_with(|request: &Request| {
+                    tracing::debug_span!(
+                        "request",
+                        method = %request.method(),
+                        uri = %redacted_uri(request.uri()),
+                        version = ?request.version(),
+                    )
+                })),
+        ))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use axum::http::StatusCode;
-    use axum_test::TestServer;
-    use shared::AppConfig;
+    use crate::config::ApiVersionSettings;
+    use axum::{body::Body, http::StatusCode};
+    use chrono::{TimeZone, Utc};
+    use shared::{API_BASE_PATH, API_VERSION_HEADER, DEPRECATION_HEADER};
+    use tower::ServiceExt;
+
+    #[test]
+    fn test_route_definitions_are_prefixed() {
+        let routes = route_definitions(&ApiSettings::default());
+
+        assert!(routes.iter().any(|r| r.method == "GET" && r.path == "/health"));
+        assert!(routes.iter().any(|r| r.method == "GET" && r.path == "/api/v1/users/:id"));
+        assert!(routes
+            .iter()
+            .filter(|r| r.documented)
+            .all(|r| r.path == "/health" || r.path == "/ready" || r.path == "/live" || r.path.starts_with(API_BASE_PATH)));
+    }
+
+    #[test]
+    fn test_openapi_path_conversion() {
+        let route = RouteDefinition {
+            method: "GET".to_string(),
+            path: "/api/v1/users/:id/profile".to_string(),
+            documented: true,
+        };
+        assert_eq!(route.openapi_path(), "/api/v1/users/{id}/profile");
+    }
+
+    #[test]
+    fn test_optional_routes_follow_settings() {
+        let undocumented = |settings: &ApiSettings| -> Vec<(String, String)> {
+            route_definitions(settings)
+                .into_iter()
+                .filter(|route| !route.documented)
+                .map(|route| (route.method, route.path))
+                .collect()
+        };
+
+        let mut settings = ApiSettings::default();
+        let routes = undocumented(&settings);
+        assert!(routes.contains(&("POST".to_string(), GRAPHQL_PATH.to_string())));
+        assert!(routes.contains(&("GET".to_string(), "/api/v1/ws".to_string())));
+        assert!(routes.contains(&("GET".to_string(), openapi_path("v1"))));
+
+        settings.enable_docs = false;
+        settings.graphql.enabled = false;
+        settings.websocket.enabled = false;
+        assert!(undocumented(&settings).is_empty());
+    }
+
+    #[test]
+    fn test_every_served_version_has_routes() {
+        for version in SERVED_VERSIONS {
+            assert!(version_routes(version, |router| router).is_some(), "{} has no routes", version);
+        }
+        assert!(version_routes("v9", |router| router).is_none());
+    }
 
     #[tokio::test]
-    async fn test_health_routes() {
-        let config = AppConfig::default();
-        
-        // This test would require running database and Redis instances
-        // In a real test environment, you would use testcontainers
-        // let state = AppState::new(config).await.unwrap();
-        // let app = create_routes(state);
-        // let server = TestServer::new(app).unwrap();
-        
-        // let response = server.get("/health").await;
-        // assert_eq!(response.status_code(), StatusCode::OK);
+    async fn test_versions_are_routed_side_by_side() {
+        let mut settings = ApiSettings::default();
+        settings.versioning.versions[0].deprecated_at = Some(Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap());
+        settings.versioning.versions.push(ApiVersionSettings {
+            name: "v2".to_string(),
+            deprecated_at: None,
+            sunset_at: None,
+            deprecation_link: None,
+        });
+
+        // Each version answers with its own name, standing in for its handlers
+        let api = nest_versions(&settings.versioning, |version| {
+            let name = version.to_string();
+            Some(ApiRouter::<()>::new().route(Method::GET, "/ping", move || async move { name }))
+        });
+        let paths: Vec<_> = api.definitions().iter().map(|route| route.path.as_str()).collect();
+        assert_eq!(paths, vec!["/api/v1/ping", "/api/v2/ping"]);
+
+        let app = Router::new()
+            .fallback_service(api.into_parts().0)
+            .layer(ApiVersionLayer::new(&settings, "test"));
+        let call = |uri: &str, version: Option<&str>| {
+            let mut request = axum::http::Request::builder().uri(uri);
+            if let Some(version) = version {
+                request = request.header(API_VERSION_HEADER, version);
+            }
+            app.clone().oneshot(request.body(Body::empty()).unwrap())
+        };
+        let body = |response: axum::response::Response| async move {
+            let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+            String::from_utf8(bytes.to_vec()).unwrap()
+        };
+
+        // v1 is deprecated, v2 is current
+        let v1 = call("/api/v1/ping", None).await.unwrap();
+        assert_eq!(v1.status(), StatusCode::OK);
+        assert_eq!(v1.headers()[API_VERSION_HEADER], "v1");
+        assert!(v1.headers().contains_key(DEPRECATION_HEADER));
+        assert_eq!(body(v1).await, "v1");
+
+        let v2 = call("/api/v2/ping", None).await.unwrap();
+        assert_eq!(v2.headers()[API_VERSION_HEADER], "v2");
+        assert!(!v2.headers().contains_key(DEPRECATION_HEADER));
+        assert_eq!(body(v2).await, "v2");
+
+        // Unversioned paths follow the header, then the default version
+        assert_eq!(body(call("/api/ping", Some("2")).await.unwrap()).await, "v2");
+        assert_eq!(body(call("/api/ping", None).await.unwrap()).await, "v1");
+
+        let unknown = call("/api/v3/ping", None).await.unwrap();
+        assert_eq!(unknown.status(), StatusCode::BAD_REQUEST);
     }
 }
\ No newline at end of file
@@ -0,0 +1,302 @@
+//! Audit log access for compliance reviews
+
+use database::{access_event, AuditLog, AuditLogFilter, AuditLogRepository, PgAuditLogger};
+use futures::{stream, Stream};
+use serde::{Deserialize, Serialize};
+use shared::{permissions, AppError, AppResult, PaginatedResponse, PaginationInfo, TenantId};
+use std::borrow::Cow;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{middleware::auth::AuthContext, state::AppState};
+
+/// `resource_type` of audit entries recording access to the audit log itself
+pub const AUDIT_LOG_RESOURCE: &str = "audit_log";
+
+/// Entries fetched per query while exporting
+const EXPORT_BATCH_SIZE: i64 = 500;
+
+/// Columns of CSV exports, in order
+const CSV_COLUMNS: [&str; 11] = [
+    "id",
+    "created_at",
+    "user_id",
+    "action",
+    "resource_type",
+    "resource_id",
+    "old_values",
+    "new_values",
+    "ip_address",
+    "user_agent",
+    "correlation_id",
+];
+
+/// Audit log export file format
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    /// Comma separated values with a header row
+    #[default]
+    Csv,
+    /// One JSON object per line
+    Ndjson,
+}
+
+impl ExportFormat {
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "text/csv; charset=utf-8",
+            ExportFormat::Ndjson => "application/x-ndjson",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Ndjson => "ndjson",
+        }
+    }
+}
+
+/// Tenant-scoped audit log queries, restricted to callers holding
+/// [`permissions::AUDIT_READ`]
+pub struct AuditService {
+    entries: AuditLogRepository,
+    audit: PgAuditLogger,
+}
+
+impl AuditService {
+    pub fn new(state: &AppState) -> Self {
+        Self {
+            entries: AuditLogRepository::new(state.database().pool().clone()),
+            audit: state.audit().clone(),
+        }
+    }
+
+    /// Page through the caller's tenant's audit log, newest first.
+    ///
+    /// `cursor` is the `next_cursor` of the previous page.
+    pub async fn list(
+        &self,
+        auth: &AuthContext,
+        filter: &AuditLogFilter,
+        cursor: Option<&str>,
+        limit: u32,
+    ) -> AppResult<PaginatedResponse<AuditLog>> {
+        auth.ensure_permission(permissions::AUDIT_READ)?;
+        let before = cursor.map(decode_cursor).transpose()?;
+
+        // One entry more than requested tells whether another page follows
+        let mut entries = self
+            .entries
+            .find_before(&auth.tenant_id, filter, before, limit as i64 + 1)
+            .await?;
+        let has_next = entries.len() > limit as usize;
+        entries.truncate(limit as usize);
+
+        let next_cursor = entries.last().filter(|_| has_next).map(encode_cursor);
+        Ok(PaginatedResponse {
+            data: entries,
+            pagination: PaginationInfo {
+                total: None,
+                limit,
+                offset: 0,
+                has_next,
+                has_prev: before.is_some(),
+                next_cursor,
+                prev_cursor: None,
+            },
+        })
+    }
+
+    /// Stream the caller's tenant's audit entries between `filter.from` and `filter.to`,
+    /// oldest first.
+    ///
+    /// The export is itself written to the audit log before any entry is returned, and
+    /// refused if it cannot be written.
+    pub async fn export(
+        self,
+        auth: &AuthContext,
+        filter: AuditLogFilter,
+        format: ExportFormat,
+    ) -> AppResult<impl Stream<Item = AppResult<String>>> {
+        auth.ensure_permission(permissions::AUDIT_READ)?;
+        match (filter.from, filter.to) {
+            (Some(from), Some(to)) if from < to => {}
+            _ => return Err(AppError::Validation("An export needs a time range with from before to".to_string())),
+        }
+
+        let details = serde_json::json!({ "format": format, "filter": filter });
+        self.audit
+            .log_now(&access_event("export", AUDIT_LOG_RESOURCE, auth.tenant_id, details))
+            .await?;
+
+        let export = Export {
+            entries: self.entries,
+            tenant_id: auth.tenant_id,
+            filter,
+            format,
+            position: None,
+            started: false,
+            finished: false,
+        };
+        Ok(export.into_stream())
+    }
+}
+
+/// Walks the matching entries in `(created_at, id)` order, rendering a batch at a time
+struct Export {
+    entries: AuditLogRepository,
+    tenant_id: TenantId,
+    filter: AuditLogFilter,
+    format: ExportFormat,
+    position: Option<(chrono::DateTime<chrono::Utc>, Uuid)>,
+    started: bool,
+    finished: bool,
+}
+
+impl Export {
+    fn into_stream(self) -> impl Stream<Item = AppResult<String>> {
+        stream::try_unfold(self, |mut export| async move {
+            let chunk = export.next_chunk().await?;
+            Ok(chunk.map(|chunk| (chunk, export)))
+        })
+    }
+
+    /// Render the next batch, or `None` once every entry was written
+    async fn next_chunk(&mut self) -> AppResult<Option<String>> {
+        if self.finished {
+            return Ok(None);
+        }
+
+        let mut chunk = String::new();
+        if !self.started && self.format == ExportFormat::Csv {
+            chunk.push_str(&CSV_COLUMNS.join(","));
+            chunk.push('\n');
+        }
+        self.started = true;
+
+        let batch = self
+            .entries
+            .find_after(&self.tenant_id, &self.filter, self.position, EXPORT_BATCH_SIZE)
+            .await?;
+        self.finished = (batch.len() as i64) < EXPORT_BATCH_SIZE;
+        self.position = batch.last().map(|entry| (entry.created_at, entry.id)).or(self.position);
+
+        for entry in &batch {
+            match self.format {
+                ExportFormat::Csv => chunk.push_str(&csv_row(entry)),
+                ExportFormat::Ndjson => {
+                    let line = serde_json::to_string(entry)
+                        .map_err(|e| AppError::Internal(format!("Failed to serialize audit entry: {}", e)))?;
+                    chunk.push_str(&line);
+                    chunk.push('\n');
+                }
+            }
+        }
+
+        Ok((!chunk.is_empty()).then_some(chunk))
+    }
+}
+
+fn csv_row(entry: &AuditLog) -> String {
+    let optional = |value: Option<String>| value.unwrap_or_default();
+    let fields = [
+        entry.id.to_string(),
+        entry.created_at.to_rfc3339(),
+        optional(entry.user_id.map(|id| id.to_string())),
+        entry.action.clone(),
+        entry.resource_type.clone(),
+        optional(entry.resource_id.map(|id| id.to_string())),
+        optional(entry.old_values.as_ref().map(|values| values.to_string())),
+        optional(entry.new_values.as_ref().map(|values| values.to_string())),
+        optional(entry.ip_address.clone()),
+        optional(entry.user_agent.clone()),
+        entry.correlation_id.to_string(),
+    ];
+
+    let mut row = fields.iter().map(|field| csv_field(field)).collect::<Vec<_>>().join(",");
+    row.push('\n');
+    row
+}
+
+/// Quote a CSV field when needed.
+///
+/// Fields that a spreadsheet would evaluate as a formula, such as a crafted user agent, are
+/// prefixed with `'`, including formulas behind a leading tab or carriage return.
+fn csv_field(value: &str) -> Cow<'_, str> {
+    let value: Cow<'_, str> = if value.starts_with(['=', '+', '-', '@', '\t', '\r']) {
+        Cow::Owned(format!("'{}", value))
+    } else {
+        Cow::Borrowed(value)
+    };
+
+    if value.contains([',', '"', '\n', '\r']) {
+        Cow::Owned(format!("\"{}\"", value.replace('"', "\"\"")))
+    } else {
+        value
+    }
+}
+
+/// Opaque keyset position of an entry
+fn encode_cursor(entry: &AuditLog) -> String {
+    hex::encode(format!("{}|{}", entry.created_at.to_rfc3339(), entry.id))
+}
+
+fn decode_cursor(cursor: &str) -> AppResult<(chrono::DateTime<chrono::Utc>, Uuid)> {
+    let invalid = || AppError::BadRequest("Invalid cursor".to_string());
+    let decoded = hex::decode(cursor).map_err(|_| invalid())?;
+    let decoded = String::from_utf8(decoded).map_err(|_| invalid())?;
+    let (created_at, id) = decoded.split_once('|').ok_or_else(invalid)?;
+
+    let created_at = chrono::DateTime::parse_from_rfc3339(created_at).map_err(|_| invalid())?;
+    let id = id.parse::<Uuid>().map_err(|_| invalid())?;
+    Ok((created_at.with_timezone(&chrono::Utc), id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn entry() -> AuditLog {
+        AuditLog {
+            id: Uuid::new_v4(),
+            tenant_id: Some(Uuid::new_v4()),
+            user_id: Some(Uuid::new_v4()),
+            action: "update".to_string(),
+            resource_type: "user".to_string(),
+            resource_id: Some(Uuid::new_v4()),
+            old_values: Some(serde_json::json!({ "first_name": "Jane", "last_name": "Doe" })),
+            new_values: Some(serde_json::json!({ "first_name": "Janet", "last_name": "Doe" })),
+            ip_address: Some("203.0.113.7".to_string()),
+            user_agent: Some("=HYPERLINK(\"http://example.com\")".to_string()),
+            correlation_id: Uuid::new_v4(),
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_cursor_round_trip() {
+        let entry = entry();
+        let (created_at, id) = decode_cursor(&encode_cursor(&entry)).unwrap();
+
+        assert_eq!((created_at, id), (entry.created_at, entry.id));
+        assert!(matches!(decode_cursor("zz"), Err(AppError::BadRequest(_))));
+        assert!(matches!(decode_cursor(&hex::encode("no separator")), Err(AppError::BadRequest(_))));
+    }
+
+    #[test]
+    fn test_csv_row_quotes_and_defuses_fields() {
+        let entry = entry();
+        let row = csv_row(&entry);
+
+        assert_eq!(row.matches('\n').count(), 1);
+        assert!(row.contains(r#""{""first_name"":""Jane"",""last_name"":""Doe""}""#));
+        assert!(row.contains(r#""'=HYPERLINK(""http://example.com"")""#));
+        assert_eq!(csv_field("plain"), "plain");
+        assert_eq!(csv_field("-1"), "'-1");
+        assert_eq!(csv_field("\t=1+1"), "'\t=1+1");
+        assert_eq!(csv_field("\r=1+1"), "\"'\r=1+1\"");
+    }
+}
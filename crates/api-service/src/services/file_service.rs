@@ -0,0 +1,175 @@
+//! File uploads and downloads
+
+use bytes::Bytes;
+use chrono::Utc;
+use database::{FileRepository, StoredFile};
+use sha2::{Digest, Sha256};
+use shared::{AppError, AppResult};
+use std::sync::Arc;
+use storage::{sniff_content_type, DownloadSignature, Storage, UrlSigner};
+use tracing::{error, warn};
+use uuid::Uuid;
+
+use crate::{config::ApiConfig, middleware::auth::AuthContext, state::AppState};
+
+/// Longest file name kept, in characters
+const MAX_FILE_NAME_LENGTH: usize = 255;
+
+/// Tenant-owned files, with contents in the configured storage backend
+pub struct FileService {
+    files: FileRepository,
+    storage: Arc<dyn Storage>,
+    signer: UrlSigner,
+    config: Arc<ApiConfig>,
+}
+
+impl FileService {
+    pub fn new(state: &AppState) -> Self {
+        Self {
+            files: FileRepository::new(state.database().pool().clone()),
+            storage: state.storage().clone(),
+            signer: state.url_signer().clone(),
+            config: state.config(),
+        }
+    }
+
+    /// Store a file for the caller's tenant.
+    ///
+    /// The content type is detected from the contents and must be one of
+    /// `storage.allowed_content_types`; whatever the client declared is ignored.
+    pub async fn upload(&self, auth: &AuthContext, file_name: &str, data: Bytes) -> AppResult<StoredFile> {
+        let settings = &self.config.app.storage;
+        if data.len() > settings.max_upload_size {
+            return Err(AppError::PayloadTooLarge(format!(
+                "Files cannot be larger than {} bytes",
+                settings.max_upload_size
+            )));
+        }
+
+        let content_type = match sniff_content_type(&data) {
+            Some(content_type) if settings.allowed_content_types.iter().any(|allowed| allowed == content_type) => {
+                content_type
+            }
+            Some(content_type) => {
+                return Err(AppError::Validation(format!("Files of type {} are not accepted", content_type)))
+            }
+            None => return Err(AppError::Validation("The file is empty or of an unrecognized type".to_string())),
+        };
+
+        let now = Utc::now();
+        let id = shared::generate_uuid();
+        let file = StoredFile {
+            id,
+            tenant_id: auth.tenant_id,
+            uploaded_by: Some(auth.user_id),
+            file_name: sanitize_file_name(file_name),
+            content_type: content_type.to_string(),
+            size_bytes: data.len() as i64,
+            checksum_sha256: hex::encode(Sha256::digest(&data)),
+            storage_key: format!("{}/{}", auth.tenant_id, id),
+            created_at: now,
+            updated_at: now,
+            deleted_at: None,
+        };
+
+        self.storage.put(&file.storage_key, data, content_type).await?;
+        match self.files.create(&file).await {
+            Ok(created) => Ok(created),
+            Err(e) => {
+                // Don't leave contents behind that no row refers to
+                if let Err(cleanup) = self.storage.delete(&file.storage_key).await {
+                    warn!(storage_key = %file.storage_key, "Failed to remove orphaned upload: {}", cleanup);
+                }
+                Err(e)
+            }
+        }
+    }
+
+    /// Get a file of the caller's tenant
+    pub async fn get(&self, auth: &AuthContext, id: &Uuid) -> AppResult<StoredFile> {
+        let file = self
+            .files
+            .find_by_id(id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("File not found".to_string()))?;
+
+        auth.ensure_tenant(&file.tenant_id, "File")?;
+        Ok(file)
+    }
+
+    /// Delete a file of the caller's tenant; only its uploader or an administrator may.
+    ///
+    /// The metadata row is kept, soft-deleted, while the contents are removed from storage.
+    pub async fn delete(&self, auth: &AuthContext, id: &Uuid) -> AppResult<bool> {
+        let file = self.get(auth, id).await?;
+        if file.uploaded_by != Some(auth.user_id) && !auth.is_admin() {
+            return Err(AppError::Authorization("Only the uploader or an administrator can delete a file".to_string()));
+        }
+
+        let deleted = self.files.delete(id).await?;
+        if deleted {
+            if let Err(e) = self.storage.delete(&file.storage_key).await {
+                warn!(file_id = %id, "Failed to remove deleted file from {} storage: {}", self.storage.name(), e);
+            }
+        }
+        Ok(deleted)
+    }
+
+    /// Sign a time-limited download link for a file
+    pub fn sign_download(&self, file: &StoredFile) -> DownloadSignature {
+        self.signer.sign(&file.id)
+    }
+
+    /// Read a file through a signed download link
+    pub async fn download(&self, id: &Uuid, expires: i64, signature: &str) -> AppResult<(StoredFile, Bytes)> {
+        self.signer.verify(id, expires, signature)?;
+
+        let file = self
+            .files
+            .find_by_id(id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("File not found".to_string()))?;
+        let data = self.storage.get(&file.storage_key).await?.ok_or_else(|| {
+            error!(file_id = %id, "File contents missing from {} storage", self.storage.name());
+            AppError::NotFound("File not found".to_string())
+        })?;
+
+        if hex::encode(Sha256::digest(&data)) != file.checksum_sha256 {
+            error!(file_id = %id, "File contents do not match their checksum");
+            return Err(AppError::Internal("File contents are corrupted".to_string()));
+        }
+        Ok((file, data))
+    }
+}
+
+/// Keep the last path segment of a client-supplied name, without control characters
+fn sanitize_file_name(name: &str) -> String {
+    let name: String = name
+        .rsplit(['/', '\\'])
+        .next()
+        .unwrap_or_default()
+        .chars()
+        .filter(|c| !c.is_control())
+        .take(MAX_FILE_NAME_LENGTH)
+        .collect();
+
+    match name.trim() {
+        "" | "." | ".." => "upload".to_string(),
+        name => name.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sanitize_file_name() {
+        assert_eq!(sanitize_file_name("report.pdf"), "report.pdf");
+        assert_eq!(sanitize_file_name("C:\\Users\\jane\\photo.png"), "photo.png");
+        assert_eq!(sanitize_file_name("../../etc/passwd"), "passwd");
+        assert_eq!(sanitize_file_name("bad\r\nname.txt"), "badname.txt");
+        assert_eq!(sanitize_file_name("dir/"), "upload");
+        assert_eq!(sanitize_file_name(&"a".repeat(300)).len(), MAX_FILE_NAME_LENGTH);
+    }
+}
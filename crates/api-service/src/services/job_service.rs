@@ -0,0 +1,186 @@
+//! Background job management for administrators
+
+use chrono::{DateTime, Utc};
+use database::{Job, JobFilter, JobRepository, JobStatus};
+use shared::{
+    context, jobs, permissions, retries, telemetry, AppError, AppResult, PaginatedResponse, PaginationParams,
+    Repository, TenantId,
+};
+use uuid::Uuid;
+
+use crate::{middleware::auth::AuthContext, state::AppState};
+
+/// A job to enqueue
+#[derive(Debug, Clone)]
+pub struct EnqueueJob {
+    /// One of [`jobs::REGISTERED`]
+    pub job_type: String,
+    pub payload: serde_json::Value,
+    /// Tenant the job runs for; the caller's tenant when unset
+    pub tenant_id: Option<TenantId>,
+    pub max_retries: Option<u32>,
+    /// Earliest time the job runs; now when unset
+    pub scheduled_at: Option<DateTime<Utc>>,
+}
+
+/// Job operations, restricted to administrators.
+///
+/// Administrators manage their own tenant's jobs; those also holding
+/// [`permissions::JOBS_ALL_TENANTS`] manage every tenant's jobs and jobs without a tenant.
+pub struct JobService {
+    jobs: JobRepository,
+}
+
+impl JobService {
+    pub fn new(state: &AppState) -> Self {
+        Self {
+            jobs: JobRepository::new(state.database().pool().clone()),
+        }
+    }
+
+    /// Enqueue a job of a registered type, carrying the request's correlation id and trace
+    pub async fn enqueue(&self, auth: &AuthContext, job: EnqueueJob) -> AppResult<Job> {
+        ensure_admin(auth)?;
+        if !jobs::REGISTERED.contains(&job.job_type.as_str()) {
+            return Err(AppError::Validation(format!(
+                "Unknown job type '{}', expected one of: {}",
+                job.job_type,
+                jobs::REGISTERED.join(", ")
+            )));
+        }
+        let tenant_id = job.tenant_id.unwrap_or(auth.tenant_id);
+        ensure_tenant_access(auth, &tenant_id)?;
+
+        let now = Utc::now();
+        let job = Job {
+            id: shared::generate_uuid(),
+            tenant_id: Some(tenant_id),
+            job_type: job.job_type,
+            status: JobStatus::Pending,
+            payload: job.payload,
+            result: None,
+            error: None,
+            retry_count: 0,
+            max_retries: job.max_retries.unwrap_or(retries::MAX_ATTEMPTS) as i32,
+            correlation_id: context::correlation_id(),
+            traceparent: telemetry::current_traceparent(),
+            scheduled_at: job.scheduled_at.unwrap_or(now),
+            started_at: None,
+            completed_at: None,
+            created_at: now,
+            updated_at: now,
+        };
+
+        self.jobs.create(&job).await
+    }
+
+    /// List jobs matching `filter`, newest first; without the all-tenants permission only
+    /// the caller's tenant's jobs are listed
+    pub async fn list(
+        &self,
+        auth: &AuthContext,
+        mut filter: JobFilter,
+        params: &PaginationParams,
+    ) -> AppResult<PaginatedResponse<Job>> {
+        ensure_admin(auth)?;
+        if !manages_all_tenants(auth) {
+            if let Some(tenant_id) = &filter.tenant_id {
+                ensure_tenant_access(auth, tenant_id)?;
+            }
+            filter.tenant_id = Some(auth.tenant_id);
+        }
+
+        self.jobs.find_filtered(&filter, params).await
+    }
+
+    /// Get a job with its payload, result and error
+    pub async fn get(&self, auth: &AuthContext, id: &Uuid) -> AppResult<Job> {
+        ensure_admin(auth)?;
+        let job = self
+            .jobs
+            .find_by_id(id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Job not found".to_string()))?;
+
+        if !manages_all_tenants(auth) {
+            match &job.tenant_id {
+                Some(tenant_id) => auth.ensure_tenant(tenant_id, "Job")?,
+                None => return Err(AppError::NotFound("Job not found".to_string())),
+            }
+        }
+        Ok(job)
+    }
+
+    /// Cancel a pending or running job; a running job is abandoned by its worker
+    pub async fn cancel(&self, auth: &AuthContext, id: &Uuid) -> AppResult<Job> {
+        self.get(auth, id).await?;
+        self.jobs
+            .cancel(id)
+            .await?
+            .ok_or_else(|| AppError::Conflict("Only pending or running jobs can be cancelled".to_string()))
+    }
+
+    /// Run a failed job again
+    pub async fn retry(&self, auth: &AuthContext, id: &Uuid) -> AppResult<Job> {
+        self.get(auth, id).await?;
+        self.jobs
+            .retry(id)
+            .await?
+            .ok_or_else(|| AppError::Conflict("Only failed jobs can be retried".to_string()))
+    }
+}
+
+fn ensure_admin(auth: &AuthContext) -> AppResult<()> {
+    if auth.is_admin() {
+        Ok(())
+    } else {
+        Err(AppError::Authorization("Only administrators can manage jobs".to_string()))
+    }
+}
+
+fn manages_all_tenants(auth: &AuthContext) -> bool {
+    auth.has_role(permissions::JOBS_ALL_TENANTS)
+}
+
+fn ensure_tenant_access(auth: &AuthContext, tenant_id: &TenantId) -> AppResult<()> {
+    if tenant_id == &auth.tenant_id || manages_all_tenants(auth) {
+        Ok(())
+    } else {
+        Err(AppError::Authorization(format!(
+            "Managing jobs of other tenants requires the {} permission",
+            permissions::JOBS_ALL_TENANTS
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::roles;
+
+    fn admin(extra_roles: &[&str]) -> AuthContext {
+        let mut roles = vec![roles::ADMIN.to_string()];
+        roles.extend(extra_roles.iter().map(|role| role.to_string()));
+        AuthContext {
+            user_id: Uuid::new_v4(),
+            tenant_id: Uuid::new_v4(),
+            roles,
+        }
+    }
+
+    #[test]
+    fn test_tenant_access() {
+        let auth = admin(&[]);
+        assert!(ensure_tenant_access(&auth, &auth.tenant_id).is_ok());
+        assert!(matches!(ensure_tenant_access(&auth, &Uuid::new_v4()), Err(AppError::Authorization(_))));
+
+        let operator = admin(&[permissions::JOBS_ALL_TENANTS]);
+        assert!(ensure_tenant_access(&operator, &Uuid::new_v4()).is_ok());
+
+        let user = AuthContext {
+            roles: vec![roles::USER.to_string()],
+            ..admin(&[])
+        };
+        assert!(ensure_admin(&user).is_err());
+    }
+}
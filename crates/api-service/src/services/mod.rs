@@ -1,11 +1,18 @@
 //! Business logic services
 
-// TODO: Add service modules as needed
-// pub mod user_service;
+pub mod audit_service;
+pub mod file_service;
+pub mod job_service;
+pub mod order_service;
+pub mod payment_service;
+pub mod user_service;
 // pub mod auth_service;
-// pub mod order_service;
 
-// Re-export services when implemented
-// pub use user_service::*;
+// Re-export services
+pub use audit_service::*;
+pub use file_service::*;
+pub use job_service::*;
+pub use order_service::*;
+pub use payment_service::*;
+pub use user_service::*;
 // pub use auth_service::*;
-// pub use order_service::*;
\ No newline at end of file
@@ -0,0 +1,125 @@
+//! Order business logic shared by the REST and GraphQL APIs
+
+use chrono::Utc;
+use database::{
+    Audited, CreateOrderDto, Event, EventRepository, Order, OrderRepository, OrderStatus, PgAuditLogger, UserRepository,
+};
+use shared::{events, generate_random_string, AppError, AppResult, PaginatedResponse, PaginationParams, Repository, UserId};
+use uuid::Uuid;
+
+use crate::{domain_events::order_event, middleware::auth::AuthContext, state::AppState};
+
+/// Tenant-scoped order operations
+pub struct OrderService {
+    orders: Audited<OrderRepository, PgAuditLogger>,
+    users: UserRepository,
+    events: EventRepository,
+}
+
+impl OrderService {
+    pub fn new(state: &AppState) -> Self {
+        Self {
+            orders: Audited::new(OrderRepository::new(state.database().pool().clone()), state.audit().clone()),
+            users: UserRepository::new(state.database().pool().clone()),
+            events: EventRepository::new(state.database().pool().clone()),
+        }
+    }
+
+    /// Get an order of the caller's tenant
+    pub async fn get(&self, auth: &AuthContext, id: &Uuid) -> AppResult<Order> {
+        let order = self
+            .orders
+            .find_by_id(id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Order not found".to_string()))?;
+
+        auth.ensure_tenant(&order.tenant_id, "Order")?;
+        Ok(order)
+    }
+
+    /// List orders of the caller's tenant
+    pub async fn list(&self, auth: &AuthContext, params: &PaginationParams) -> AppResult<PaginatedResponse<Order>> {
+        self.orders.find_by_tenant(&auth.tenant_id, params).await
+    }
+
+    /// Load several orders of the caller's tenant at once
+    pub async fn get_many(&self, auth: &AuthContext, ids: &[Uuid]) -> AppResult<Vec<Order>> {
+        self.orders.find_by_ids(&auth.tenant_id, ids).await
+    }
+
+    /// Load the orders placed by several users of the caller's tenant at once
+    pub async fn for_users(&self, auth: &AuthContext, user_ids: &[UserId]) -> AppResult<Vec<Order>> {
+        self.orders.find_by_user_ids(&auth.tenant_id, user_ids).await
+    }
+
+    /// Place an order in the caller's tenant
+    pub async fn create(&self, auth: &AuthContext, dto: CreateOrderDto) -> AppResult<Order> {
+        auth.ensure_tenant(&dto.tenant_id, "Tenant")?;
+
+        if dto.user_id != auth.user_id {
+            if !auth.is_admin() {
+                return Err(AppError::Authorization("Cannot place orders for other users".to_string()));
+            }
+
+            // Admins may order on behalf of other users, but only within their own tenant
+            let user = self
+                .users
+                .find_by_id(&dto.user_id)
+                .await?
+                .ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
+            auth.ensure_tenant(&user.tenant_id, "User")?;
+        }
+        if dto.total_amount < 0 {
+            return Err(AppError::Validation("Total amount cannot be negative".to_string()));
+        }
+        if dto.currency.len() != 3 {
+            return Err(AppError::Validation(format!("Invalid currency code: {}", dto.currency)));
+        }
+
+        let now = Utc::now();
+        let order = Order {
+            id: shared::generate_uuid(),
+            tenant_id: dto.tenant_id,
+            user_id: dto.user_id,
+            order_number: format!("ORD-{}", generate_random_string(12).to_uppercase()),
+            status: OrderStatus::Pending,
+            total_amount: dto.total_amount,
+            currency: dto.currency.to_uppercase(),
+            items: dto.items,
+            shipping_address: dto.shipping_address,
+            billing_address: dto.billing_address,
+            notes: dto.notes,
+            created_at: now,
+            updated_at: now,
+            deleted_at: None,
+        };
+
+        let order = self.orders.create(&order).await?;
+        self.record(&order, events::ORDER_CREATED).await?;
+        Ok(order)
+    }
+
+    /// Cancel an order of the caller's tenant
+    pub async fn cancel(&self, auth: &AuthContext, id: &Uuid) -> AppResult<Order> {
+        let mut order = self.get(auth, id).await?;
+
+        if order.user_id != auth.user_id && !auth.is_admin() {
+            return Err(AppError::Authorization("Not allowed to cancel this order".to_string()));
+        }
+        if !matches!(order.status, OrderStatus::Pending | OrderStatus::Confirmed) {
+            return Err(AppError::Conflict(format!("Order in status {:?} cannot be cancelled", order.status)));
+        }
+
+        order.status = OrderStatus::Cancelled;
+        let order = self.orders.update(id, &order).await?;
+        self.record(&order, events::ORDER_CANCELLED).await?;
+        Ok(order)
+    }
+
+    /// Append the change to the tenant's event stream
+    async fn record(&self, order: &Order, event_type: &str) -> AppResult<()> {
+        let event = Event::recorded(&order_event(order, event_type).metadata, order)?;
+        self.events.append(&event).await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,84 @@
+//! Payment business logic shared by the REST and GraphQL APIs
+
+use chrono::Utc;
+use database::{Audited, Event, EventRepository, Payment, PaymentMethod, PaymentRepository, PaymentStatus, PgAuditLogger};
+use shared::{events, AppError, AppResult, PaginatedResponse, PaginationParams};
+use uuid::Uuid;
+
+use crate::{domain_events::payment_event, middleware::auth::AuthContext, services::OrderService, state::AppState};
+
+/// Tenant-scoped payment operations
+pub struct PaymentService {
+    payments: Audited<PaymentRepository, PgAuditLogger>,
+    orders: OrderService,
+    events: EventRepository,
+}
+
+impl PaymentService {
+    pub fn new(state: &AppState) -> Self {
+        Self {
+            payments: Audited::new(PaymentRepository::new(state.database().pool().clone()), state.audit().clone()),
+            orders: OrderService::new(state),
+            events: EventRepository::new(state.database().pool().clone()),
+        }
+    }
+
+    /// Get a payment of the caller's tenant
+    pub async fn get(&self, auth: &AuthContext, id: &Uuid) -> AppResult<Payment> {
+        let payment = self
+            .payments
+            .find_by_id(id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Payment not found".to_string()))?;
+
+        auth.ensure_tenant(&payment.tenant_id, "Payment")?;
+        Ok(payment)
+    }
+
+    /// List payments of the caller's tenant
+    pub async fn list(&self, auth: &AuthContext, params: &PaginationParams) -> AppResult<PaginatedResponse<Payment>> {
+        self.payments.find_by_tenant(&auth.tenant_id, params).await
+    }
+
+    /// Load several payments of the caller's tenant at once
+    pub async fn get_many(&self, auth: &AuthContext, ids: &[Uuid]) -> AppResult<Vec<Payment>> {
+        self.payments.find_by_ids(&auth.tenant_id, ids).await
+    }
+
+    /// Load the payments of several orders of the caller's tenant at once
+    pub async fn for_orders(&self, auth: &AuthContext, order_ids: &[Uuid]) -> AppResult<Vec<Payment>> {
+        self.payments.find_by_order_ids(&auth.tenant_id, order_ids).await
+    }
+
+    /// Record a pending payment for the full amount of an order
+    pub async fn create(&self, auth: &AuthContext, order_id: &Uuid, method: PaymentMethod) -> AppResult<Payment> {
+        let order = self.orders.get(auth, order_id).await?;
+
+        if order.user_id != auth.user_id && !auth.is_admin() {
+            return Err(AppError::Authorization("Not allowed to pay for this order".to_string()));
+        }
+
+        let now = Utc::now();
+        let payment = Payment {
+            id: shared::generate_uuid(),
+            tenant_id: order.tenant_id,
+            order_id: order.id,
+            user_id: order.user_id,
+            payment_method: method,
+            status: PaymentStatus::Pending,
+            amount: order.total_amount,
+            currency: order.currency,
+            external_id: None,
+            gateway_response: None,
+            failure_reason: None,
+            processed_at: None,
+            created_at: now,
+            updated_at: now,
+        };
+
+        let payment = self.payments.create(&payment).await?;
+        let event = Event::recorded(&payment_event(&payment, events::PAYMENT_CREATED).metadata, &payment)?;
+        self.events.append(&event).await?;
+        Ok(payment)
+    }
+}
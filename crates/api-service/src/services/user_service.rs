@@ -0,0 +1,187 @@
+//! User business logic shared by the REST and GraphQL APIs
+
+use chrono::Utc;
+use database::{Audited, CreateUserDto, Event, EventRepository, PgAuditLogger, UpdateUserDto, User, UserRepository};
+use shared::{
+    events, hash_password, is_valid_email, services, AppError, AppResult, EventMetadata, PaginatedResponse, PaginationParams,
+    Repository, UserId,
+};
+
+use crate::{middleware::auth::AuthContext, state::AppState};
+
+/// Tenant-scoped user operations
+pub struct UserService {
+    users: Audited<UserRepository, PgAuditLogger>,
+    events: EventRepository,
+}
+
+impl UserService {
+    pub fn new(state: &AppState) -> Self {
+        Self {
+            users: Audited::new(UserRepository::new(state.database().pool().clone()), state.audit().clone()),
+            events: EventRepository::new(state.database().pool().clone()),
+        }
+    }
+
+    /// Get a user of the caller's tenant
+    pub async fn get(&self, auth: &AuthContext, id: &UserId) -> AppResult<User> {
+        let user = self
+            .users
+            .find_by_id(id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
+
+        auth.ensure_tenant(&user.tenant_id, "User")?;
+        Ok(user)
+    }
+
+    /// List users of the caller's tenant
+    pub async fn list(&self, auth: &AuthContext, params: &PaginationParams) -> AppResult<PaginatedResponse<User>> {
+        self.users.find_by_tenant(&auth.tenant_id, params).await
+    }
+
+    /// Load several users of the caller's tenant at once
+    pub async fn get_many(&self, auth: &AuthContext, ids: &[UserId]) -> AppResult<Vec<User>> {
+        self.users.find_by_ids(&auth.tenant_id, ids).await
+    }
+
+    /// Create a user in the caller's tenant (admin only)
+    pub async fn create(&self, auth: &AuthContext, dto: CreateUserDto) -> AppResult<User> {
+        if !auth.is_admin() {
+            return Err(AppError::Authorization("Only administrators can create users".to_string()));
+        }
+        auth.ensure_tenant(&dto.tenant_id, "Tenant")?;
+
+        if !is_valid_email(&dto.email) {
+            return Err(AppError::Validation(format!("Invalid email address: {}", dto.email)));
+        }
+        self.ensure_unique(auth, Some(&dto.email), Some(&dto.username), None).await?;
+
+        let now = Utc::now();
+        let user = User {
+            id: shared::generate_uuid(),
+            tenant_id: dto.tenant_id,
+            email: dto.email,
+            username: dto.username,
+            password_hash: hash_password(&dto.password)?,
+            first_name: dto.first_name,
+            last_name: dto.last_name,
+            is_active: true,
+            is_verified: false,
+            last_login_at: None,
+            created_at: now,
+            updated_at: now,
+            deleted_at: None,
+        };
+
+        let user = self.users.create(&user).await.map_err(taken)?;
+        self.record(&user, events::USER_CREATED).await?;
+        Ok(user)
+    }
+
+    /// Update a user of the caller's tenant.
+    ///
+    /// Users may update themselves; administrators may update anyone and change flags.
+    pub async fn update(&self, auth: &AuthContext, id: &UserId, dto: UpdateUserDto) -> AppResult<User> {
+        let mut user = self.get(auth, id).await?;
+        self.ensure_can_modify(auth, &user)?;
+
+        if (dto.is_active.is_some() || dto.is_verified.is_some()) && !auth.is_admin() {
+            return Err(AppError::Authorization("Only administrators can change account status".to_string()));
+        }
+        if let Some(email) = &dto.email {
+            if !is_valid_email(email) {
+                return Err(AppError::Validation(format!("Invalid email address: {}", email)));
+            }
+        }
+        self.ensure_unique(auth, dto.email.as_deref(), dto.username.as_deref(), Some(id)).await?;
+
+        if let Some(email) = dto.email {
+            user.email = email;
+        }
+        if let Some(username) = dto.username {
+            user.username = username;
+        }
+        if let Some(first_name) = dto.first_name {
+            user.first_name = Some(first_name);
+        }
+        if let Some(last_name) = dto.last_name {
+            user.last_name = Some(last_name);
+        }
+        if let Some(is_active) = dto.is_active {
+            user.is_active = is_active;
+        }
+        if let Some(is_verified) = dto.is_verified {
+            user.is_verified = is_verified;
+        }
+
+        let user = self.users.update(id, &user).await.map_err(taken)?;
+        self.record(&user, events::USER_UPDATED).await?;
+        Ok(user)
+    }
+
+    /// Soft delete a user of the caller's tenant (admin only)
+    pub async fn delete(&self, auth: &AuthContext, id: &UserId) -> AppResult<bool> {
+        if !auth.is_admin() {
+            return Err(AppError::Authorization("Only administrators can delete users".to_string()));
+        }
+        let user = self.get(auth, id).await?;
+        let deleted = self.users.delete(id).await?;
+        if deleted {
+            self.record(&user, events::USER_DELETED).await?;
+        }
+        Ok(deleted)
+    }
+
+    /// Append the change to the tenant's event stream, attributed to the user it is about
+    async fn record(&self, user: &User, event_type: &str) -> AppResult<()> {
+        let metadata = EventMetadata::current(event_type, services::API)
+            .with_tenant(user.tenant_id)
+            .with_user(user.id);
+        self.events.append(&Event::recorded(&metadata, user)?).await?;
+        Ok(())
+    }
+
+    fn ensure_can_modify(&self, auth: &AuthContext, user: &User) -> AppResult<()> {
+        if auth.is_admin() || auth.user_id == user.id {
+            Ok(())
+        } else {
+            Err(AppError::Authorization("Not allowed to modify this user".to_string()))
+        }
+    }
+
+    /// Email and username are unique per tenant
+    async fn ensure_unique(
+        &self,
+        auth: &AuthContext,
+        email: Option<&str>,
+        username: Option<&str>,
+        exclude: Option<&UserId>,
+    ) -> AppResult<()> {
+        let is_taken = |user: Option<User>| user.map_or(false, |u| Some(&u.id) != exclude);
+
+        if let Some(email) = email {
+            if is_taken(self.users.find_by_tenant_and_email(&auth.tenant_id, email).await?) {
+                return Err(AppError::Conflict("Email is already taken".to_string()));
+            }
+        }
+        if let Some(username) = username {
+            if is_taken(self.users.find_by_tenant_and_username(&auth.tenant_id, username).await?) {
+                return Err(AppError::Conflict("Username is already taken".to_string()));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Report a unique constraint violation as a conflict; it is hit when a concurrent request
+/// or a deleted user holds the email or username
+fn taken(error: AppError) -> AppError {
+    match error {
+        AppError::Database(sqlx::Error::Database(e)) if e.is_unique_violation() => {
+            AppError::Conflict("Email or username is already taken".to_string())
+        }
+        other => other,
+    }
+}
@@ -1,36 +1,85 @@
 //! Application state management
 
-use cache::RedisManager;
-use database::DatabaseManager;
-use shared::{AppConfig, AppResult};
+use cache::{RedisHealthCheck, RedisManager};
+use database::{DatabaseManager, MigrationHealthCheck, PgAuditLogger, PostgresHealthCheck};
+use shared::{kafka::KafkaHealthCheck, runtime::CancellationToken, AppResult, ConfigHandle, HealthRegistry};
 use std::sync::Arc;
+use storage::{Storage, UrlSigner};
+
+use crate::{config::ApiConfig, domain_events::DomainEvents, realtime::NotificationHub};
 
 /// Application state shared across all handlers
 #[derive(Debug, Clone)]
 pub struct AppState {
-    config: AppConfig,
+    config: ConfigHandle<ApiConfig>,
     database: DatabaseManager,
+    audit: PgAuditLogger,
+    storage: Arc<dyn Storage>,
+    url_signer: UrlSigner,
     cache: RedisManager,
+    notifications: NotificationHub,
+    domain_events: DomainEvents,
+    health: HealthRegistry,
+    shutdown: CancellationToken,
 }
 
 impl AppState {
-    /// Create new application state
-    pub async fn new(config: AppConfig) -> AppResult<Self> {
+    /// Create new application state; `shutdown` is cancelled when the server stops taking
+    /// requests
+    pub async fn new(handle: ConfigHandle<ApiConfig>, shutdown: CancellationToken) -> AppResult<Self> {
+        let snapshot = handle.load();
+        let ApiConfig { app: config, api } = snapshot.as_ref();
+
         // Initialize database connection
         let database = DatabaseManager::new(&config.database).await?;
 
+        // Record changes to tenant data in the audit log
+        let audit = PgAuditLogger::new(database.pool().clone(), &config.audit);
+
+        // Uploaded file contents, and signing of their download links
+        let storage = storage::from_config(&config.storage)?;
+        let url_signer = UrlSigner::new(config.storage.signing_key.clone(), config.storage_url_ttl());
+
         // Initialize Redis cache
         let cache = RedisManager::new(&config.redis).await?;
 
+        // Fan out real-time notifications published by any instance; the service runs the
+        // Redis bridge under its supervisor
+        let notifications = NotificationHub::new(cache.clone(), api.websocket.broadcast_capacity);
+
+        // Publish changes to Kafka for the event service
+        let domain_events = DomainEvents::new(&config.kafka, &api.domain_events)?;
+
+        // Requests are still served without Redis (rate limiting follows its failure mode) or
+        // Kafka (domain events are dropped), so losing either only degrades the service
+        let timeout = config.health.check_timeout;
+        let health = HealthRegistry::new(config.service_name.clone(), config.version.clone())
+            .register(PostgresHealthCheck::new(database.clone(), timeout))
+            .register(MigrationHealthCheck::new(&database, timeout))
+            .register_non_critical(RedisHealthCheck::new(cache.clone(), timeout))
+            .register_non_critical(KafkaHealthCheck::new(&config.kafka, timeout)?);
+
         Ok(Self {
-            config,
+            config: handle,
             database,
+            audit,
+            storage,
+            url_signer,
             cache,
+            notifications,
+            domain_events,
+            health,
+            shutdown,
         })
     }
 
-    /// Get configuration
-    pub fn config(&self) -> &AppConfig {
+    /// Snapshot of the running configuration, which may be replaced by a reload
+    pub fn config(&self) -> Arc<ApiConfig> {
+        self.config.load()
+    }
+
+    /// Get the reloadable configuration handle
+    pub fn config_handle(&self) -> &ConfigHandle<ApiConfig> {
         &self.config
     }
 
@@ -39,29 +88,65 @@ impl AppState {
         &self.database
     }
 
+    /// Get the audit logger
+    pub fn audit(&self) -> &PgAuditLogger {
+        &self.audit
+    }
+
+    /// Get the storage backend for uploaded files
+    pub fn storage(&self) -> &Arc<dyn Storage> {
+        &self.storage
+    }
+
+    /// Get the download link signer
+    pub fn url_signer(&self) -> &UrlSigner {
+        &self.url_signer
+    }
+
     /// Get cache manager
     pub fn cache(&self) -> &RedisManager {
         &self.cache
     }
 
+    /// Get real-time notification hub
+    pub fn notifications(&self) -> &NotificationHub {
+        &self.notifications
+    }
+
+    /// Get the Kafka domain event publisher
+    pub fn domain_events(&self) -> &DomainEvents {
+        &self.domain_events
+    }
+
+    /// Get dependency health checks
+    pub fn health(&self) -> &HealthRegistry {
+        &self.health
+    }
+
+    /// Cancelled when the server stops taking requests, so long-lived responses such as
+    /// event streams and WebSockets end and do not hold up the drain
+    pub fn shutdown_token(&self) -> &CancellationToken {
+        &self.shutdown
+    }
+
     /// Check if running in production
     pub fn is_production(&self) -> bool {
-        self.config.is_production()
+        self.config().app.is_production()
     }
 
     /// Check if running in development
     pub fn is_development(&self) -> bool {
-        self.config.is_development()
+        self.config().app.is_development()
     }
 
     /// Get service name
-    pub fn service_name(&self) -> &str {
-        &self.config.service_name
+    pub fn service_name(&self) -> String {
+        self.config().app.service_name.clone()
     }
 
     /// Get service version
-    pub fn version(&self) -> &str {
-        &self.config.version
+    pub fn version(&self) -> String {
+        self.config().app.version.clone()
     }
 }
 
@@ -79,11 +164,11 @@ mod tests {
 
     #[tokio::test]
     async fn test_app_state_creation() {
-        let config = AppConfig::default();
+        let config = ApiConfig::default();
         
         // This test would require running database and Redis instances
         // In a real test environment, you would use testcontainers
-        // let state = AppState::new(config).await;
+        // let state = AppState::new(ConfigHandle::new(config), CancellationToken::new()).await;
         // assert!(state.is_ok());
     }
 }
\ No newline at end of file
@@ -9,6 +9,7 @@ use tracing::{info, warn};
 /// Redis cache manager
 #[derive(Debug, Clone)]
 pub struct RedisManager {
+    client: Client,
     connection_manager: ConnectionManager,
     default_ttl: u64,
 }
@@ -18,10 +19,10 @@ impl RedisManager {
     pub async fn new(config: &RedisConfig) -> AppResult<Self> {
         info!("Initializing Redis connection manager");
 
-        let client = Client::open(config.url.as_str())
+        let client = Client::open(config.url.expose().as_str())
             .map_err(|e| AppError::Redis(e))?;
 
-        let connection_manager = ConnectionManager::new(client)
+        let connection_manager = ConnectionManager::new(client.clone())
             .await
             .map_err(|e| AppError::Redis(e))?;
 
@@ -32,6 +33,7 @@ impl RedisManager {
         info!("Redis connection manager initialized successfully");
 
         Ok(Self {
+            client,
             connection_manager,
             default_ttl: config.default_ttl,
         })
@@ -42,6 +44,17 @@ impl RedisManager {
         self.connection_manager.clone()
     }
 
+    /// Open a dedicated pub/sub connection
+    pub async fn pubsub(&self) -> AppResult<redis::aio::PubSub> {
+        let connection = self
+            .client
+            .get_async_connection()
+            .await
+            .map_err(|e| AppError::Redis(e))?;
+
+        Ok(connection.into_pubsub())
+    }
+
     /// Check Redis health
     pub async fn health_check(&self) -> AppResult<RedisHealth> {
         let start = std::time::Instant::now();
@@ -323,86 +336,6 @@ impl SessionManager {
     }
 }
 
-/// Rate limiter using Redis
-pub struct RateLimiter {
-    redis: RedisManager,
-    prefix: String,
-}
-
-impl RateLimiter {
-    /// Create a new rate limiter
-    pub fn new(redis: RedisManager, prefix: String) -> Self {
-        Self { redis, prefix }
-    }
-
-    /// Check if request is allowed (sliding window)
-    pub async fn is_allowed(&self, key: &str, limit: u32, window_seconds: u64) -> AppResult<bool> {
-        let redis_key = format!("{}:{}", self.prefix, key);
-        let mut conn = self.redis.get_connection();
-        
-        let now = chrono::Utc::now().timestamp() as u64;
-        let window_start = now - window_seconds;
-
-        // Remove old entries and count current requests
-        let script = r#"
-            local key = KEYS[1]
-            local now = tonumber(ARGV[1])
-            local window_start = tonumber(ARGV[2])
-            local limit = tonumber(ARGV[3])
-            local window_seconds = tonumber(ARGV[4])
-            
-            -- Remove old entries
-            redis.call('ZREMRANGEBYSCORE', key, 0, window_start)
-            
-            -- Count current requests
-            local current = redis.call('ZCARD', key)
-            
-            if current < limit then
-                -- Add current request
-                redis.call('ZADD', key, now, now)
-                redis.call('EXPIRE', key, window_seconds)
-                return 1
-            else
-                return 0
-            end
-        "#;
-
-        let result: i32 = redis::Script::new(script)
-            .key(&redis_key)
-            .arg(now)
-            .arg(window_start)
-            .arg(limit)
-            .arg(window_seconds)
-            .invoke_async(&mut conn)
-            .await
-            .map_err(|e| AppError::Redis(e))?;
-
-        Ok(result == 1)
-    }
-
-    /// Get remaining requests for key
-    pub async fn remaining(&self, key: &str, limit: u32, window_seconds: u64) -> AppResult<u32> {
-        let redis_key = format!("{}:{}", self.prefix, key);
-        let mut conn = self.redis.get_connection();
-        
-        let now = chrono::Utc::now().timestamp() as u64;
-        let window_start = now - window_seconds;
-
-        // Remove old entries and count current requests
-        let _: () = conn.zrembyscore(&redis_key, 0, window_start as isize).await.map_err(|e| AppError::Redis(e))?;
-        let current: u32 = conn.zcard(&redis_key).await.map_err(|e| AppError::Redis(e))?;
-
-        Ok(limit.saturating_sub(current))
-    }
-
-    /// Reset rate limit for key
-    pub async fn reset(&self, key: &str) -> AppResult<()> {
-        let redis_key = format!("{}:{}", self.prefix, key);
-        self.redis.delete(&redis_key).await?;
-        Ok(())
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -411,7 +344,7 @@ mod tests {
     #[tokio::test]
     async fn test_redis_manager_creation() {
         let config = RedisConfig {
-            url: "redis://localhost:6379".to_string(),
+            url: "redis://localhost:6379".into(),
             max_connections: 10,
             connect_timeout: 5,
             response_timeout: 5,
@@ -0,0 +1,39 @@
+//! Redis health check for the shared health registry
+
+use async_trait::async_trait;
+use shared::{AppError, AppResult, HealthCheck};
+
+use crate::client::{HealthStatus, RedisManager};
+
+/// Checks that Redis answers `PING`
+pub struct RedisHealthCheck {
+    redis: RedisManager,
+    timeout: u64,
+}
+
+impl RedisHealthCheck {
+    pub fn new(redis: RedisManager, timeout: u64) -> Self {
+        Self { redis, timeout }
+    }
+}
+
+#[async_trait]
+impl HealthCheck for RedisHealthCheck {
+    async fn check(&self) -> AppResult<bool> {
+        let health = self.redis.health_check().await?;
+        match health.status {
+            HealthStatus::Healthy => Ok(true),
+            _ => Err(AppError::ServiceUnavailable(
+                health.error.unwrap_or_else(|| "Redis is unhealthy".to_string()),
+            )),
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        "redis"
+    }
+
+    fn timeout(&self) -> u64 {
+        self.timeout
+    }
+}
@@ -1,12 +1,18 @@
 //! Redis cache integration for caching and session management
 
 pub mod client;
+pub mod health;
 pub mod operations;
+pub mod pubsub;
+pub mod rate_limit;
 pub mod serialization;
 
 // Re-export commonly used items
 pub use client::*;
+pub use health::*;
 pub use operations::*;
+pub use pubsub::*;
+pub use rate_limit::*;
 pub use serialization::*;
 
 // Re-export Redis types for convenience
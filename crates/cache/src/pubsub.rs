@@ -0,0 +1,174 @@
+//! Redis pub/sub for real-time notifications shared across service instances
+
+use chrono::{DateTime, Utc};
+use futures::{Stream, StreamExt};
+use redis::AsyncCommands;
+use serde::{Deserialize, Serialize};
+use shared::{cache_keys, context, AppError, AppResult, CorrelationId, TenantId, UserId};
+use std::{fmt, str::FromStr};
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::client::RedisManager;
+
+/// Notification channel clients can subscribe to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NotificationChannel {
+    Orders,
+    Payments,
+    Jobs,
+}
+
+impl NotificationChannel {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NotificationChannel::Orders => "orders",
+            NotificationChannel::Payments => "payments",
+            NotificationChannel::Jobs => "jobs",
+        }
+    }
+}
+
+impl fmt::Display for NotificationChannel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for NotificationChannel {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "orders" => Ok(NotificationChannel::Orders),
+            "payments" => Ok(NotificationChannel::Payments),
+            "jobs" => Ok(NotificationChannel::Jobs),
+            other => Err(AppError::Validation(format!("Unknown notification channel: {}", other))),
+        }
+    }
+}
+
+/// Real-time notification delivered to subscribed clients
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Notification {
+    pub id: Uuid,
+    pub tenant_id: TenantId,
+    pub channel: NotificationChannel,
+    /// Event name, e.g. `order.created` or `job.completed`
+    pub event: String,
+    /// Owner of the resource; `None` for tenant-wide notifications
+    pub user_id: Option<UserId>,
+    pub resource_id: Option<Uuid>,
+    pub payload: serde_json::Value,
+    /// Correlation id of the request or job that caused the notification
+    #[serde(default)]
+    pub correlation_id: Option<CorrelationId>,
+    pub timestamp: DateTime<Utc>,
+}
+
+impl Notification {
+    pub fn new(tenant_id: TenantId, channel: NotificationChannel, event: impl Into<String>, payload: serde_json::Value) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            tenant_id,
+            channel,
+            event: event.into(),
+            user_id: None,
+            resource_id: None,
+            payload,
+            correlation_id: context::correlation_id(),
+            timestamp: Utc::now(),
+        }
+    }
+
+    pub fn with_user(mut self, user_id: UserId) -> Self {
+        self.user_id = Some(user_id);
+        self
+    }
+
+    pub fn with_resource(mut self, resource_id: Uuid) -> Self {
+        self.resource_id = Some(resource_id);
+        self
+    }
+
+    /// Redis channel this notification is published on
+    pub fn redis_channel(&self) -> String {
+        format!("{}:{}:{}", cache_keys::NOTIFICATIONS, self.tenant_id, self.channel)
+    }
+}
+
+/// Publishes and subscribes to notifications through Redis pub/sub
+#[derive(Debug, Clone)]
+pub struct NotificationPublisher {
+    redis: RedisManager,
+}
+
+impl NotificationPublisher {
+    pub fn new(redis: RedisManager) -> Self {
+        Self { redis }
+    }
+
+    /// Publish a notification, returning the number of Redis subscribers that received it
+    pub async fn publish(&self, notification: &Notification) -> AppResult<u32> {
+        let mut conn = self.redis.get_connection();
+        let message = serde_json::to_string(notification).map_err(|e| AppError::Serialization(e))?;
+
+        let receivers: u32 = conn
+            .publish(notification.redis_channel(), message)
+            .await
+            .map_err(|e| AppError::Redis(e))?;
+
+        Ok(receivers)
+    }
+
+    /// Subscribe to notifications of every tenant and channel.
+    ///
+    /// Messages that cannot be decoded are logged and skipped.
+    pub async fn subscribe_all(&self) -> AppResult<impl Stream<Item = Notification>> {
+        let mut pubsub = self.redis.pubsub().await?;
+        pubsub
+            .psubscribe(format!("{}:*", cache_keys::NOTIFICATIONS))
+            .await
+            .map_err(|e| AppError::Redis(e))?;
+
+        Ok(pubsub.into_on_message().filter_map(|message| async move {
+            let payload: String = match message.get_payload() {
+                Ok(payload) => payload,
+                Err(e) => {
+                    warn!("Invalid notification payload on {}: {}", message.get_channel_name(), e);
+                    return None;
+                }
+            };
+
+            match serde_json::from_str(&payload) {
+                Ok(notification) => Some(notification),
+                Err(e) => {
+                    warn!("Failed to decode notification on {}: {}", message.get_channel_name(), e);
+                    None
+                }
+            }
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_notification_channel_round_trip() {
+        for channel in [NotificationChannel::Orders, NotificationChannel::Payments, NotificationChannel::Jobs] {
+            assert_eq!(channel.as_str().parse::<NotificationChannel>().unwrap(), channel);
+        }
+        assert!("unknown".parse::<NotificationChannel>().is_err());
+    }
+
+    #[test]
+    fn test_redis_channel_is_tenant_scoped() {
+        let tenant_id = Uuid::new_v4();
+        let notification = Notification::new(tenant_id, NotificationChannel::Orders, "order.created", serde_json::json!({}));
+
+        assert_eq!(notification.redis_channel(), format!("notifications:{}:orders", tenant_id));
+    }
+}
@@ -0,0 +1,461 @@
+//! Redis-backed rate limiting
+//!
+//! Every algorithm runs as a single Lua script so concurrent requests against the same key
+//! are evaluated atomically. Scripts report `{allowed, remaining, retry_ms, reset_ms}`, which
+//! is turned into a [`RateLimitDecision`] regardless of the algorithm used.
+//!
+//! Scripts read the current time from Redis with `TIME` rather than taking it from the caller,
+//! so instances with skewed clocks still enforce the same limits (requires Redis 5 or later).
+
+use async_trait::async_trait;
+use redis::AsyncCommands;
+use serde::{Deserialize, Serialize};
+use shared::{AppError, AppResult};
+
+use crate::client::RedisManager;
+
+/// Sliding log: one sorted-set entry per request inside the window.
+/// Exact, but memory grows with the limit.
+const SLIDING_WINDOW_SCRIPT: &str = r#"
+    local key = KEYS[1]
+    local time = redis.call('TIME')
+    local now = tonumber(time[1]) * 1000 + math.floor(tonumber(time[2]) / 1000)
+    local window = tonumber(ARGV[1])
+    local limit = tonumber(ARGV[2])
+    local cost = tonumber(ARGV[3])
+
+    redis.call('ZREMRANGEBYSCORE', key, 0, now - window)
+
+    local current = redis.call('ZCARD', key)
+    local allowed = 0
+    if current + cost <= limit then
+        if cost > 0 then
+            redis.call('ZADD', key, now, ARGV[4])
+            redis.call('PEXPIRE', key, window)
+            current = current + cost
+        end
+        allowed = 1
+    end
+
+    local oldest = redis.call('ZRANGE', key, 0, 0, 'WITHSCORES')
+    local reset = 0
+    if oldest[2] then
+        reset = tonumber(oldest[2]) + window - now
+    end
+
+    local retry = 0
+    if allowed == 0 then
+        retry = reset
+    end
+
+    return {allowed, limit - current, retry, reset}
+"#;
+
+/// Token bucket: `limit` tokens refilled evenly over the window, stored as a two-field hash
+const TOKEN_BUCKET_SCRIPT: &str = r#"
+    local key = KEYS[1]
+    local time = redis.call('TIME')
+    local now = tonumber(time[1]) * 1000 + math.floor(tonumber(time[2]) / 1000)
+    local window = tonumber(ARGV[1])
+    local limit = tonumber(ARGV[2])
+    local cost = tonumber(ARGV[3])
+    local rate = limit / window
+
+    local state = redis.call('HMGET', key, 'tokens', 'ts')
+    local tokens = tonumber(state[1])
+    local ts = tonumber(state[2])
+    if tokens == nil or ts == nil then
+        tokens = limit
+        ts = now
+    end
+    tokens = math.min(limit, tokens + math.max(0, now - ts) * rate)
+
+    local allowed = 0
+    if tokens >= cost then
+        tokens = tokens - cost
+        allowed = 1
+        if cost > 0 then
+            redis.call('HSET', key, 'tokens', tokens, 'ts', now)
+            redis.call('PEXPIRE', key, window)
+        end
+    end
+
+    local retry = 0
+    if allowed == 0 then
+        retry = math.ceil((cost - tokens) / rate)
+    end
+    local reset = math.ceil((limit - tokens) / rate)
+
+    return {allowed, math.floor(tokens), retry, reset}
+"#;
+
+/// GCRA: a single theoretical arrival time per key, allowing bursts of up to `limit`
+const GCRA_SCRIPT: &str = r#"
+    local key = KEYS[1]
+    local time = redis.call('TIME')
+    local now = tonumber(time[1]) * 1000 + math.floor(tonumber(time[2]) / 1000)
+    local window = tonumber(ARGV[1])
+    local limit = tonumber(ARGV[2])
+    local cost = tonumber(ARGV[3])
+    local interval = window / limit
+
+    local tat = tonumber(redis.call('GET', key)) or now
+    tat = math.max(tat, now)
+
+    local new_tat = tat + interval * cost
+    local allow_at = new_tat - window
+
+    if now < allow_at then
+        return {0, 0, math.ceil(allow_at - now), math.ceil(tat - now)}
+    end
+
+    if cost > 0 then
+        redis.call('SET', key, new_tat, 'PX', math.max(1, math.ceil(new_tat - now)))
+    end
+
+    local remaining = math.floor((now + window - new_tat) / interval)
+    return {1, remaining, 0, math.ceil(new_tat - now)}
+"#;
+
+/// Algorithm used to enforce a rate limit
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RateLimitAlgorithm {
+    /// Exact sliding log, one entry per request
+    #[default]
+    SlidingWindow,
+    /// Bucket of `limit` tokens refilled continuously over the window
+    TokenBucket,
+    /// Generic cell rate algorithm, constant memory per key
+    Gcra,
+}
+
+impl RateLimitAlgorithm {
+    pub const ALL: [RateLimitAlgorithm; 3] = [Self::SlidingWindow, Self::TokenBucket, Self::Gcra];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::SlidingWindow => "sliding_window",
+            Self::TokenBucket => "token_bucket",
+            Self::Gcra => "gcra",
+        }
+    }
+
+    fn script(&self) -> &'static str {
+        match self {
+            Self::SlidingWindow => SLIDING_WINDOW_SCRIPT,
+            Self::TokenBucket => TOKEN_BUCKET_SCRIPT,
+            Self::Gcra => GCRA_SCRIPT,
+        }
+    }
+}
+
+impl std::fmt::Display for RateLimitAlgorithm {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Outcome of a rate limit check
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimitDecision {
+    /// Whether the request may proceed
+    pub allowed: bool,
+    /// Requests allowed per window
+    pub limit: u32,
+    /// Requests that could be made right now
+    pub remaining: u32,
+    /// Seconds until the limit is fully replenished
+    pub reset_after: u64,
+    /// Seconds a rejected client should wait before retrying
+    pub retry_after: Option<u64>,
+}
+
+/// Limit enforced for a key
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimitRule {
+    pub algorithm: RateLimitAlgorithm,
+    pub limit: u32,
+    pub window_seconds: u64,
+}
+
+/// Rate limiter using Redis
+pub struct RateLimiter {
+    redis: RedisManager,
+    prefix: String,
+}
+
+impl RateLimiter {
+    /// Create a new rate limiter
+    pub fn new(redis: RedisManager, prefix: String) -> Self {
+        Self { redis, prefix }
+    }
+
+    /// Bind the limiter to a rule, giving a [`shared::RateLimiter`] implementation
+    pub fn with_rule(self, rule: RateLimitRule) -> RuleRateLimiter {
+        RuleRateLimiter { limiter: self, rule }
+    }
+
+    /// Check if request is allowed (sliding window)
+    pub async fn is_allowed(&self, key: &str, limit: u32, window_seconds: u64) -> AppResult<bool> {
+        Ok(self.check(key, limit, window_seconds).await?.allowed)
+    }
+
+    /// Record a request against a sliding window and report the resulting limit state
+    pub async fn check(&self, key: &str, limit: u32, window_seconds: u64) -> AppResult<RateLimitDecision> {
+        self.check_with(RateLimitAlgorithm::SlidingWindow, key, limit, window_seconds)
+            .await
+    }
+
+    /// Record a request using the given algorithm and report the resulting limit state
+    pub async fn check_with(
+        &self,
+        algorithm: RateLimitAlgorithm,
+        key: &str,
+        limit: u32,
+        window_seconds: u64,
+    ) -> AppResult<RateLimitDecision> {
+        self.evaluate(algorithm, key, limit, window_seconds, 1).await
+    }
+
+    /// Report the limit state for a key without recording a request
+    pub async fn peek(
+        &self,
+        algorithm: RateLimitAlgorithm,
+        key: &str,
+        limit: u32,
+        window_seconds: u64,
+    ) -> AppResult<RateLimitDecision> {
+        self.evaluate(algorithm, key, limit, window_seconds, 0).await
+    }
+
+    /// Get remaining requests for key (sliding window)
+    pub async fn remaining(&self, key: &str, limit: u32, window_seconds: u64) -> AppResult<u32> {
+        Ok(self
+            .peek(RateLimitAlgorithm::SlidingWindow, key, limit, window_seconds)
+            .await?
+            .remaining)
+    }
+
+    /// Reset rate limit for key under every algorithm
+    pub async fn reset(&self, key: &str) -> AppResult<()> {
+        let keys: Vec<String> = RateLimitAlgorithm::ALL
+            .iter()
+            .map(|algorithm| self.redis_key(*algorithm, key))
+            .collect();
+        let mut conn = self.redis.get_connection();
+        let _: () = conn.del(keys).await.map_err(AppError::Redis)?;
+        Ok(())
+    }
+
+    fn redis_key(&self, algorithm: RateLimitAlgorithm, key: &str) -> String {
+        format!("{}:{}:{}", self.prefix, algorithm, key)
+    }
+
+    async fn evaluate(
+        &self,
+        algorithm: RateLimitAlgorithm,
+        key: &str,
+        limit: u32,
+        window_seconds: u64,
+        cost: u32,
+    ) -> AppResult<RateLimitDecision> {
+        if limit == 0 || window_seconds == 0 {
+            return Err(AppError::Configuration(
+                "Rate limit and window must be greater than zero".to_string(),
+            ));
+        }
+
+        let mut conn = self.redis.get_connection();
+        run_script(&mut conn, algorithm, &self.redis_key(algorithm, key), limit, window_seconds, cost).await
+    }
+}
+
+/// Run an algorithm's script against a Redis key, recording `cost` requests
+async fn run_script<C>(
+    conn: &mut C,
+    algorithm: RateLimitAlgorithm,
+    redis_key: &str,
+    limit: u32,
+    window_seconds: u64,
+    cost: u32,
+) -> AppResult<RateLimitDecision>
+where
+    C: redis::aio::ConnectionLike,
+{
+    let window_ms = (window_seconds * 1000) as i64;
+
+    let script = redis::Script::new(algorithm.script());
+    let mut invocation = script.prepare_invoke();
+    invocation.key(redis_key).arg(window_ms).arg(limit).arg(cost);
+    if algorithm == RateLimitAlgorithm::SlidingWindow {
+        // Unique member so concurrent requests in the same millisecond are all counted
+        invocation.arg(uuid::Uuid::new_v4().to_string());
+    }
+
+    let (allowed, remaining, retry_ms, reset_ms): (i64, i64, i64, i64) = invocation
+        .invoke_async(conn)
+        .await
+        .map_err(AppError::Redis)?;
+
+    Ok(decision(allowed == 1, limit, remaining, retry_ms, reset_ms))
+}
+
+/// Rate limiter bound to a single rule
+pub struct RuleRateLimiter {
+    limiter: RateLimiter,
+    rule: RateLimitRule,
+}
+
+impl RuleRateLimiter {
+    pub fn rule(&self) -> RateLimitRule {
+        self.rule
+    }
+
+    /// Record a request and report the resulting limit state
+    pub async fn check(&self, key: &str) -> AppResult<RateLimitDecision> {
+        self.limiter
+            .check_with(self.rule.algorithm, key, self.rule.limit, self.rule.window_seconds)
+            .await
+    }
+}
+
+#[async_trait]
+impl shared::RateLimiter for RuleRateLimiter {
+    async fn is_allowed(&self, key: &str) -> AppResult<bool> {
+        Ok(self.check(key).await?.allowed)
+    }
+
+    async fn remaining(&self, key: &str) -> AppResult<u32> {
+        let decision = self
+            .limiter
+            .peek(self.rule.algorithm, key, self.rule.limit, self.rule.window_seconds)
+            .await?;
+        Ok(decision.remaining)
+    }
+
+    async fn reset(&self, key: &str) -> AppResult<()> {
+        let redis_key = self.limiter.redis_key(self.rule.algorithm, key);
+        self.limiter.redis.delete(&redis_key).await?;
+        Ok(())
+    }
+}
+
+/// Build a decision from a script result, rounding times up to whole seconds
+fn decision(allowed: bool, limit: u32, remaining: i64, retry_ms: i64, reset_ms: i64) -> RateLimitDecision {
+    let to_seconds = |ms: i64| ((ms.max(0) + 999) / 1000) as u64;
+
+    RateLimitDecision {
+        allowed,
+        limit,
+        remaining: remaining.clamp(0, limit as i64) as u32,
+        reset_after: to_seconds(reset_ms),
+        retry_after: (!allowed).then(|| to_seconds(retry_ms).max(1)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decision() {
+        let allowed = decision(true, 10, 7, 0, 60_000);
+        assert_eq!(allowed.remaining, 7);
+        assert_eq!(allowed.reset_after, 60);
+        assert_eq!(allowed.retry_after, None);
+
+        // Next slot frees up 15.5s from now
+        let rejected = decision(false, 10, 0, 15_500, 15_500);
+        assert_eq!(rejected.remaining, 0);
+        assert_eq!(rejected.reset_after, 16);
+        assert_eq!(rejected.retry_after, Some(16));
+
+        // Sub-second waits still ask clients to back off for a second
+        let rejected = decision(false, 10, -1, 200, 200);
+        assert_eq!(rejected.remaining, 0);
+        assert_eq!(rejected.retry_after, Some(1));
+    }
+
+    #[test]
+    fn test_algorithm_names() {
+        assert_eq!(
+            serde_json::from_str::<RateLimitAlgorithm>("\"token_bucket\"").unwrap(),
+            RateLimitAlgorithm::TokenBucket
+        );
+        assert_eq!(serde_json::to_string(&RateLimitAlgorithm::Gcra).unwrap(), "\"gcra\"");
+        assert_eq!(RateLimitAlgorithm::default().to_string(), "sliding_window");
+    }
+
+    /// Script tests run against a throwaway Redis: `cargo test -p cache -- --ignored`
+    mod scripts {
+        use super::*;
+        use redis::aio::MultiplexedConnection;
+        use testcontainers::{clients::Cli, core::WaitFor, GenericImage};
+
+        fn redis_image() -> GenericImage {
+            GenericImage::new("redis", "7-alpine")
+                .with_wait_for(WaitFor::message_on_stdout("Ready to accept connections"))
+        }
+
+        async fn connect(port: u16) -> MultiplexedConnection {
+            redis::Client::open(format!("redis://127.0.0.1:{}", port))
+                .unwrap()
+                .get_multiplexed_async_connection()
+                .await
+                .unwrap()
+        }
+
+        #[tokio::test]
+        #[ignore = "requires Docker"]
+        async fn test_scripts_enforce_limit() {
+            let docker = Cli::default();
+            let node = docker.run(redis_image());
+            let mut conn = connect(node.get_host_port_ipv4(6379)).await;
+
+            for algorithm in RateLimitAlgorithm::ALL {
+                let key = format!("test:{}", algorithm);
+
+                for expected in [2, 1, 0] {
+                    let allowed = run_script(&mut conn, algorithm, &key, 3, 60, 1).await.unwrap();
+                    assert!(allowed.allowed, "{} rejected a request within the limit", algorithm);
+                    assert_eq!(allowed.remaining, expected, "{}", algorithm);
+                    assert!(allowed.reset_after > 0 && allowed.reset_after <= 60, "{}", algorithm);
+                }
+
+                let rejected = run_script(&mut conn, algorithm, &key, 3, 60, 1).await.unwrap();
+                assert!(!rejected.allowed, "{} allowed a request over the limit", algorithm);
+                let retry_after = rejected.retry_after.unwrap();
+                assert!((1..=60).contains(&retry_after), "{} retry after {}", algorithm, retry_after);
+
+                // Peeking reports the state without using up the limit
+                let peeked = run_script(&mut conn, algorithm, &key, 3, 60, 0).await.unwrap();
+                assert_eq!(peeked.remaining, 0, "{}", algorithm);
+            }
+        }
+
+        #[tokio::test]
+        #[ignore = "requires Docker"]
+        async fn test_scripts_keep_keys_apart_and_expire() {
+            let docker = Cli::default();
+            let node = docker.run(redis_image());
+            let mut conn = connect(node.get_host_port_ipv4(6379)).await;
+
+            for algorithm in RateLimitAlgorithm::ALL {
+                let key = format!("test:{}:a", algorithm);
+                let other = format!("test:{}:b", algorithm);
+
+                assert!(run_script(&mut conn, algorithm, &key, 1, 1, 1).await.unwrap().allowed);
+                assert!(!run_script(&mut conn, algorithm, &key, 1, 1, 1).await.unwrap().allowed);
+                assert!(run_script(&mut conn, algorithm, &other, 1, 1, 1).await.unwrap().allowed);
+
+                // A one second window frees up again once it has passed, by the Redis clock
+                tokio::time::sleep(std::time::Duration::from_millis(1100)).await;
+                assert!(run_script(&mut conn, algorithm, &key, 1, 1, 1).await.unwrap().allowed, "{}", algorithm);
+
+                let ttl: i64 = redis::cmd("PTTL").arg(&key).query_async(&mut conn).await.unwrap();
+                assert!(ttl > 0 && ttl <= 1000, "{} left key with ttl {}", algorithm, ttl);
+            }
+        }
+    }
+}
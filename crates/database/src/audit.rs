@@ -0,0 +1,513 @@
+//! Audit trail of changes to tenant data
+//!
+//! [`Audited`] wraps a repository and records every create, update and delete together with
+//! the changed fields before and after, attributed to the caller in scope (see
+//! [`shared::context::actor`]). Entries are written to `audit_logs` in batches by
+//! [`PgAuditLogger`], off the request path.
+
+use async_trait::async_trait;
+use chrono::Utc;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use shared::{
+    context, AppError, AppResult, AuditConfig, AuditEvent, AuditLogger, Entity, PaginatedResponse, PaginationParams,
+    Repository, TenantId,
+};
+use sqlx::{PgPool, Postgres, QueryBuilder};
+use std::{collections::BTreeSet, net::IpAddr, ops::Deref, time::Duration};
+use tokio::{
+    sync::{mpsc, oneshot},
+    time::MissedTickBehavior,
+};
+use tracing::error;
+use uuid::Uuid;
+
+use crate::{Payment, PaymentRepository, PaymentStatus};
+
+/// Fields whose values never reach the audit log
+pub const SENSITIVE_FIELDS: &[&str] = &["password_hash", "password", "token", "secret", "api_key"];
+
+/// Recorded in place of the value of a sensitive field
+pub const REDACTED: &str = "[REDACTED]";
+
+/// Full batches kept for retry while the database is unavailable; older entries are dropped
+const MAX_PENDING_BATCHES: usize = 10;
+
+/// Kind of change recorded in the audit log
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuditAction {
+    Create,
+    Update,
+    Delete,
+}
+
+impl AuditAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditAction::Create => "create",
+            AuditAction::Update => "update",
+            AuditAction::Delete => "delete",
+        }
+    }
+}
+
+/// Entity whose changes are recorded in the audit log
+pub trait Auditable: Entity<Id = Uuid> + Serialize {
+    /// `resource_type` of its audit entries
+    const RESOURCE_TYPE: &'static str;
+
+    /// Tenant the entity belongs to
+    fn audit_tenant_id(&self) -> TenantId;
+}
+
+/// Build the audit entry for a change from `before` to `after`, attributed to the caller in scope.
+///
+/// Updates record only the fields that changed; creates and deletes record the whole entity.
+pub fn change_event<T: Auditable>(action: AuditAction, before: Option<&T>, after: Option<&T>) -> AppResult<AuditEvent> {
+    let entity = after
+        .or(before)
+        .ok_or_else(|| AppError::Internal("Audit entry without an entity".to_string()))?;
+
+    let (old_values, new_values) = match (before.map(to_value).transpose()?, after.map(to_value).transpose()?) {
+        (Some(old), Some(new)) => {
+            let (old, new) = diff(&old, &new);
+            (Some(old), Some(new))
+        }
+        (old, new) => (old.map(redact), new.map(redact)),
+    };
+
+    let mut event = caller_event(action.as_str(), T::RESOURCE_TYPE, entity.audit_tenant_id());
+    event.resource_id = Some(*entity.id());
+    event.old_values = old_values;
+    event.new_values = new_values;
+    Ok(event)
+}
+
+/// Build the audit entry for an action on a tenant's data that changes nothing, such as an
+/// export, attributed to the caller in scope
+pub fn access_event(action: &str, resource_type: &str, tenant_id: TenantId, details: Value) -> AuditEvent {
+    let mut event = caller_event(action, resource_type, tenant_id);
+    event.new_values = Some(redact(details));
+    event
+}
+
+fn caller_event(action: &str, resource_type: &str, tenant_id: TenantId) -> AuditEvent {
+    let actor = context::actor().unwrap_or_default();
+    AuditEvent {
+        event_id: shared::generate_uuid(),
+        tenant_id: Some(tenant_id),
+        user_id: actor.user_id,
+        action: action.to_string(),
+        resource: resource_type.to_string(),
+        resource_id: None,
+        old_values: None,
+        new_values: None,
+        timestamp: Utc::now(),
+        // The column is INET, and one unparsable address would fail the whole batch
+        ip_address: actor.ip_address.filter(|ip| ip.parse::<IpAddr>().is_ok()),
+        user_agent: actor.user_agent,
+        correlation_id: context::current_correlation_id(),
+    }
+}
+
+fn to_value<T: Serialize>(entity: &T) -> AppResult<Value> {
+    serde_json::to_value(entity).map_err(|e| AppError::Internal(format!("Failed to serialize audited entity: {}", e)))
+}
+
+/// Fields that differ between two serialized entities, as `(before, after)` objects.
+///
+/// A changed sensitive field is still listed, so a password change shows up, but its values
+/// are redacted.
+pub fn diff(old: &Value, new: &Value) -> (Value, Value) {
+    let empty = Map::new();
+    let old_fields = old.as_object().unwrap_or(&empty);
+    let new_fields = new.as_object().unwrap_or(&empty);
+
+    let mut before = Map::new();
+    let mut after = Map::new();
+    let keys: BTreeSet<&String> = old_fields.keys().chain(new_fields.keys()).collect();
+    for key in keys {
+        let old_value = old_fields.get(key).cloned().unwrap_or(Value::Null);
+        let new_value = new_fields.get(key).cloned().unwrap_or(Value::Null);
+        if old_value != new_value {
+            before.insert(key.clone(), old_value);
+            after.insert(key.clone(), new_value);
+        }
+    }
+
+    (redact(Value::Object(before)), redact(Value::Object(after)))
+}
+
+/// Replace the values of sensitive fields, at any depth
+pub fn redact(value: Value) -> Value {
+    match value {
+        Value::Object(fields) => Value::Object(
+            fields
+                .into_iter()
+                .map(|(key, value)| {
+                    let value = if is_sensitive(&key) {
+                        Value::String(REDACTED.to_string())
+                    } else {
+                        redact(value)
+                    };
+                    (key, value)
+                })
+                .collect(),
+        ),
+        Value::Array(items) => Value::Array(items.into_iter().map(redact).collect()),
+        other => other,
+    }
+}
+
+fn is_sensitive(field: &str) -> bool {
+    SENSITIVE_FIELDS.iter().any(|sensitive| field.eq_ignore_ascii_case(sensitive))
+}
+
+/// Repository decorator recording every create, update and delete in the audit log.
+///
+/// Reads, including the wrapped repository's own query methods, pass straight through.
+#[derive(Debug, Clone)]
+pub struct Audited<R, L> {
+    inner: R,
+    audit: L,
+}
+
+impl<R, L> Audited<R, L>
+where
+    R: Sync,
+    L: AuditLogger + Sync,
+{
+    pub fn new(inner: R, audit: L) -> Self {
+        Self { inner, audit }
+    }
+
+    /// Record a change made outside the [`Repository`] methods.
+    ///
+    /// The change is already committed, so a failure to record it is logged, not returned.
+    pub async fn record<T: Auditable + Sync>(&self, action: AuditAction, before: Option<&T>, after: Option<&T>) {
+        let result = match change_event(action, before, after) {
+            Ok(event) => self.audit.log(&event).await,
+            Err(e) => Err(e),
+        };
+
+        if let Err(e) = result {
+            error!(action = action.as_str(), resource = T::RESOURCE_TYPE, "Failed to record audit entry: {}", e);
+        }
+    }
+}
+
+impl<R, L> Deref for Audited<R, L> {
+    type Target = R;
+
+    fn deref(&self) -> &R {
+        &self.inner
+    }
+}
+
+#[async_trait]
+impl<T, R, L> Repository<T, Uuid> for Audited<R, L>
+where
+    T: Auditable + Send + Sync,
+    R: Repository<T, Uuid> + Send + Sync,
+    L: AuditLogger + Send + Sync,
+{
+    async fn find_by_id(&self, id: &Uuid) -> AppResult<Option<T>> {
+        self.inner.find_by_id(id).await
+    }
+
+    async fn find_all(&self, params: &PaginationParams) -> AppResult<PaginatedResponse<T>> {
+        self.inner.find_all(params).await
+    }
+
+    async fn create(&self, entity: &T) -> AppResult<T> {
+        let created = self.inner.create(entity).await?;
+        self.record(AuditAction::Create, None, Some(&created)).await;
+        Ok(created)
+    }
+
+    async fn update(&self, id: &Uuid, entity: &T) -> AppResult<T> {
+        let before = self.inner.find_by_id(id).await?;
+        let updated = self.inner.update(id, entity).await?;
+        self.record(AuditAction::Update, before.as_ref(), Some(&updated)).await;
+        Ok(updated)
+    }
+
+    async fn delete(&self, id: &Uuid) -> AppResult<bool> {
+        let before = self.inner.find_by_id(id).await?;
+        let deleted = self.inner.delete(id).await?;
+        if let (true, Some(before)) = (deleted, &before) {
+            self.record(AuditAction::Delete, Some(before), None).await;
+        }
+        Ok(deleted)
+    }
+
+    async fn exists(&self, id: &Uuid) -> AppResult<bool> {
+        self.inner.exists(id).await
+    }
+
+    async fn count(&self) -> AppResult<u64> {
+        self.inner.count().await
+    }
+}
+
+/// Payment writes, which have no [`Repository`] implementation
+impl<L> Audited<PaymentRepository, L>
+where
+    L: AuditLogger + Sync,
+{
+    pub async fn create(&self, payment: &Payment) -> AppResult<Payment> {
+        let created = self.inner.create(payment).await?;
+        self.record(AuditAction::Create, None, Some(&created)).await;
+        Ok(created)
+    }
+
+    pub async fn update_status(&self, id: &Uuid, status: PaymentStatus, failure_reason: Option<&str>) -> AppResult<Payment> {
+        let before = self.inner.find_by_id(id).await?;
+        let updated = self.inner.update_status(id, status, failure_reason).await?;
+        self.record(AuditAction::Update, before.as_ref(), Some(&updated)).await;
+        Ok(updated)
+    }
+}
+
+enum Command {
+    Record(AuditEvent),
+    Flush(oneshot::Sender<()>),
+}
+
+/// Audit logger writing to the `audit_logs` table in batches.
+///
+/// [`AuditLogger::log`] only queues the entry, waiting when the queue is full. A background
+/// task inserts queued entries once a batch is full or the flush interval passes; call
+/// [`PgAuditLogger::flush`] before shutting down.
+#[derive(Debug, Clone)]
+pub struct PgAuditLogger {
+    sender: mpsc::Sender<Command>,
+    pool: PgPool,
+}
+
+impl PgAuditLogger {
+    /// Start the background writer; must be called from within a Tokio runtime
+    pub fn new(pool: PgPool, config: &AuditConfig) -> Self {
+        let (sender, commands) = mpsc::channel(config.queue_size);
+        let writer = AuditWriter {
+            pool: pool.clone(),
+            batch_size: config.batch_size,
+            pending: Vec::new(),
+            failing: false,
+        };
+        tokio::spawn(writer.run(commands, Duration::from_millis(config.flush_interval)));
+
+        Self { sender, pool }
+    }
+
+    /// Write an entry immediately, bypassing the queue, for actions that must not proceed
+    /// unless they are on record
+    pub async fn log_now(&self, event: &AuditEvent) -> AppResult<()> {
+        insert(&self.pool, std::slice::from_ref(event)).await?;
+        Ok(())
+    }
+
+    /// Write every entry queued so far
+    pub async fn flush(&self) {
+        let (done, flushed) = oneshot::channel();
+        if self.sender.send(Command::Flush(done)).await.is_ok() {
+            let _ = flushed.await;
+        }
+    }
+}
+
+#[async_trait]
+impl AuditLogger for PgAuditLogger {
+    async fn log(&self, event: &AuditEvent) -> AppResult<()> {
+        self.sender
+            .send(Command::Record(event.clone()))
+            .await
+            .map_err(|_| AppError::Internal("Audit log writer has stopped".to_string()))
+    }
+}
+
+struct AuditWriter {
+    pool: PgPool,
+    batch_size: usize,
+    pending: Vec<AuditEvent>,
+    /// Set while inserts fail, so retries wait for the next tick instead of every new entry
+    failing: bool,
+}
+
+impl AuditWriter {
+    async fn run(mut self, mut commands: mpsc::Receiver<Command>, flush_interval: Duration) {
+        let mut ticker = tokio::time::interval(flush_interval);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        loop {
+            tokio::select! {
+                command = commands.recv() => match command {
+                    Some(Command::Record(event)) => {
+                        self.pending.push(event);
+                        if self.pending.len() >= self.batch_size && !self.failing {
+                            self.write().await;
+                        }
+                    }
+                    Some(Command::Flush(done)) => {
+                        self.write().await;
+                        let _ = done.send(());
+                    }
+                    None => {
+                        self.write().await;
+                        return;
+                    }
+                },
+                _ = ticker.tick() => self.write().await,
+            }
+        }
+    }
+
+    /// Insert pending entries a batch at a time, keeping them for the next attempt on failure
+    async fn write(&mut self) {
+        while !self.pending.is_empty() {
+            let size = self.pending.len().min(self.batch_size);
+            match insert(&self.pool, &self.pending[..size]).await {
+                Ok(()) => {
+                    self.pending.drain(..size);
+                    self.failing = false;
+                }
+                Err(sqlx::Error::Database(e)) => {
+                    // The database rejected a row; write the rest of the batch without it
+                    error!("Audit batch rejected, retrying entries one by one: {}", e);
+                    let batch: Vec<AuditEvent> = self.pending.drain(..size).collect();
+                    for event in batch {
+                        if let Err(e) = insert(&self.pool, std::slice::from_ref(&event)).await {
+                            error!(event_id = %event.event_id, "Dropped audit entry: {}", e);
+                        }
+                    }
+                }
+                Err(e) => {
+                    error!(pending = self.pending.len(), "Failed to write audit entries: {}", e);
+                    self.failing = true;
+
+                    let limit = self.batch_size * MAX_PENDING_BATCHES;
+                    if self.pending.len() > limit {
+                        let dropped = self.pending.len() - limit;
+                        self.pending.drain(..dropped);
+                        error!(dropped, "Dropped audit entries that could not be written");
+                    }
+                    return;
+                }
+            }
+        }
+    }
+}
+
+async fn insert(pool: &PgPool, events: &[AuditEvent]) -> Result<(), sqlx::Error> {
+    let mut query = QueryBuilder::<Postgres>::new(
+        "INSERT INTO audit_logs (id, tenant_id, user_id, action, resource_type, resource_id, old_values, \
+         new_values, ip_address, user_agent, correlation_id, created_at) ",
+    );
+    query.push_values(events, |mut row, event| {
+        row.push_bind(event.event_id)
+            .push_bind(event.tenant_id)
+            .push_bind(event.user_id)
+            .push_bind(event.action.clone())
+            .push_bind(event.resource.clone())
+            .push_bind(event.resource_id)
+            .push_bind(event.old_values.clone())
+            .push_bind(event.new_values.clone())
+            .push_bind(event.ip_address.clone())
+            .push_unseparated("::inet")
+            .push_bind(event.user_agent.clone())
+            .push_bind(event.correlation_id)
+            .push_bind(event.timestamp);
+    });
+
+    query.build().execute(pool).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::User;
+    use shared::context::{with_actor, Actor};
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Clone, Default)]
+    struct Recorder(Arc<Mutex<Vec<AuditEvent>>>);
+
+    #[async_trait]
+    impl AuditLogger for Recorder {
+        async fn log(&self, event: &AuditEvent) -> AppResult<()> {
+            self.0.lock().unwrap().push(event.clone());
+            Ok(())
+        }
+    }
+
+    fn user() -> User {
+        let now = Utc::now();
+        User {
+            id: Uuid::new_v4(),
+            tenant_id: Uuid::new_v4(),
+            email: "jane@example.com".to_string(),
+            username: "jane".to_string(),
+            password_hash: "$argon2id$v=19$old".to_string(),
+            first_name: None,
+            last_name: None,
+            is_active: true,
+            is_verified: false,
+            last_login_at: None,
+            created_at: now,
+            updated_at: now,
+            deleted_at: None,
+        }
+    }
+
+    #[test]
+    fn test_diff_keeps_changed_fields_and_redacts_secrets() {
+        let old = serde_json::json!({ "email": "a@example.com", "password_hash": "x", "is_active": true });
+        let new = serde_json::json!({ "email": "b@example.com", "password_hash": "y", "is_active": true });
+
+        let (before, after) = diff(&old, &new);
+        assert_eq!(before, serde_json::json!({ "email": "a@example.com", "password_hash": REDACTED }));
+        assert_eq!(after, serde_json::json!({ "email": "b@example.com", "password_hash": REDACTED }));
+
+        let nested = redact(serde_json::json!({ "gateway": [{ "Token": "tok_123", "status": "ok" }] }));
+        assert_eq!(nested, serde_json::json!({ "gateway": [{ "Token": REDACTED, "status": "ok" }] }));
+    }
+
+    #[tokio::test]
+    async fn test_change_event_attributed_to_actor() {
+        let recorder = Recorder::default();
+        let audited = Audited::new((), recorder.clone());
+        let before = user();
+        let mut after = before.clone();
+        after.username = "jane.doe".to_string();
+
+        let actor = Actor {
+            user_id: Some(Uuid::new_v4()),
+            tenant_id: Some(before.tenant_id),
+            ip_address: Some("203.0.113.7".to_string()),
+            user_agent: Some("curl/8.0".to_string()),
+        };
+        with_actor(actor.clone(), audited.record(AuditAction::Update, Some(&before), Some(&after))).await;
+        with_actor(
+            Actor { ip_address: Some("not-an-ip".to_string()), ..actor.clone() },
+            audited.record(AuditAction::Create, None, Some(&after)),
+        )
+        .await;
+
+        let events = recorder.0.lock().unwrap();
+        let update = &events[0];
+        assert_eq!(update.action, "update");
+        assert_eq!(update.resource, "user");
+        assert_eq!(update.resource_id, Some(before.id));
+        assert_eq!(update.tenant_id, Some(before.tenant_id));
+        assert_eq!(update.user_id, actor.user_id);
+        assert_eq!(update.ip_address.as_deref(), Some("203.0.113.7"));
+        assert_eq!(update.user_agent.as_deref(), Some("curl/8.0"));
+        assert_eq!(update.new_values, Some(serde_json::json!({ "username": "jane.doe" })));
+
+        let create = &events[1];
+        assert_eq!(create.new_values.as_ref().unwrap()["password_hash"], REDACTED);
+        assert!(create.old_values.is_none());
+        assert!(create.ip_address.is_none());
+    }
+}
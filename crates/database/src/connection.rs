@@ -22,7 +22,7 @@ impl DatabaseManager {
             .acquire_timeout(Duration::from_secs(config.connect_timeout))
             .idle_timeout(Some(Duration::from_secs(config.idle_timeout)))
             .max_lifetime(Some(Duration::from_secs(config.max_lifetime)))
-            .connect(&config.url)
+            .connect(config.url.expose())
             .await
             .map_err(|e| AppError::Database(e))?;
 
@@ -91,7 +91,7 @@ impl DatabaseManager {
     pub async fn migrate(&self) -> AppResult<()> {
         info!("Running database migrations");
         
-        sqlx::migrate!("./migrations")
+        crate::migrations::MIGRATOR
             .run(&self.pool)
             .await
             .map_err(|e| AppError::Database(e))?;
@@ -0,0 +1,78 @@
+//! Database health checks for the shared health registry
+
+use async_trait::async_trait;
+use shared::{AppError, AppResult, HealthCheck};
+
+use crate::{connection::HealthStatus, DatabaseManager, MigrationManager};
+
+/// Checks that Postgres answers queries
+pub struct PostgresHealthCheck {
+    database: DatabaseManager,
+    timeout: u64,
+}
+
+impl PostgresHealthCheck {
+    pub fn new(database: DatabaseManager, timeout: u64) -> Self {
+        Self { database, timeout }
+    }
+}
+
+#[async_trait]
+impl HealthCheck for PostgresHealthCheck {
+    async fn check(&self) -> AppResult<bool> {
+        let health = self.database.health_check().await?;
+        match health.status {
+            HealthStatus::Healthy => Ok(true),
+            _ => Err(AppError::ServiceUnavailable(
+                health.error.unwrap_or_else(|| "Database is unhealthy".to_string()),
+            )),
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        "postgres"
+    }
+
+    fn timeout(&self) -> u64 {
+        self.timeout
+    }
+}
+
+/// Checks that every embedded migration has been applied
+pub struct MigrationHealthCheck {
+    migrations: MigrationManager,
+    timeout: u64,
+}
+
+impl MigrationHealthCheck {
+    pub fn new(database: &DatabaseManager, timeout: u64) -> Self {
+        Self {
+            migrations: MigrationManager::new(database.pool().clone()),
+            timeout,
+        }
+    }
+}
+
+#[async_trait]
+impl HealthCheck for MigrationHealthCheck {
+    async fn check(&self) -> AppResult<bool> {
+        let pending = self.migrations.pending_migrations().await?;
+        if pending.is_empty() {
+            Ok(true)
+        } else {
+            Err(AppError::ServiceUnavailable(format!(
+                "{} pending migration(s): {:?}",
+                pending.len(),
+                pending
+            )))
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        "migrations"
+    }
+
+    fn timeout(&self) -> u64 {
+        self.timeout
+    }
+}
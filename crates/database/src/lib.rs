@@ -1,15 +1,21 @@
 //! Database layer with SQLx integration and migration support
 
+pub mod audit;
 pub mod connection;
+pub mod health;
 pub mod migrations;
 pub mod models;
 pub mod repositories;
+pub mod table;
 
 // Re-export commonly used items
+pub use audit::*;
 pub use connection::*;
+pub use health::*;
 pub use migrations::*;
 pub use models::*;
 pub use repositories::*;
+pub use table::*;
 
 // Re-export SQLx types for convenience
 pub use sqlx::{
@@ -1,9 +1,15 @@
 //! Database migration utilities
 
 use shared::{AppError, AppResult};
-use sqlx::{migrate::MigrateDatabase, PgPool, Postgres};
+use sqlx::{
+    migrate::{MigrateDatabase, Migrator},
+    PgPool, Postgres,
+};
 use tracing::{info, warn};
 
+/// Migrations embedded from the workspace `migrations` directory
+pub static MIGRATOR: Migrator = sqlx::migrate!("../../migrations");
+
 /// Migration manager for handling database schema changes
 pub struct MigrationManager {
     pool: PgPool,
@@ -19,7 +25,7 @@ impl MigrationManager {
     pub async fn migrate(&self) -> AppResult<()> {
         info!("Starting database migrations");
 
-        sqlx::migrate!("./migrations")
+        MIGRATOR
             .run(&self.pool)
             .await
             .map_err(|e| AppError::Database(e))?;
@@ -85,6 +91,21 @@ impl MigrationManager {
         Ok(migrations)
     }
 
+    /// Versions of embedded migrations that have not been applied successfully
+    pub async fn pending_migrations(&self) -> AppResult<Vec<i64>> {
+        let applied: Vec<i64> = sqlx::query_scalar("SELECT version FROM _sqlx_migrations WHERE success")
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| AppError::Database(e))?;
+
+        Ok(MIGRATOR
+            .iter()
+            .filter(|migration| !migration.migration_type.is_down_migration())
+            .map(|migration| migration.version)
+            .filter(|version| !applied.contains(version))
+            .collect())
+    }
+
     /// Check if migrations are up to date
     pub async fn is_up_to_date(&self) -> AppResult<bool> {
         // This is a simplified check - in a real implementation,
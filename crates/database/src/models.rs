@@ -2,10 +2,13 @@
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use shared::{Entity, MultiTenant, SoftDelete, TenantId, UserId};
+use shared::{AppResult, Entity, EventMetadata, MultiTenant, SoftDelete, TenantId, UserId};
+use sea_query::SimpleExpr;
 use sqlx::FromRow;
 use uuid::Uuid;
 
+use crate::{audit::{redact, Auditable}, table::{PgEnum, Table}};
+
 /// User entity
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct User {
@@ -46,12 +49,45 @@ impl MultiTenant for User {
     }
 }
 
+impl Auditable for User {
+    const RESOURCE_TYPE: &'static str = "user";
+
+    fn audit_tenant_id(&self) -> TenantId {
+        self.tenant_id
+    }
+}
+
 impl SoftDelete for User {
     fn deleted_at(&self) -> &Option<DateTime<Utc>> {
         &self.deleted_at
     }
 }
 
+impl Table for User {
+    const NAME: &'static str = "users";
+    const COLUMNS: &'static [&'static str] = &[
+        "id", "tenant_id", "email", "username", "password_hash", "first_name", "last_name",
+        "is_active", "is_verified", "last_login_at", "created_at", "updated_at", "deleted_at",
+    ];
+    const DELETED_AT: Option<&'static str> = Some("deleted_at");
+
+    fn insert_values(&self) -> Vec<(&'static str, SimpleExpr)> {
+        vec![
+            ("id", self.id.into()),
+            ("tenant_id", self.tenant_id.into()),
+            ("email", self.email.clone().into()),
+            ("username", self.username.clone().into()),
+            ("password_hash", self.password_hash.clone().into()),
+            ("first_name", self.first_name.clone().into()),
+            ("last_name", self.last_name.clone().into()),
+            ("is_active", self.is_active.into()),
+            ("is_verified", self.is_verified.into()),
+            ("created_at", self.created_at.into()),
+            ("updated_at", self.updated_at.into()),
+        ]
+    }
+}
+
 /// Order entity
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct Order {
@@ -93,12 +129,47 @@ impl MultiTenant for Order {
     }
 }
 
+impl Auditable for Order {
+    const RESOURCE_TYPE: &'static str = "order";
+
+    fn audit_tenant_id(&self) -> TenantId {
+        self.tenant_id
+    }
+}
+
 impl SoftDelete for Order {
     fn deleted_at(&self) -> &Option<DateTime<Utc>> {
         &self.deleted_at
     }
 }
 
+impl Table for Order {
+    const NAME: &'static str = "orders";
+    const COLUMNS: &'static [&'static str] = &[
+        "id", "tenant_id", "user_id", "order_number", "status", "total_amount", "currency", "items",
+        "shipping_address", "billing_address", "notes", "created_at", "updated_at", "deleted_at",
+    ];
+    const DELETED_AT: Option<&'static str> = Some("deleted_at");
+
+    fn insert_values(&self) -> Vec<(&'static str, SimpleExpr)> {
+        vec![
+            ("id", self.id.into()),
+            ("tenant_id", self.tenant_id.into()),
+            ("user_id", self.user_id.into()),
+            ("order_number", self.order_number.clone().into()),
+            ("status", self.status.to_expr()),
+            ("total_amount", self.total_amount.into()),
+            ("currency", self.currency.clone().into()),
+            ("items", self.items.clone().into()),
+            ("shipping_address", self.shipping_address.clone().into()),
+            ("billing_address", self.billing_address.clone().into()),
+            ("notes", self.notes.clone().into()),
+            ("created_at", self.created_at.into()),
+            ("updated_at", self.updated_at.into()),
+        ]
+    }
+}
+
 /// Order status enum
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::Type)]
 #[sqlx(type_name = "order_status", rename_all = "lowercase")]
@@ -112,6 +183,22 @@ pub enum OrderStatus {
     Refunded,
 }
 
+impl PgEnum for OrderStatus {
+    const TYPE_NAME: &'static str = "order_status";
+
+    fn label(&self) -> &'static str {
+        match self {
+            OrderStatus::Pending => "pending",
+            OrderStatus::Confirmed => "confirmed",
+            OrderStatus::Processing => "processing",
+            OrderStatus::Shipped => "shipped",
+            OrderStatus::Delivered => "delivered",
+            OrderStatus::Cancelled => "cancelled",
+            OrderStatus::Refunded => "refunded",
+        }
+    }
+}
+
 /// Payment entity
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct Payment {
@@ -153,6 +240,14 @@ impl MultiTenant for Payment {
     }
 }
 
+impl Auditable for Payment {
+    const RESOURCE_TYPE: &'static str = "payment";
+
+    fn audit_tenant_id(&self) -> TenantId {
+        self.tenant_id
+    }
+}
+
 /// Payment method enum
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::Type)]
 #[sqlx(type_name = "payment_method", rename_all = "lowercase")]
@@ -189,6 +284,10 @@ pub struct Job {
     pub error: Option<String>,
     pub retry_count: i32,
     pub max_retries: i32,
+    /// Correlation id of the request that enqueued the job
+    pub correlation_id: Option<Uuid>,
+    /// W3C `traceparent` of the span that enqueued the job
+    pub traceparent: Option<String>,
     pub scheduled_at: DateTime<Utc>,
     pub started_at: Option<DateTime<Utc>>,
     pub completed_at: Option<DateTime<Utc>>,
@@ -212,8 +311,33 @@ impl Entity for Job {
     }
 }
 
+impl Table for Job {
+    const NAME: &'static str = "jobs";
+    const COLUMNS: &'static [&'static str] = &[
+        "id", "tenant_id", "job_type", "status", "payload", "result", "error", "retry_count", "max_retries",
+        "correlation_id", "traceparent", "scheduled_at", "started_at", "completed_at", "created_at", "updated_at",
+    ];
+
+    fn insert_values(&self) -> Vec<(&'static str, SimpleExpr)> {
+        vec![
+            ("id", self.id.into()),
+            ("tenant_id", self.tenant_id.into()),
+            ("job_type", self.job_type.clone().into()),
+            ("status", self.status.to_expr()),
+            ("payload", self.payload.clone().into()),
+            ("retry_count", self.retry_count.into()),
+            ("max_retries", self.max_retries.into()),
+            ("correlation_id", self.correlation_id.into()),
+            ("traceparent", self.traceparent.clone().into()),
+            ("scheduled_at", self.scheduled_at.into()),
+            ("created_at", self.created_at.into()),
+            ("updated_at", self.updated_at.into()),
+        ]
+    }
+}
+
 /// Job status enum
-#[derive(Debug, Clone, Serialize, Deserialize, sqlx::Type)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
 #[sqlx(type_name = "job_status", rename_all = "lowercase")]
 pub enum JobStatus {
     Pending,
@@ -223,6 +347,28 @@ pub enum JobStatus {
     Cancelled,
 }
 
+impl PgEnum for JobStatus {
+    const TYPE_NAME: &'static str = "job_status";
+
+    fn label(&self) -> &'static str {
+        match self {
+            JobStatus::Pending => "pending",
+            JobStatus::Running => "running",
+            JobStatus::Completed => "completed",
+            JobStatus::Failed => "failed",
+            JobStatus::Cancelled => "cancelled",
+        }
+    }
+}
+
+/// Job query filters; unset fields match every job
+#[derive(Debug, Clone, Default)]
+pub struct JobFilter {
+    pub status: Option<JobStatus>,
+    pub job_type: Option<String>,
+    pub tenant_id: Option<TenantId>,
+}
+
 /// Event entity for event sourcing
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct Event {
@@ -256,6 +402,29 @@ impl Entity for Event {
     }
 }
 
+impl Event {
+    /// Row recording the change to `aggregate` described by `metadata`.
+    ///
+    /// Sensitive fields of the aggregate are redacted from the payload. `version` and
+    /// `created_at` are assigned when the event is appended.
+    pub fn recorded<T: Auditable>(metadata: &EventMetadata, aggregate: &T) -> AppResult<Self> {
+        Ok(Self {
+            id: metadata.event_id,
+            tenant_id: metadata.tenant_id,
+            event_type: metadata.event_type.clone(),
+            aggregate_id: *aggregate.id(),
+            aggregate_type: T::RESOURCE_TYPE.to_string(),
+            version: 0,
+            payload: redact(serde_json::to_value(aggregate)?),
+            metadata: serde_json::to_value(metadata)?,
+            correlation_id: metadata.correlation_id,
+            causation_id: None,
+            user_id: metadata.user_id,
+            created_at: metadata.timestamp,
+        })
+    }
+}
+
 /// Session entity for user sessions
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct Session {
@@ -327,6 +496,65 @@ impl Entity for AuditLog {
     }
 }
 
+/// Audit log query filters; unset fields match every entry
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AuditLogFilter {
+    pub user_id: Option<UserId>,
+    pub action: Option<String>,
+    pub resource_type: Option<String>,
+    pub resource_id: Option<Uuid>,
+    /// Entries created at or after this time
+    pub from: Option<DateTime<Utc>>,
+    /// Entries created before this time
+    pub to: Option<DateTime<Utc>>,
+}
+
+/// Metadata of an uploaded file; the contents are kept in object storage under `storage_key`
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct StoredFile {
+    pub id: Uuid,
+    pub tenant_id: TenantId,
+    pub uploaded_by: Option<UserId>,
+    pub file_name: String,
+    /// Content type detected from the contents
+    pub content_type: String,
+    pub size_bytes: i64,
+    /// Hex-encoded SHA-256 of the contents
+    pub checksum_sha256: String,
+    pub storage_key: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub deleted_at: Option<DateTime<Utc>>,
+}
+
+impl Entity for StoredFile {
+    type Id = Uuid;
+
+    fn id(&self) -> &Self::Id {
+        &self.id
+    }
+
+    fn created_at(&self) -> &DateTime<Utc> {
+        &self.created_at
+    }
+
+    fn updated_at(&self) -> &DateTime<Utc> {
+        &self.updated_at
+    }
+}
+
+impl MultiTenant for StoredFile {
+    fn tenant_id(&self) -> &TenantId {
+        &self.tenant_id
+    }
+}
+
+impl SoftDelete for StoredFile {
+    fn deleted_at(&self) -> &Option<DateTime<Utc>> {
+        &self.deleted_at
+    }
+}
+
 /// Tenant entity for multi-tenancy
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct Tenant {
@@ -363,6 +591,14 @@ impl SoftDelete for Tenant {
     }
 }
 
+impl Auditable for Tenant {
+    const RESOURCE_TYPE: &'static str = "tenant";
+
+    fn audit_tenant_id(&self) -> TenantId {
+        self.id
+    }
+}
+
 /// Create user DTO
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CreateUserDto {
@@ -0,0 +1,396 @@
+//! Generic repository over tables described by a [`Table`]
+//!
+//! Statements are built with sea-query from the table's descriptor and bound to sqlx at run
+//! time, so column lists, soft-delete handling and pagination are written once rather than in
+//! every query. Filters are sea-query [`Condition`]s; sort columns are checked against the
+//! table's columns, so they may come from API input.
+
+use sea_query::{
+    Alias, Asterisk, Condition, Expr, Order, PostgresQueryBuilder, Query, SelectStatement, SimpleExpr, Value, Values,
+};
+use shared::{AppError, AppResult, PaginatedResponse, PaginationInfo, PaginationParams, DEFAULT_PAGE_SIZE};
+use sqlx::{
+    postgres::{PgArguments, PgRow},
+    Arguments, FromRow, PgPool,
+};
+use std::marker::PhantomData;
+use uuid::Uuid;
+
+/// How an entity is stored: its table, the columns read into it and the values written
+pub trait Table: for<'r> FromRow<'r, PgRow> + Send + Unpin {
+    /// Table name
+    const NAME: &'static str;
+
+    /// Columns read into the entity, in `SELECT` and `RETURNING` lists
+    const COLUMNS: &'static [&'static str];
+
+    const PRIMARY_KEY: &'static str = "id";
+
+    /// Column set to the current time on every update
+    const UPDATED_AT: Option<&'static str> = Some("updated_at");
+
+    /// Column marking rows as deleted; such rows are never read and deleting only sets it
+    const DELETED_AT: Option<&'static str> = None;
+
+    /// Columns and values of a new row
+    fn insert_values(&self) -> Vec<(&'static str, SimpleExpr)>;
+}
+
+/// Postgres enum type a Rust enum is stored as
+pub trait PgEnum {
+    const TYPE_NAME: &'static str;
+
+    /// Label of the variant in the Postgres type
+    fn label(&self) -> &'static str;
+
+    /// The variant cast to the Postgres type, for writing or comparing with a column
+    fn to_expr(&self) -> SimpleExpr {
+        Expr::val(self.label()).as_enum(Alias::new(Self::TYPE_NAME))
+    }
+}
+
+/// Sort key of a query
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sort {
+    pub column: String,
+    pub descending: bool,
+}
+
+impl Sort {
+    pub fn asc(column: impl Into<String>) -> Self {
+        Self {
+            column: column.into(),
+            descending: false,
+        }
+    }
+
+    pub fn desc(column: impl Into<String>) -> Self {
+        Self {
+            column: column.into(),
+            descending: true,
+        }
+    }
+}
+
+/// Column reference for use in filters and update values
+pub fn col(name: &str) -> Expr {
+    Expr::col(Alias::new(name))
+}
+
+/// Repository reading and writing the entities of one [`Table`]
+pub struct TableRepository<T> {
+    pool: PgPool,
+    table: PhantomData<fn() -> T>,
+}
+
+impl<T> Clone for TableRepository<T> {
+    fn clone(&self) -> Self {
+        Self {
+            pool: self.pool.clone(),
+            table: PhantomData,
+        }
+    }
+}
+
+impl<T: Table> TableRepository<T> {
+    pub fn new(pool: PgPool) -> Self {
+        Self {
+            pool,
+            table: PhantomData,
+        }
+    }
+
+    pub fn pool(&self) -> &PgPool {
+        &self.pool
+    }
+
+    /// Find a row by primary key
+    pub async fn find_by_id(&self, id: &Uuid) -> AppResult<Option<T>> {
+        self.find_one(Condition::all().add(col(T::PRIMARY_KEY).eq(*id))).await
+    }
+
+    /// Find the first row matching `filter`
+    pub async fn find_one(&self, filter: Condition) -> AppResult<Option<T>> {
+        let (sql, values) = select::<T>(filter, &[], Some(1), None)?;
+        Ok(sqlx::query_as_with(&sql, bind(values)?).fetch_optional(&self.pool).await?)
+    }
+
+    /// Find rows matching `filter`, ordered by `sort`
+    pub async fn find_many(&self, filter: Condition, sort: &[Sort], limit: Option<u64>) -> AppResult<Vec<T>> {
+        let (sql, values) = select::<T>(filter, sort, limit, None)?;
+        Ok(sqlx::query_as_with(&sql, bind(values)?).fetch_all(&self.pool).await?)
+    }
+
+    /// Find a page of rows matching `filter`, ordered by `sort`, with the total count
+    pub async fn find_page(
+        &self,
+        filter: Condition,
+        sort: &[Sort],
+        params: &PaginationParams,
+    ) -> AppResult<PaginatedResponse<T>> {
+        let limit = params.limit.unwrap_or(DEFAULT_PAGE_SIZE);
+        let offset = params.offset.unwrap_or(0);
+
+        let (sql, values) = select::<T>(filter.clone(), sort, Some(limit as u64), Some(offset as u64))?;
+        let data = sqlx::query_as_with(&sql, bind(values)?).fetch_all(&self.pool).await?;
+        let total = self.count(filter).await?;
+
+        Ok(PaginatedResponse {
+            data,
+            pagination: page_info(limit, offset, total),
+        })
+    }
+
+    /// Count rows matching `filter`
+    pub async fn count(&self, filter: Condition) -> AppResult<u64> {
+        let (sql, values) = count::<T>(filter);
+        let count: i64 = sqlx::query_scalar_with(&sql, bind(values)?).fetch_one(&self.pool).await?;
+        Ok(count as u64)
+    }
+
+    /// Whether any row matches `filter`
+    pub async fn exists(&self, filter: Condition) -> AppResult<bool> {
+        let (sql, values) = exists::<T>(filter);
+        Ok(sqlx::query_scalar_with(&sql, bind(values)?).fetch_one(&self.pool).await?)
+    }
+
+    /// Insert a row, returning it as stored
+    pub async fn insert(&self, entity: &T) -> AppResult<T> {
+        let (sql, values) = insert(entity)?;
+        Ok(sqlx::query_as_with(&sql, bind(values)?).fetch_one(&self.pool).await?)
+    }
+
+    /// Set `values` on the row matching `filter`, returning it as updated, or `None` when no
+    /// row matches. `filter` should match at most one row.
+    pub async fn update(&self, filter: Condition, values: Vec<(&'static str, SimpleExpr)>) -> AppResult<Option<T>> {
+        let (sql, values) = update::<T>(filter, values, true);
+        Ok(sqlx::query_as_with(&sql, bind(values)?).fetch_optional(&self.pool).await?)
+    }
+
+    /// Set `values` on every row matching `filter`, returning how many were updated
+    pub async fn update_all(&self, filter: Condition, values: Vec<(&'static str, SimpleExpr)>) -> AppResult<u64> {
+        let (sql, values) = update::<T>(filter, values, false);
+        Ok(sqlx::query_with(&sql, bind(values)?).execute(&self.pool).await?.rows_affected())
+    }
+
+    /// Delete rows matching `filter`, soft-deleting them when the table supports it; returns
+    /// how many were deleted
+    pub async fn delete(&self, filter: Condition) -> AppResult<u64> {
+        let (sql, values) = delete::<T>(filter);
+        Ok(sqlx::query_with(&sql, bind(values)?).execute(&self.pool).await?.rows_affected())
+    }
+}
+
+/// `filter`, excluding soft-deleted rows; `None` when every row matches
+fn visible<T: Table>(filter: Condition) -> Option<Condition> {
+    let mut condition = Condition::all();
+    if !filter.is_empty() {
+        condition = condition.add(filter);
+    }
+    let condition = condition.add_option(T::DELETED_AT.map(|deleted_at| col(deleted_at).is_null()));
+    (!condition.is_empty()).then_some(condition)
+}
+
+fn select_from<T: Table>(filter: Condition) -> SelectStatement {
+    let mut query = Query::select();
+    query
+        .columns(T::COLUMNS.iter().map(|column| Alias::new(*column)))
+        .from(Alias::new(T::NAME));
+    if let Some(condition) = visible::<T>(filter) {
+        query.cond_where(condition);
+    }
+    query
+}
+
+fn select<T: Table>(filter: Condition, sort: &[Sort], limit: Option<u64>, offset: Option<u64>) -> AppResult<(String, Values)> {
+    let mut query = select_from::<T>(filter);
+    for key in sort {
+        if !T::COLUMNS.contains(&key.column.as_str()) {
+            return Err(AppError::Validation(format!("Cannot sort by unknown column '{}'", key.column)));
+        }
+        let order = if key.descending { Order::Desc } else { Order::Asc };
+        query.order_by(Alias::new(key.column.as_str()), order);
+    }
+    // Keep pages stable when sort keys tie
+    if !sort.is_empty() && !sort.iter().any(|key| key.column == T::PRIMARY_KEY) {
+        let order = if sort[sort.len() - 1].descending { Order::Desc } else { Order::Asc };
+        query.order_by(Alias::new(T::PRIMARY_KEY), order);
+    }
+    if let Some(limit) = limit {
+        query.limit(limit);
+    }
+    if let Some(offset) = offset {
+        query.offset(offset);
+    }
+
+    Ok(query.build(PostgresQueryBuilder))
+}
+
+fn count<T: Table>(filter: Condition) -> (String, Values) {
+    let mut query = Query::select();
+    query.expr(Expr::col(Asterisk).count()).from(Alias::new(T::NAME));
+    if let Some(condition) = visible::<T>(filter) {
+        query.cond_where(condition);
+    }
+    query.build(PostgresQueryBuilder)
+}
+
+fn exists<T: Table>(filter: Condition) -> (String, Values) {
+    Query::select()
+        .expr(Expr::exists(select_from::<T>(filter)))
+        .build(PostgresQueryBuilder)
+}
+
+fn insert<T: Table>(entity: &T) -> AppResult<(String, Values)> {
+    let (columns, values): (Vec<_>, Vec<_>) = entity
+        .insert_values()
+        .into_iter()
+        .map(|(column, value)| (Alias::new(column), value))
+        .unzip();
+
+    let query = Query::insert()
+        .into_table(Alias::new(T::NAME))
+        .columns(columns)
+        .values(values)
+        .map_err(|e| AppError::Internal(format!("Invalid insert into {}: {}", T::NAME, e)))?
+        .returning(Query::returning().columns(T::COLUMNS.iter().map(|column| Alias::new(*column))))
+        .to_owned();
+
+    Ok(query.build(PostgresQueryBuilder))
+}
+
+fn update<T: Table>(filter: Condition, values: Vec<(&'static str, SimpleExpr)>, returning: bool) -> (String, Values) {
+    let mut query = Query::update();
+    query
+        .table(Alias::new(T::NAME))
+        .values(values.into_iter().map(|(column, value)| (Alias::new(column), value)));
+    if let Some(condition) = visible::<T>(filter) {
+        query.cond_where(condition);
+    }
+    if let Some(updated_at) = T::UPDATED_AT {
+        query.value(Alias::new(updated_at), Expr::current_timestamp());
+    }
+    if returning {
+        query.returning(Query::returning().columns(T::COLUMNS.iter().map(|column| Alias::new(*column))));
+    }
+
+    query.build(PostgresQueryBuilder)
+}
+
+fn delete<T: Table>(filter: Condition) -> (String, Values) {
+    match T::DELETED_AT {
+        Some(deleted_at) => update::<T>(filter, vec![(deleted_at, Expr::current_timestamp().into())], false),
+        None => {
+            let mut query = Query::delete();
+            query.from_table(Alias::new(T::NAME));
+            if !filter.is_empty() {
+                query.cond_where(filter);
+            }
+            query.build(PostgresQueryBuilder)
+        }
+    }
+}
+
+fn page_info(limit: u32, offset: u32, total: u64) -> PaginationInfo {
+    PaginationInfo {
+        total: Some(total),
+        limit,
+        offset,
+        has_next: (offset as u64 + limit as u64) < total,
+        has_prev: offset > 0,
+        next_cursor: None,
+        prev_cursor: None,
+    }
+}
+
+/// Bind values collected by sea-query as sqlx arguments.
+///
+/// Postgres has no unsigned types, so unsigned values are widened to the next signed type.
+fn bind(values: Values) -> AppResult<PgArguments> {
+    let mut arguments = PgArguments::default();
+    for value in values {
+        match value {
+            Value::Bool(v) => arguments.add(v),
+            Value::TinyInt(v) => arguments.add(v.map(i16::from)),
+            Value::SmallInt(v) => arguments.add(v),
+            Value::Int(v) => arguments.add(v),
+            Value::BigInt(v) => arguments.add(v),
+            Value::TinyUnsigned(v) => arguments.add(v.map(i16::from)),
+            Value::SmallUnsigned(v) => arguments.add(v.map(i32::from)),
+            Value::Unsigned(v) => arguments.add(v.map(i64::from)),
+            Value::BigUnsigned(v) => arguments.add(
+                v.map(i64::try_from)
+                    .transpose()
+                    .map_err(|_| AppError::Validation("Integer value out of range".to_string()))?,
+            ),
+            Value::Float(v) => arguments.add(v),
+            Value::Double(v) => arguments.add(v),
+            Value::String(v) => arguments.add(v.map(|v| *v)),
+            Value::Char(v) => arguments.add(v.map(String::from)),
+            Value::Bytes(v) => arguments.add(v.map(|v| *v)),
+            Value::Json(v) => arguments.add(v.map(|v| *v)),
+            Value::ChronoDate(v) => arguments.add(v.map(|v| *v)),
+            Value::ChronoTime(v) => arguments.add(v.map(|v| *v)),
+            Value::ChronoDateTime(v) => arguments.add(v.map(|v| *v)),
+            Value::ChronoDateTimeUtc(v) => arguments.add(v.map(|v| *v)),
+            Value::ChronoDateTimeLocal(v) => arguments.add(v.map(|v| *v)),
+            Value::ChronoDateTimeWithTimeZone(v) => arguments.add(v.map(|v| *v)),
+            Value::Uuid(v) => arguments.add(v.map(|v| *v)),
+            // Types of sea-query features this crate does not enable
+            #[allow(unreachable_patterns)]
+            other => return Err(AppError::Internal(format!("Unsupported query value {:?}", other))),
+        }
+    }
+    Ok(arguments)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Job, JobStatus, User};
+
+    #[test]
+    fn test_select_excludes_deleted_rows() {
+        let filter = Condition::all().add(col("email").eq("jane@example.com"));
+        let (sql, values) = select::<User>(filter, &[Sort::desc("created_at")], Some(20), Some(40)).unwrap();
+
+        assert!(sql.starts_with(r#"SELECT "id", "tenant_id", "email""#));
+        assert!(sql.ends_with(
+            r#"FROM "users" WHERE "email" = $1 AND "deleted_at" IS NULL ORDER BY "created_at" DESC, "id" DESC LIMIT $2 OFFSET $3"#
+        ));
+        assert_eq!(values.0.len(), 3);
+    }
+
+    #[test]
+    fn test_unknown_sort_column_refused() {
+        let result = select::<User>(Condition::all(), &[Sort::asc("password_hash; DROP TABLE users")], None, None);
+        assert!(matches!(result, Err(AppError::Validation(_))));
+    }
+
+    #[test]
+    fn test_delete_and_update() {
+        let by_id = || Condition::all().add(col("id").eq(Uuid::nil()));
+
+        let (sql, _) = delete::<User>(by_id());
+        assert_eq!(
+            sql,
+            r#"UPDATE "users" SET "deleted_at" = CURRENT_TIMESTAMP, "updated_at" = CURRENT_TIMESTAMP WHERE "id" = $1 AND "deleted_at" IS NULL"#
+        );
+
+        let (sql, _) = delete::<Job>(by_id());
+        assert_eq!(sql, r#"DELETE FROM "jobs" WHERE "id" = $1"#);
+
+        let (sql, values) = update::<Job>(by_id(), vec![("status", JobStatus::Running.to_expr())], true);
+        assert!(sql.starts_with(
+            r#"UPDATE "jobs" SET "status" = CAST($1 AS job_status), "updated_at" = CURRENT_TIMESTAMP WHERE "id" = $2 RETURNING "id""#
+        ));
+        assert_eq!(values.0[0], Value::String(Some(Box::new("running".to_string()))));
+    }
+
+    #[test]
+    fn test_page_info() {
+        let info = page_info(20, 20, 45);
+        assert!(info.has_next && info.has_prev);
+        assert!(!page_info(20, 40, 45).has_next);
+    }
+}
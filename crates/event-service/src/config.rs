@@ -0,0 +1,110 @@
+//! Event service configuration
+
+use shared::{AppConfig, ValidateConfig};
+use serde::{Deserialize, Serialize};
+
+/// Event service configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EventConfig {
+    /// Base application configuration
+    #[serde(flatten)]
+    pub app: AppConfig,
+
+    /// Event specific settings
+    pub events: EventSettings,
+}
+
+/// Event specific settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EventSettings {
+    /// Time to wait for room in the producer queue, in milliseconds
+    pub queue_timeout_ms: u64,
+
+    /// Producer message timeout in milliseconds
+    pub message_timeout_ms: u64,
+
+    /// Interval between producer flushes, in seconds
+    pub flush_interval: u64,
+
+    /// Topic receiving messages whose handler still fails after retrying
+    pub dead_letter_topic: String,
+}
+
+impl Default for EventConfig {
+    fn default() -> Self {
+        Self {
+            app: AppConfig::default(),
+            events: EventSettings::default(),
+        }
+    }
+}
+
+impl Default for EventSettings {
+    fn default() -> Self {
+        Self {
+            queue_timeout_ms: 5000,
+            message_timeout_ms: 30000,
+            flush_interval: 5,
+            dead_letter_topic: "events.dead-letter".to_string(),
+        }
+    }
+}
+
+impl EventConfig {
+    /// Load event service configuration
+    pub fn load() -> Result<Self, figment::Error> {
+        use figment::{providers::{Env, Format, Yaml}, Figment};
+
+        Figment::new()
+            .merge(Yaml::file("config/event.yml"))
+            .merge(Yaml::file(format!("config/event-{}.yml", std::env::var("ENVIRONMENT").unwrap_or_else(|_| "development".to_string()))))
+            .merge(Env::prefixed("EVENT_"))
+            .extract()
+    }
+
+    /// Get producer queue timeout as Duration
+    pub fn queue_timeout_duration(&self) -> std::time::Duration {
+        std::time::Duration::from_millis(self.events.queue_timeout_ms)
+    }
+
+    /// Get producer flush interval as Duration
+    pub fn flush_interval_duration(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(self.events.flush_interval)
+    }
+
+    /// Validate event configuration
+    pub fn validate(&self) -> Result<(), String> {
+        // Validate base app config
+        self.app.validate()?;
+
+        if self.app.kafka.brokers.is_empty() {
+            return Err("At least one Kafka broker is required".to_string());
+        }
+
+        if self.events.message_timeout_ms == 0 {
+            return Err("Message timeout cannot be zero".to_string());
+        }
+
+        if self.events.flush_interval == 0 {
+            return Err("Flush interval cannot be zero".to_string());
+        }
+
+        if self.events.dead_letter_topic.is_empty() {
+            return Err("Dead letter topic cannot be empty".to_string());
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_event_config_default() {
+        let config = EventConfig::default();
+        assert_eq!(config.events.flush_interval, 5);
+        assert!(config.validate().is_ok());
+    }
+}
@@ -0,0 +1,443 @@
+//! Kafka event consumers
+
+use database::DatabaseManager;
+use rdkafka::{
+    consumer::{CommitMode, Consumer, StreamConsumer},
+    message::{BorrowedMessage, Header, Headers, OwnedHeaders},
+    producer::{FutureProducer, FutureRecord},
+    Message,
+};
+use shared::{
+    context,
+    kafka::client_config,
+    kafka_headers, retries,
+    telemetry::{self, TraceContext, TRACE_CONTEXT_HEADERS},
+    runtime::CancellationToken,
+    AppError, AppResult, CorrelationId,
+};
+use std::{future::Future, time::Duration};
+use tracing::{error, info, warn, Instrument};
+
+use crate::{
+    config::EventConfig,
+    handlers::{self, EventHandlerRegistry, RawEvent},
+};
+
+/// How often and how long to retry a failing handler
+#[derive(Debug, Clone, Copy)]
+struct Backoff {
+    max_attempts: u32,
+    initial_delay: Duration,
+    max_delay: Duration,
+}
+
+impl Default for Backoff {
+    fn default() -> Self {
+        Self {
+            max_attempts: retries::MAX_ATTEMPTS,
+            initial_delay: Duration::from_millis(retries::INITIAL_DELAY_MS),
+            max_delay: Duration::from_millis(retries::MAX_DELAY_MS),
+        }
+    }
+}
+
+/// Consumes events from Kafka and dispatches them to the registered handlers.
+///
+/// Handler failures that may be transient are retried with exponential backoff. A message
+/// that still fails is forwarded to the dead-letter topic before its offset is committed; if
+/// that fails too, the consumer stops without committing, so the message is redelivered when
+/// the consumer is restarted.
+pub struct EventConsumerManager {
+    consumer: StreamConsumer,
+    handlers: EventHandlerRegistry,
+    auto_commit: bool,
+    dead_letters: FutureProducer,
+    dead_letter_topic: String,
+    queue_timeout: Duration,
+    backoff: Backoff,
+}
+
+impl EventConsumerManager {
+    pub async fn new(config: &EventConfig, topics: Vec<String>, database: &DatabaseManager) -> AppResult<Self> {
+        let kafka = &config.app.kafka;
+        let consumer: StreamConsumer = client_config(kafka)
+            .set("group.id", &kafka.group_id)
+            .set("auto.offset.reset", &kafka.auto_offset_reset)
+            .set("enable.auto.commit", kafka.enable_auto_commit.to_string())
+            .set("session.timeout.ms", kafka.session_timeout_ms.to_string())
+            .set("heartbeat.interval.ms", kafka.heartbeat_interval_ms.to_string())
+            .set("max.poll.interval.ms", kafka.max_poll_interval_ms.to_string())
+            .create()
+            .map_err(|e| AppError::Kafka(format!("Failed to create consumer: {}", e)))?;
+
+        let topics: Vec<&str> = topics.iter().map(String::as_str).collect();
+        consumer
+            .subscribe(&topics)
+            .map_err(|e| AppError::Kafka(format!("Failed to subscribe to {:?}: {}", topics, e)))?;
+
+        let dead_letters: FutureProducer = client_config(kafka)
+            .set("message.timeout.ms", config.events.message_timeout_ms.to_string())
+            .create()
+            .map_err(|e| AppError::Kafka(format!("Failed to create dead-letter producer: {}", e)))?;
+
+        Ok(Self {
+            consumer,
+            handlers: handlers::default_registry(database),
+            auto_commit: kafka.enable_auto_commit,
+            dead_letters,
+            dead_letter_topic: config.events.dead_letter_topic.clone(),
+            queue_timeout: config.queue_timeout_duration(),
+            backoff: Backoff::default(),
+        })
+    }
+
+    /// Consume messages until cancelled; the message being handled is finished and committed
+    pub async fn start(&self, token: CancellationToken) -> AppResult<()> {
+        info!("Event consumer started");
+
+        let mut result = Ok(());
+        loop {
+            let received = tokio::select! {
+                _ = token.cancelled() => break,
+                received = self.consumer.recv() => received,
+            };
+            let message = match received {
+                Ok(message) => message,
+                Err(e) => {
+                    error!("Failed to receive message: {}", e);
+                    continue;
+                }
+            };
+
+            let processed = {
+                let message = &message;
+                with_retries(self.backoff, &token, move || self.process(message)).await
+            };
+            match processed {
+                Some(Ok(())) => {}
+                Some(Err(e)) => {
+                    error!(topic = message.topic(), offset = message.offset(), "Event handling failed: {}", e);
+                    if let Err(e) = self.dead_letter(&message, &e).await {
+                        result = Err(e);
+                        break;
+                    }
+                }
+                // Cancelled while waiting to retry; left uncommitted so it is redelivered
+                None => break,
+            }
+
+            if !self.auto_commit {
+                if let Err(e) = self.consumer.commit_message(&message, CommitMode::Async) {
+                    warn!("Failed to commit offset: {}", e);
+                }
+            }
+        }
+
+        // Make sure offsets committed asynchronously are stored before the consumer goes away
+        if !self.auto_commit {
+            if let Err(e) = self.consumer.commit_consumer_state(CommitMode::Sync) {
+                warn!("Failed to commit offsets on shutdown: {}", e);
+            }
+        }
+        info!("Event consumer stopped");
+        result
+    }
+
+    /// Decode and dispatch a message with its correlation id in scope, continuing the
+    /// publisher's trace
+    async fn process(&self, message: &BorrowedMessage<'_>) -> AppResult<()> {
+        let Some(payload) = message.payload() else {
+            warn!(topic = message.topic(), offset = message.offset(), "Skipping message without payload");
+            return Ok(());
+        };
+        let event: RawEvent = serde_json::from_slice(payload)?;
+
+        // Prefer the header so ids survive producers that do not use the shared envelope
+        let correlation_id = header_correlation_id(message).unwrap_or(event.metadata.correlation_id);
+        let span = tracing::info_span!(
+            "event",
+            topic = message.topic(),
+            event_type = %event.metadata.event_type,
+            correlation_id = %correlation_id,
+        );
+        telemetry::set_remote_parent(&span, &header_trace_context(message.headers()));
+
+        let dispatch = async {
+            if !self.handlers.dispatch(&event, correlation_id).await? {
+                warn!("No handler registered for event");
+            }
+            Ok(())
+        };
+
+        context::with_correlation_id(correlation_id, dispatch.instrument(span)).await
+    }
+
+    /// Forward a message that could not be handled to the dead-letter topic
+    async fn dead_letter(&self, message: &BorrowedMessage<'_>, error: &AppError) -> AppResult<()> {
+        let headers = dead_letter_headers(message.headers(), message.topic(), message.partition(), message.offset(), error);
+        let mut record = FutureRecord::to(&self.dead_letter_topic).headers(headers);
+        if let Some(payload) = message.payload() {
+            record = record.payload(payload);
+        }
+        if let Some(key) = message.key() {
+            record = record.key(key);
+        }
+
+        self.dead_letters
+            .send(record, self.queue_timeout)
+            .await
+            .map_err(|(e, _)| AppError::Kafka(format!("Failed to send message to {}: {}", self.dead_letter_topic, e)))?;
+
+        warn!(
+            topic = message.topic(),
+            offset = message.offset(),
+            dead_letter_topic = %self.dead_letter_topic,
+            "Message sent to dead-letter topic"
+        );
+        Ok(())
+    }
+}
+
+/// Run `attempt` until it succeeds, fails with an error retrying cannot fix, or has failed
+/// `max_attempts` times, backing off exponentially in between.
+///
+/// Returns `None` when cancelled while waiting to retry.
+async fn with_retries<F, Fut>(backoff: Backoff, token: &CancellationToken, mut attempt: F) -> Option<AppResult<()>>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = AppResult<()>>,
+{
+    let mut delay = backoff.initial_delay;
+    let mut attempts = 1;
+    loop {
+        match attempt().await {
+            Err(e) if is_transient(&e) && attempts < backoff.max_attempts => {
+                warn!(attempt = attempts, "Event handling failed, retrying in {:?}: {}", delay, e);
+                tokio::select! {
+                    _ = token.cancelled() => return None,
+                    _ = tokio::time::sleep(delay) => {}
+                }
+                delay = delay.mul_f64(retries::BACKOFF_MULTIPLIER).min(backoff.max_delay);
+                attempts += 1;
+            }
+            result => return Some(result),
+        }
+    }
+}
+
+/// Whether handling may succeed when retried; malformed or invalid events never will
+fn is_transient(error: &AppError) -> bool {
+    matches!(
+        error,
+        AppError::Database(_)
+            | AppError::Redis(_)
+            | AppError::Kafka(_)
+            | AppError::Timeout(_)
+            | AppError::RateLimited(_)
+            | AppError::ServiceUnavailable(_)
+            | AppError::ExternalService(_)
+    )
+}
+
+/// Headers of a dead-lettered message: the original ones plus the error and where the
+/// message was consumed from
+fn dead_letter_headers<H: Headers>(
+    original: Option<&H>,
+    topic: &str,
+    partition: i32,
+    offset: i64,
+    error: &AppError,
+) -> OwnedHeaders {
+    let headers = original
+        .into_iter()
+        .flat_map(|headers| headers.iter())
+        .fold(OwnedHeaders::new(), |headers, header| headers.insert(header));
+
+    [
+        (kafka_headers::DEAD_LETTER_ERROR, error.to_string()),
+        (kafka_headers::ORIGINAL_TOPIC, topic.to_string()),
+        (kafka_headers::ORIGINAL_PARTITION, partition.to_string()),
+        (kafka_headers::ORIGINAL_OFFSET, offset.to_string()),
+    ]
+    .into_iter()
+    .fold(headers, |headers, (key, value)| headers.insert(Header { key, value: Some(&value) }))
+}
+
+/// Correlation id carried in the message headers, if present and well-formed
+fn header_correlation_id(message: &BorrowedMessage<'_>) -> Option<CorrelationId> {
+    message
+        .headers()?
+        .iter()
+        .find(|header| header.key == kafka_headers::CORRELATION_ID)
+        .and_then(|header| header.value)
+        .and_then(|value| std::str::from_utf8(value).ok())
+        .and_then(context::parse_correlation_id)
+}
+
+/// W3C trace context carried in the message headers
+fn header_trace_context<H: Headers>(headers: Option<&H>) -> TraceContext {
+    let Some(headers) = headers else {
+        return TraceContext::new();
+    };
+
+    headers
+        .iter()
+        .filter(|header| TRACE_CONTEXT_HEADERS.contains(&header.key))
+        .filter_map(|header| {
+            let value = std::str::from_utf8(header.value?).ok()?;
+            Some((header.key.to_string(), value.to_string()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{handlers::payment_job, producers::event_headers};
+    use async_trait::async_trait;
+    use opentelemetry::trace::TracerProvider as _;
+    use opentelemetry_sdk::trace::TracerProvider;
+    use shared::{events, services, Event, EventHandler};
+    use std::sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc,
+    };
+    use tracing_subscriber::layer::SubscriberExt;
+    use uuid::Uuid;
+
+    const BACKOFF: Backoff = Backoff {
+        max_attempts: 3,
+        initial_delay: Duration::from_millis(1),
+        max_delay: Duration::from_millis(5),
+    };
+
+    /// Fails its first `failures` calls with the error built by `error`
+    struct FlakyHandler {
+        calls: AtomicU32,
+        failures: u32,
+        error: fn() -> AppError,
+    }
+
+    impl FlakyHandler {
+        fn new(failures: u32, error: fn() -> AppError) -> Arc<Self> {
+            Arc::new(Self { calls: AtomicU32::new(0), failures, error })
+        }
+    }
+
+    #[async_trait]
+    impl EventHandler<RawEvent> for FlakyHandler {
+        async fn handle(&self, _event: &RawEvent, _correlation_id: CorrelationId) -> AppResult<()> {
+            if self.calls.fetch_add(1, Ordering::SeqCst) < self.failures {
+                Err((self.error)())
+            } else {
+                Ok(())
+            }
+        }
+
+        fn event_type(&self) -> &'static str {
+            events::PAYMENT_CREATED
+        }
+    }
+
+    async fn handle(handler: Arc<FlakyHandler>, token: &CancellationToken) -> Option<AppResult<()>> {
+        let registry = EventHandlerRegistry::new().register(handler);
+        let event = Event::new(events::PAYMENT_CREATED, services::API, Uuid::new_v4(), serde_json::json!({}));
+        let (registry, event) = (&registry, &event);
+
+        with_retries(BACKOFF, token, move || async move {
+            registry.dispatch(event, event.metadata.correlation_id).await.map(|_| ())
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_transient_handler_failure_is_retried() {
+        let handler = FlakyHandler::new(2, || AppError::Timeout("database".to_string()));
+
+        let result = handle(handler.clone(), &CancellationToken::new()).await;
+
+        assert!(matches!(result, Some(Ok(()))));
+        assert_eq!(handler.calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_handler_failure_is_returned_once_retries_are_exhausted() {
+        let handler = FlakyHandler::new(u32::MAX, || AppError::Timeout("database".to_string()));
+
+        let result = handle(handler.clone(), &CancellationToken::new()).await;
+
+        assert!(matches!(result, Some(Err(AppError::Timeout(_)))));
+        assert_eq!(handler.calls.load(Ordering::SeqCst), BACKOFF.max_attempts);
+
+        // Invalid events are not retried
+        let handler = FlakyHandler::new(u32::MAX, || AppError::Validation("missing payment id".to_string()));
+        let result = handle(handler.clone(), &CancellationToken::new()).await;
+        assert!(matches!(result, Some(Err(AppError::Validation(_)))));
+        assert_eq!(handler.calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_retry_abandoned_on_shutdown() {
+        let handler = FlakyHandler::new(u32::MAX, || AppError::Timeout("database".to_string()));
+        let token = CancellationToken::new();
+        token.cancel();
+
+        assert!(handle(handler.clone(), &token).await.is_none());
+        assert_eq!(handler.calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_dead_letter_headers_keep_original_headers() {
+        let event = Event::new(events::PAYMENT_CREATED, services::API, Uuid::new_v4(), serde_json::json!({}));
+        let original = event_headers(&event.metadata);
+        let error = AppError::Validation("missing payment id".to_string());
+
+        let headers = dead_letter_headers(Some(&original), "events", 2, 42, &error);
+
+        let value = |key: &str| {
+            headers
+                .iter()
+                .find(|header| header.key == key)
+                .and_then(|header| header.value)
+                .map(|value| String::from_utf8_lossy(value).into_owned())
+        };
+        assert_eq!(value(kafka_headers::EVENT_ID), Some(event.metadata.event_id.to_string()));
+        assert_eq!(value(kafka_headers::DEAD_LETTER_ERROR), Some(error.to_string()));
+        assert_eq!(value(kafka_headers::ORIGINAL_TOPIC).as_deref(), Some("events"));
+        assert_eq!(value(kafka_headers::ORIGINAL_PARTITION).as_deref(), Some("2"));
+        assert_eq!(value(kafka_headers::ORIGINAL_OFFSET).as_deref(), Some("42"));
+    }
+
+    #[test]
+    fn test_trace_context_carried_from_message_to_job() {
+        let provider = TracerProvider::builder().build();
+        let subscriber = tracing_subscriber::registry().with(tracing_opentelemetry::layer().with_tracer(provider.tracer("test")));
+
+        tracing::subscriber::with_default(subscriber, || {
+            let payment = serde_json::json!({
+                "id": Uuid::new_v4(),
+                "tenant_id": Uuid::new_v4(),
+                "amount": 4200,
+                "currency": "EUR",
+                "payment_method": "CreditCard",
+            });
+            let event = Event::new(events::PAYMENT_CREATED, services::API, Uuid::new_v4(), payment);
+
+            // api-service publishes the event from within the request's span
+            let request = tracing::info_span!("request");
+            let traceparent = request.in_scope(telemetry::current_traceparent).unwrap();
+            let headers = request.in_scope(|| event_headers(&event.metadata));
+
+            // event-service continues the trace and stores it on the job for the worker
+            let span = tracing::info_span!("event");
+            telemetry::set_remote_parent(&span, &header_trace_context(Some(&headers)));
+            let job = span.in_scope(|| payment_job(&event, event.metadata.correlation_id)).unwrap();
+
+            let trace_id = traceparent.split('-').nth(1).unwrap();
+            let job_traceparent = job.traceparent.unwrap();
+            assert_eq!(job_traceparent.split('-').nth(1), Some(trace_id));
+            assert_ne!(job_traceparent, traceparent);
+            assert_eq!(job.correlation_id, Some(event.metadata.correlation_id));
+        });
+    }
+}
@@ -0,0 +1,182 @@
+//! Event handlers dispatched by the consumers
+
+use async_trait::async_trait;
+use chrono::Utc;
+use database::{DatabaseManager, Job, JobRepository, JobStatus};
+use serde::Deserialize;
+use shared::{events, jobs, retries, telemetry, AppResult, CorrelationId, Event, EventHandler, Repository, TenantId};
+use std::{collections::HashMap, sync::Arc};
+use tracing::info;
+use uuid::Uuid;
+
+/// Event envelope as received from Kafka
+pub type RawEvent = Event<serde_json::Value>;
+
+/// Handler for decoded events
+pub type BoxedEventHandler = Arc<dyn EventHandler<RawEvent> + Send + Sync>;
+
+/// Routes events to the handler registered for their type
+#[derive(Clone, Default)]
+pub struct EventHandlerRegistry {
+    handlers: HashMap<&'static str, BoxedEventHandler>,
+    fallback: Option<BoxedEventHandler>,
+}
+
+impl EventHandlerRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a handler for the event type it declares
+    pub fn register(mut self, handler: BoxedEventHandler) -> Self {
+        self.handlers.insert(handler.event_type(), handler);
+        self
+    }
+
+    /// Handler used for event types without a dedicated handler
+    pub fn fallback(mut self, handler: BoxedEventHandler) -> Self {
+        self.fallback = Some(handler);
+        self
+    }
+
+    /// Dispatch an event, returning `false` if no handler accepted it
+    pub async fn dispatch(&self, event: &RawEvent, correlation_id: CorrelationId) -> AppResult<bool> {
+        let handler = self
+            .handlers
+            .get(event.metadata.event_type.as_str())
+            .or(self.fallback.as_ref());
+
+        match handler {
+            Some(handler) => handler.handle(event, correlation_id).await.map(|_| true),
+            None => Ok(false),
+        }
+    }
+}
+
+/// Logs every event it receives
+pub struct LoggingHandler;
+
+#[async_trait]
+impl EventHandler<RawEvent> for LoggingHandler {
+    async fn handle(&self, event: &RawEvent, correlation_id: CorrelationId) -> AppResult<()> {
+        info!(
+            event_id = %event.metadata.event_id,
+            event_type = %event.metadata.event_type,
+            source_service = %event.metadata.source_service,
+            %correlation_id,
+            "Event received"
+        );
+        Ok(())
+    }
+
+    fn event_type(&self) -> &'static str {
+        "*"
+    }
+}
+
+/// Fields of a `payment.created` payload needed to process the payment
+#[derive(Debug, Deserialize)]
+struct PaymentCreated {
+    id: Uuid,
+    tenant_id: TenantId,
+    amount: i64,
+    currency: String,
+    payment_method: String,
+}
+
+/// Enqueues a payment processing job for every payment created through the API
+pub struct PaymentCreatedHandler {
+    jobs: JobRepository,
+}
+
+impl PaymentCreatedHandler {
+    pub fn new(database: &DatabaseManager) -> Self {
+        Self {
+            jobs: JobRepository::new(database.pool().clone()),
+        }
+    }
+}
+
+#[async_trait]
+impl EventHandler<RawEvent> for PaymentCreatedHandler {
+    async fn handle(&self, event: &RawEvent, correlation_id: CorrelationId) -> AppResult<()> {
+        let job = self.jobs.create(&payment_job(event, correlation_id)?).await?;
+        info!(job_id = %job.id, "Payment processing job enqueued");
+        Ok(())
+    }
+
+    fn event_type(&self) -> &'static str {
+        events::PAYMENT_CREATED
+    }
+}
+
+/// Job processing the payment in a `payment.created` event, continuing the current trace
+pub fn payment_job(event: &RawEvent, correlation_id: CorrelationId) -> AppResult<Job> {
+    let payment: PaymentCreated = serde_json::from_value(event.payload.clone())?;
+
+    let now = Utc::now();
+    Ok(Job {
+        id: shared::generate_uuid(),
+        tenant_id: Some(payment.tenant_id),
+        job_type: jobs::PROCESS_PAYMENT.to_string(),
+        status: JobStatus::Pending,
+        payload: serde_json::json!({
+            "payment_id": payment.id,
+            "amount": payment.amount,
+            "currency": payment.currency,
+            "payment_method": payment.payment_method,
+        }),
+        result: None,
+        error: None,
+        retry_count: 0,
+        max_retries: retries::MAX_ATTEMPTS as i32,
+        correlation_id: Some(correlation_id),
+        traceparent: telemetry::current_traceparent(),
+        scheduled_at: now,
+        started_at: None,
+        completed_at: None,
+        created_at: now,
+        updated_at: now,
+    })
+}
+
+/// Registry with the service's default handlers
+pub fn default_registry(database: &DatabaseManager) -> EventHandlerRegistry {
+    EventHandlerRegistry::new()
+        .register(Arc::new(PaymentCreatedHandler::new(database)))
+        .fallback(Arc::new(LoggingHandler))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use uuid::Uuid;
+
+    struct CountingHandler(AtomicUsize);
+
+    #[async_trait]
+    impl EventHandler<RawEvent> for CountingHandler {
+        async fn handle(&self, _event: &RawEvent, _correlation_id: CorrelationId) -> AppResult<()> {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+
+        fn event_type(&self) -> &'static str {
+            "order.created"
+        }
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_by_event_type() {
+        let counter = Arc::new(CountingHandler(AtomicUsize::new(0)));
+        let registry = EventHandlerRegistry::new().register(counter.clone());
+
+        let created = Event::new("order.created", "api-service", Uuid::new_v4(), serde_json::json!({}));
+        let other = Event::new("user.created", "api-service", Uuid::new_v4(), serde_json::json!({}));
+
+        assert!(registry.dispatch(&created, created.metadata.correlation_id).await.unwrap());
+        assert!(!registry.dispatch(&other, other.metadata.correlation_id).await.unwrap());
+        assert_eq!(counter.0.load(Ordering::SeqCst), 1);
+    }
+}
@@ -1,8 +1,17 @@
 //! Event Service - Kafka event streaming service
 
 use anyhow::Result;
-use clap::Parser;
-use shared::{AppConfig, ValidateConfig};
+use clap::{Parser, Subcommand};
+use database::DatabaseManager;
+use figment::Figment;
+use shared::{
+    describe_config,
+    kafka::KafkaHealthCheck,
+    logging,
+    runtime::{shutdown_signal, RestartPolicy, Shutdown, ShutdownPhase, Supervisor},
+    watch_config, AppConfig, CliOverrides, ConfigHandle, HealthRegistry, ValidateConfig,
+};
+use std::{sync::Arc, time::Duration};
 use tracing::{info, warn};
 
 mod config;
@@ -10,12 +19,12 @@ mod consumers;
 mod producers;
 mod handlers;
 
-use config::EventConfig;
+use config::{EventConfig, EventSettings};
 use consumers::EventConsumerManager;
 use producers::EventProducerManager;
 
 /// Event Service CLI arguments
-#[derive(Parser, Debug)]
+#[derive(Parser, Debug, Clone)]
 #[command(name = "event-service")]
 #[command(about = "Kafka event streaming service")]
 struct Args {
@@ -42,6 +51,24 @@ struct Args {
     /// Enable consumer mode
     #[arg(long)]
     consumer: bool,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug, Clone)]
+enum Command {
+    /// Inspect the service configuration
+    #[command(subcommand)]
+    Config(ConfigCommand),
+}
+
+#[derive(Subcommand, Debug, Clone)]
+enum ConfigCommand {
+    /// Load and validate the configuration as startup does, then exit
+    Check,
+    /// Print the effective configuration and where each value came from, with secrets masked
+    Print,
 }
 
 #[tokio::main]
@@ -52,32 +79,40 @@ async fn main() -> Result<()> {
     // Parse CLI arguments
     let args = Args::parse();
 
-    // Initialize configuration
-    let mut config = if args.config == "config" {
-        AppConfig::load()?
-    } else {
-        AppConfig::load_from_path(&args.config)?
-    };
-
-    // Override config with CLI arguments
-    if let Some(environment) = args.environment {
-        config.environment = environment;
+    if let Some(Command::Config(command)) = &args.command {
+        return run_config_command(&args, command);
     }
 
-    if let Some(group_id) = args.group_id {
-        config.kafka.group_id = group_id;
-    }
+    // Initialize configuration
+    let config = load_config(&args).map_err(anyhow::Error::msg)?;
 
     // Validate configuration
-    config.validate()?;
+    config.validate().map_err(anyhow::Error::msg)?;
 
     // Initialize logging
-    init_logging(&config)?;
+    let logging = logging::init(&config)?;
+
+    // Reload configuration on file changes and SIGHUP; Kafka clients keep their startup settings
+    let config_handle = ConfigHandle::new(config.clone());
+    let reload_args = args.clone();
+    watch_config(config_handle.clone(), &args.config, move || load_config(&reload_args))?;
+    logging.watch_level(&config_handle, |config| config.logging.level.clone());
+
+    let event_config = EventConfig {
+        app: config.clone(),
+        events: EventSettings::default(),
+    };
+    event_config.validate().map_err(anyhow::Error::msg)?;
 
     info!("Starting Event service");
     info!("Environment: {}", config.environment);
     info!("Version: {}", config.version);
 
+    // Report dependency health at startup
+    let health = HealthRegistry::new(config.service_name.clone(), config.version.clone())
+        .register(KafkaHealthCheck::new(&config.kafka, config.health.check_timeout)?);
+    health.log_status().await;
+
     let topics = args.topics
         .map(|t| t.split(',').map(|s| s.trim().to_string()).collect())
         .unwrap_or_else(|| vec!["events".to_string()]);
@@ -88,106 +123,102 @@ async fn main() -> Result<()> {
     let enable_producer = args.producer || (!args.consumer && !args.producer);
     let enable_consumer = args.consumer || (!args.consumer && !args.producer);
 
-    let mut handles = Vec::new();
+    let mut supervisor = Supervisor::new();
+    let restart = RestartPolicy::OnFailure {
+        max_restarts: 5,
+        backoff: Duration::from_secs(1),
+    };
 
     // Start producer if enabled
-    if enable_producer {
+    let producer_manager = if enable_producer {
         info!("Starting Kafka producer");
-        let producer_manager = EventProducerManager::new(&config.kafka).await?;
-        let producer_handle = tokio::spawn(async move {
-            if let Err(e) = producer_manager.start().await {
-                tracing::error!("Producer manager failed: {}", e);
-            }
+        let producer_manager = EventProducerManager::new(&event_config).await?;
+        let manager = producer_manager.clone();
+        supervisor.spawn("event producer", restart, move |token| {
+            let manager = manager.clone();
+            async move { manager.start(token).await }
         });
-        handles.push(producer_handle);
-    }
+        Some(producer_manager)
+    } else {
+        None
+    };
 
-    // Start consumer if enabled
-    if enable_consumer {
+    // Start consumer if enabled; its handlers enqueue jobs for the worker service
+    let database = if enable_consumer {
         info!("Starting Kafka consumer for topics: {:?}", topics);
-        let consumer_manager = EventConsumerManager::new(&config.kafka, topics).await?;
-        let consumer_handle = tokio::spawn(async move {
-            if let Err(e) = consumer_manager.start().await {
-                tracing::error!("Consumer manager failed: {}", e);
-            }
+        let database = DatabaseManager::new(&config.database).await?;
+        let consumer_manager = Arc::new(EventConsumerManager::new(&event_config, topics, &database).await?);
+        supervisor.spawn("event consumer", restart, move |token| {
+            let consumer_manager = consumer_manager.clone();
+            async move { consumer_manager.start(token).await }
         });
-        handles.push(consumer_handle);
-    }
+        Some(database)
+    } else {
+        None
+    };
 
     // Wait for shutdown signal
     shutdown_signal().await;
 
-    // Graceful shutdown
+    // Graceful shutdown: stop consuming, finish in-flight events, then deliver queued messages
     info!("Shutting down event service");
-    for handle in handles {
-        handle.abort();
+    let deadline = config.shutdown_timeout();
+    let token = supervisor.token().clone();
+    let mut shutdown = Shutdown::new(deadline)
+        .step(ShutdownPhase::StopIntake, "event consumer", async move { token.cancel() })
+        .step(ShutdownPhase::Drain, "event handlers", supervisor.shutdown());
+    if let Some(producer_manager) = producer_manager {
+        shutdown = shutdown.step(ShutdownPhase::FlushProducers, "event producer", async move {
+            if let Err(e) = producer_manager.flush(deadline).await {
+                warn!("{}", e);
+            }
+        });
+    }
+    if let Some(database) = database {
+        shutdown = shutdown.step(ShutdownPhase::ClosePools, "database", async move { database.close().await });
     }
+    shutdown.run().await;
 
+    logging.shutdown();
     info!("Event service stopped");
     Ok(())
 }
 
-/// Initialize logging and tracing
-fn init_logging(config: &AppConfig) -> Result<()> {
-    use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
-
-    let env_filter = EnvFilter::try_from_default_env()
-        .unwrap_or_else(|_| EnvFilter::new(&config.logging.level));
-
-    let subscriber = tracing_subscriber::registry().with(env_filter);
-
-    match config.logging.format.as_str() {
-        "json" => {
-            let json_layer = tracing_subscriber::fmt::layer()
-                .json()
-                .with_current_span(false)
-                .with_span_list(true);
-            subscriber.with(json_layer).init();
-        }
-        _ => {
-            let fmt_layer = tracing_subscriber::fmt::layer()
-                .with_target(true)
-                .with_thread_ids(true)
-                .with_file(true)
-                .with_line_number(true);
-            subscriber.with(fmt_layer).init();
-        }
+/// Configuration sources, with CLI overrides taking precedence
+fn config_figment(args: &Args) -> Figment {
+    let mut overrides = CliOverrides::default();
+    if let Some(environment) = &args.environment {
+        overrides.set("environment", environment);
+    }
+    if let Some(group_id) = &args.group_id {
+        overrides.set("kafka.group_id", group_id);
     }
 
-    Ok(())
+    AppConfig::figment(&args.config).merge(overrides)
 }
 
-/// Graceful shutdown signal
-async fn shutdown_signal() {
-    use tokio::signal;
-
-    let ctrl_c = async {
-        signal::ctrl_c()
-            .await
-            .expect("Failed to install Ctrl+C handler");
-    };
+/// Load configuration from files, the environment and CLI flags
+fn load_config(args: &Args) -> Result<AppConfig, String> {
+    config_figment(args).extract().map_err(|e| e.to_string())
+}
 
-    #[cfg(unix)]
-    let terminate = async {
-        signal::unix::signal(signal::unix::SignalKind::terminate())
-            .expect("Failed to install signal handler")
-            .recv()
-            .await;
+/// Run a `config` subcommand
+fn run_config_command(args: &Args, command: &ConfigCommand) -> Result<()> {
+    let figment = config_figment(args);
+    let config = EventConfig {
+        app: figment.extract()?,
+        events: EventSettings::default(),
     };
 
-    #[cfg(not(unix))]
-    let terminate = std::future::pending::<()>();
-
-    tokio::select! {
-        _ = ctrl_c => {
-            info!("Received Ctrl+C signal");
-        },
-        _ = terminate => {
-            info!("Received terminate signal");
-        },
+    match command {
+        ConfigCommand::Check => {
+            config.validate().map_err(anyhow::Error::msg)?;
+            println!("Configuration is valid");
+        }
+        ConfigCommand::Print => print!("{}", describe_config(&config, &figment)?),
     }
 
-    info!("Starting graceful shutdown");
+    Ok(())
 }
 
 #[cfg(test)]
@@ -0,0 +1,160 @@
+//! Kafka event producers
+
+use async_trait::async_trait;
+use rdkafka::{
+    message::{Header, OwnedHeaders},
+    producer::{FutureProducer, FutureRecord, Producer},
+};
+use serde::Serialize;
+use shared::{
+    kafka::client_config, runtime::CancellationToken, services, telemetry, AppError, AppResult, CorrelationId, Event,
+    EventMetadata, EventPublisher,
+};
+use std::time::Duration;
+use tokio::time::interval;
+use tracing::{debug, info};
+
+use crate::config::EventConfig;
+
+/// Kafka headers carrying the event's metadata plus the W3C trace context of the publishing span
+pub fn event_headers(metadata: &EventMetadata) -> OwnedHeaders {
+    telemetry::event_headers(metadata)
+        .into_iter()
+        .fold(OwnedHeaders::new(), |headers, (key, value)| {
+            headers.insert(Header { key: &key, value: Some(&value) })
+        })
+}
+
+/// Publishes events to Kafka wrapped in an [`Event`] envelope
+#[derive(Clone)]
+pub struct EventProducer {
+    producer: FutureProducer,
+    queue_timeout: Duration,
+}
+
+impl EventProducer {
+    pub fn new(config: &EventConfig) -> AppResult<Self> {
+        let producer = client_config(&config.app.kafka)
+            .set("message.timeout.ms", config.events.message_timeout_ms.to_string())
+            .create()
+            .map_err(|e| AppError::Kafka(format!("Failed to create producer: {}", e)))?;
+
+        Ok(Self {
+            producer,
+            queue_timeout: config.queue_timeout_duration(),
+        })
+    }
+
+    /// Send an event envelope, keyed for partitioning when a key is given
+    pub async fn send<T>(&self, topic: &str, key: Option<&str>, event: &Event<T>) -> AppResult<()>
+    where
+        T: Serialize + Send + Sync,
+    {
+        let payload = serde_json::to_vec(event)?;
+        let mut record = FutureRecord::to(topic)
+            .payload(&payload)
+            .headers(event_headers(&event.metadata));
+        if let Some(key) = key {
+            record = record.key(key);
+        }
+
+        let (partition, offset) = self
+            .producer
+            .send(record, self.queue_timeout)
+            .await
+            .map_err(|(e, _)| AppError::Kafka(format!("Failed to publish to {}: {}", topic, e)))?;
+
+        debug!(
+            topic,
+            partition,
+            offset,
+            event_type = %event.metadata.event_type,
+            correlation_id = %event.metadata.correlation_id,
+            "Event published"
+        );
+        Ok(())
+    }
+
+    /// The publisher trait carries no event type, so the topic is recorded as the type
+    fn envelope<'a, T>(topic: &str, event: &'a T, correlation_id: CorrelationId) -> Event<&'a T> {
+        Event::new(topic, services::EVENT, correlation_id, event)
+    }
+}
+
+#[async_trait]
+impl EventPublisher for EventProducer {
+    async fn publish<T>(&self, topic: &str, event: &T, correlation_id: CorrelationId) -> AppResult<()>
+    where
+        T: Serialize + Send + Sync,
+    {
+        self.send(topic, None, &Self::envelope(topic, event, correlation_id)).await
+    }
+
+    async fn publish_with_key<T>(&self, topic: &str, key: &str, event: &T, correlation_id: CorrelationId) -> AppResult<()>
+    where
+        T: Serialize + Send + Sync,
+    {
+        self.send(topic, Some(key), &Self::envelope(topic, event, correlation_id)).await
+    }
+}
+
+/// Owns the service's producer and keeps its queue flushed
+#[derive(Clone)]
+pub struct EventProducerManager {
+    producer: EventProducer,
+    flush_interval: Duration,
+}
+
+impl EventProducerManager {
+    pub async fn new(config: &EventConfig) -> AppResult<Self> {
+        Ok(Self {
+            producer: EventProducer::new(config)?,
+            flush_interval: config.flush_interval_duration(),
+        })
+    }
+
+    /// Periodically flush queued messages until cancelled
+    pub async fn start(&self, token: CancellationToken) -> AppResult<()> {
+        info!("Event producer started");
+
+        let mut flush_interval = interval(self.flush_interval);
+        loop {
+            tokio::select! {
+                _ = token.cancelled() => break,
+                _ = flush_interval.tick() => {}
+            }
+            self.flush(self.flush_interval).await?;
+        }
+
+        info!("Event producer stopped");
+        Ok(())
+    }
+
+    /// Wait up to `timeout` for queued messages to be delivered
+    pub async fn flush(&self, timeout: Duration) -> AppResult<()> {
+        let producer = self.producer.producer.clone();
+        tokio::task::spawn_blocking(move || producer.flush(timeout))
+            .await
+            .map_err(|e| AppError::Internal(format!("Producer flush task failed: {}", e)))?
+            .map_err(|e| AppError::Kafka(format!("Failed to flush producer: {}", e)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rdkafka::message::Headers;
+    use shared::kafka_headers;
+    use uuid::Uuid;
+
+    #[test]
+    fn test_event_headers_carry_correlation_id() {
+        let correlation_id = Uuid::new_v4();
+        let metadata = EventMetadata::new("order.created", services::API, correlation_id);
+        let headers = event_headers(&metadata);
+
+        let header = headers.iter().find(|header| header.key == kafka_headers::CORRELATION_ID).unwrap();
+        assert_eq!(header.value, Some(correlation_id.to_string().as_bytes()));
+        assert_eq!(headers.count(), 4);
+    }
+}
@@ -0,0 +1,275 @@
+//! Circuit breaking for calls to unreliable dependencies
+//!
+//! A breaker counts consecutive failures. Once they reach the failure threshold the circuit
+//! opens and calls are refused without reaching the dependency. After the open duration a
+//! limited number of trial calls are let through: enough successes close the circuit again,
+//! while any failure reopens it.
+
+use async_trait::async_trait;
+use std::{
+    future::Future,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tokio::time::Instant;
+use tracing::{info, warn};
+
+use crate::{AppError, AppResult, CircuitBreaker, CircuitBreakerConfig, CircuitBreakerState};
+
+/// [`CircuitBreaker`] opening after a number of consecutive failures
+#[derive(Debug, Clone)]
+pub struct ThresholdCircuitBreaker {
+    name: String,
+    failure_threshold: u32,
+    success_threshold: u32,
+    open_duration: Duration,
+    inner: Arc<Mutex<Inner>>,
+}
+
+#[derive(Debug)]
+struct Inner {
+    state: State,
+    /// Bumped on every transition, so outcomes of calls admitted before it are ignored
+    generation: u64,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum State {
+    Closed { failures: u32 },
+    Open { until: Instant },
+    HalfOpen { in_flight: u32, successes: u32 },
+}
+
+impl ThresholdCircuitBreaker {
+    /// Breaker named after the dependency it protects, e.g. a host name
+    pub fn new(name: impl Into<String>, failure_threshold: u32, success_threshold: u32, open_duration: Duration) -> Self {
+        Self {
+            name: name.into(),
+            failure_threshold: failure_threshold.max(1),
+            success_threshold: success_threshold.max(1),
+            open_duration,
+            inner: Arc::new(Mutex::new(Inner {
+                state: State::Closed { failures: 0 },
+                generation: 0,
+            })),
+        }
+    }
+
+    pub fn from_config(name: impl Into<String>, config: &CircuitBreakerConfig) -> Self {
+        Self::new(
+            name,
+            config.failure_threshold,
+            config.success_threshold,
+            Duration::from_secs(config.open_duration),
+        )
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Ask to make a call; refused with `ServiceUnavailable` while the circuit is open.
+    ///
+    /// Report the call's outcome through the returned permit. A permit dropped without an
+    /// outcome, e.g. because the call was cancelled, only gives back its trial slot.
+    pub fn acquire(&self) -> AppResult<CircuitPermit> {
+        let mut inner = self.inner.lock().unwrap();
+        match inner.state {
+            State::Closed { .. } => {}
+            State::Open { until } if Instant::now() < until => {
+                return Err(AppError::ServiceUnavailable(format!("Circuit for {} is open", self.name)));
+            }
+            State::Open { .. } => {
+                info!(circuit = %self.name, "Circuit half-open, letting trial calls through");
+                inner.transition(State::HalfOpen { in_flight: 1, successes: 0 });
+            }
+            State::HalfOpen { in_flight, successes } if in_flight + successes < self.success_threshold => {
+                inner.state = State::HalfOpen {
+                    in_flight: in_flight + 1,
+                    successes,
+                };
+            }
+            State::HalfOpen { .. } => {
+                return Err(AppError::ServiceUnavailable(format!(
+                    "Circuit for {} is half-open and waiting on trial calls",
+                    self.name
+                )));
+            }
+        }
+
+        Ok(CircuitPermit {
+            breaker: self.clone(),
+            generation: inner.generation,
+            done: false,
+        })
+    }
+
+    fn record(&self, generation: u64, outcome: Option<bool>) {
+        let mut inner = self.inner.lock().unwrap();
+        if inner.generation != generation {
+            return;
+        }
+
+        match (inner.state, outcome) {
+            (State::Closed { .. }, Some(true)) => inner.state = State::Closed { failures: 0 },
+            (State::Closed { failures }, Some(false)) if failures + 1 >= self.failure_threshold => {
+                warn!(circuit = %self.name, failures = failures + 1, "Circuit open for {:?}", self.open_duration);
+                inner.transition(State::Open {
+                    until: Instant::now() + self.open_duration,
+                });
+            }
+            (State::Closed { failures }, Some(false)) => inner.state = State::Closed { failures: failures + 1 },
+            (State::HalfOpen { successes, .. }, Some(true)) if successes + 1 >= self.success_threshold => {
+                info!(circuit = %self.name, "Circuit closed");
+                inner.transition(State::Closed { failures: 0 });
+            }
+            (State::HalfOpen { in_flight, successes }, Some(true)) => {
+                inner.state = State::HalfOpen {
+                    in_flight: in_flight.saturating_sub(1),
+                    successes: successes + 1,
+                };
+            }
+            (State::HalfOpen { .. }, Some(false)) => {
+                warn!(circuit = %self.name, "Trial call failed, circuit open for {:?}", self.open_duration);
+                inner.transition(State::Open {
+                    until: Instant::now() + self.open_duration,
+                });
+            }
+            (State::HalfOpen { in_flight, successes }, None) => {
+                inner.state = State::HalfOpen {
+                    in_flight: in_flight.saturating_sub(1),
+                    successes,
+                };
+            }
+            (State::Closed { .. }, None) | (State::Open { .. }, _) => {}
+        }
+    }
+}
+
+impl Inner {
+    fn transition(&mut self, state: State) {
+        self.state = state;
+        self.generation += 1;
+    }
+}
+
+#[async_trait]
+impl CircuitBreaker for ThresholdCircuitBreaker {
+    /// Run `f` if the circuit allows it; any error it returns counts as a failure
+    async fn execute<F, T>(&self, f: F) -> AppResult<T>
+    where
+        F: Future<Output = AppResult<T>> + Send,
+        T: Send,
+    {
+        let permit = self.acquire()?;
+        let result = f.await;
+        permit.record(result.is_ok());
+        result
+    }
+
+    /// Current state; an open circuit whose open duration has passed reports half-open
+    fn state(&self) -> CircuitBreakerState {
+        match self.inner.lock().unwrap().state {
+            State::Closed { .. } => CircuitBreakerState::Closed,
+            State::Open { until } if Instant::now() < until => CircuitBreakerState::Open,
+            State::Open { .. } | State::HalfOpen { .. } => CircuitBreakerState::HalfOpen,
+        }
+    }
+}
+
+/// Admission of a single call through a [`ThresholdCircuitBreaker`]
+#[derive(Debug)]
+pub struct CircuitPermit {
+    breaker: ThresholdCircuitBreaker,
+    generation: u64,
+    done: bool,
+}
+
+impl CircuitPermit {
+    /// Report whether the call succeeded
+    pub fn record(mut self, success: bool) {
+        self.done = true;
+        self.breaker.record(self.generation, Some(success));
+    }
+}
+
+impl Drop for CircuitPermit {
+    fn drop(&mut self) {
+        if !self.done {
+            self.breaker.record(self.generation, None);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn breaker(open_duration: Duration) -> ThresholdCircuitBreaker {
+        ThresholdCircuitBreaker::new("test", 3, 2, open_duration)
+    }
+
+    fn fail(breaker: &ThresholdCircuitBreaker) {
+        breaker.acquire().unwrap().record(false);
+    }
+
+    #[test]
+    fn test_opens_after_consecutive_failures() {
+        let breaker = breaker(Duration::from_secs(60));
+        fail(&breaker);
+        fail(&breaker);
+        breaker.acquire().unwrap().record(true);
+        fail(&breaker);
+        fail(&breaker);
+        assert_eq!(breaker.state(), CircuitBreakerState::Closed);
+
+        fail(&breaker);
+        assert_eq!(breaker.state(), CircuitBreakerState::Open);
+        assert!(matches!(breaker.acquire(), Err(AppError::ServiceUnavailable(_))));
+    }
+
+    #[tokio::test]
+    async fn test_half_open_trials() {
+        let breaker = breaker(Duration::from_millis(20));
+        for _ in 0..3 {
+            fail(&breaker);
+        }
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        assert_eq!(breaker.state(), CircuitBreakerState::HalfOpen);
+
+        // As many trial calls as successes needed to close, and a dropped one frees its slot
+        let first = breaker.acquire().unwrap();
+        let second = breaker.acquire().unwrap();
+        assert!(breaker.acquire().is_err());
+        drop(second);
+        let second = breaker.acquire().unwrap();
+
+        first.record(true);
+        assert_eq!(breaker.state(), CircuitBreakerState::HalfOpen);
+        second.record(true);
+        assert_eq!(breaker.state(), CircuitBreakerState::Closed);
+
+        // A failed trial reopens the circuit and late outcomes are ignored
+        for _ in 0..3 {
+            fail(&breaker);
+        }
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        let trial = breaker.acquire().unwrap();
+        let late = breaker.acquire().unwrap();
+        trial.record(false);
+        late.record(true);
+        assert_eq!(breaker.state(), CircuitBreakerState::Open);
+    }
+
+    #[tokio::test]
+    async fn test_execute_counts_errors() {
+        let breaker = ThresholdCircuitBreaker::new("test", 1, 1, Duration::from_secs(60));
+        assert_eq!(breaker.execute(async { Ok(1) }).await.unwrap(), 1);
+
+        let result: AppResult<()> = breaker.execute(async { Err(AppError::Timeout("slow".to_string())) }).await;
+        assert!(matches!(result, Err(AppError::Timeout(_))));
+
+        let result = breaker.execute(async { Ok(()) }).await;
+        assert!(matches!(result, Err(AppError::ServiceUnavailable(_))));
+    }
+}
@@ -1,17 +1,201 @@
 //! Configuration management utilities
 
 use figment::{
-    providers::{Env, Format, Yaml},
-    Figment,
+    providers::{Env, Format, Serialized, Yaml},
+    value::{Dict, Map},
+    Figment, Metadata, Profile, Provider,
 };
-use serde::{Deserialize, Serialize};
-use std::time::Duration;
+use serde::{Deserialize, Serialize, Serializer};
+use std::{collections::BTreeMap, fmt, time::Duration};
 use url::Url;
 
+/// Placeholder secrets from the sample configuration, refused in production
+pub const DEFAULT_SECRETS: &[&str] = &["your-secret-key", "your-secret-key-change-in-production"];
+
+const REDACTED: &str = "[REDACTED]";
+
+/// A configuration value that is redacted in `Debug`, `Display` and `Serialize` output.
+///
+/// Use [`expose`](Self::expose) at the point the value is actually needed.
+///
+/// Serialization is one-way: a serialized config deserializes with `"[REDACTED]"` in place of
+/// every secret, so never feed one back into a [`Figment`] (e.g. through `Serialized::defaults`)
+/// to build a config that is used afterwards.
+#[derive(Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(transparent)]
+pub struct Secret<T>(T);
+
+impl<T> Secret<T> {
+    pub fn new(value: T) -> Self {
+        Self(value)
+    }
+
+    pub fn expose(&self) -> &T {
+        &self.0
+    }
+}
+
+impl Secret<String> {
+    /// Whether the value is one of the sample [`DEFAULT_SECRETS`]
+    pub fn is_default(&self) -> bool {
+        DEFAULT_SECRETS.contains(&self.0.as_str())
+    }
+}
+
+impl<T> fmt::Debug for Secret<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(REDACTED)
+    }
+}
+
+impl<T> fmt::Display for Secret<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(REDACTED)
+    }
+}
+
+impl<T> Serialize for Secret<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(REDACTED)
+    }
+}
+
+impl From<String> for Secret<String> {
+    fn from(value: String) -> Self {
+        Self(value)
+    }
+}
+
+impl From<&str> for Secret<String> {
+    fn from(value: &str) -> Self {
+        Self(value.to_string())
+    }
+}
+
+/// Reads configuration values from files named by `{PREFIX}{KEY}_FILE` variables.
+///
+/// Keys nest with `__`, so `APP_SECURITY__JWT_SECRET_FILE=/run/secrets/jwt`
+/// sets `security.jwt_secret` to the contents of `/run/secrets/jwt`, without the trailing
+/// newline. This is how Docker and Kubernetes mount secrets.
+#[derive(Debug, Clone)]
+pub struct EnvFiles {
+    prefix: String,
+    nest_under: Option<String>,
+}
+
+impl EnvFiles {
+    pub fn prefixed(prefix: &str) -> Self {
+        Self {
+            prefix: prefix.to_string(),
+            nest_under: None,
+        }
+    }
+
+    /// Place every key under `key`, e.g. `api`
+    pub fn nest_under(mut self, key: &str) -> Self {
+        self.nest_under = Some(key.to_string());
+        self
+    }
+
+    /// Configuration key and file path for each matching variable
+    fn files(&self) -> Vec<(String, String)> {
+        std::env::vars()
+            .filter_map(|(name, path)| {
+                let name = name.to_lowercase();
+                let key = name
+                    .strip_prefix(&self.prefix.to_lowercase())?
+                    .strip_suffix("_file")?
+                    .replace("__", ".");
+                let key = match &self.nest_under {
+                    Some(parent) => format!("{}.{}", parent, key),
+                    None => key,
+                };
+                Some((key, path))
+            })
+            .collect()
+    }
+}
+
+impl Provider for EnvFiles {
+    fn metadata(&self) -> Metadata {
+        Metadata::named(format!("`{}*_FILE` environment files", self.prefix))
+    }
+
+    fn data(&self) -> Result<Map<Profile, Dict>, figment::Error> {
+        let mut figment = Figment::new();
+        for (key, path) in self.files() {
+            let value = std::fs::read_to_string(&path)
+                .map_err(|e| format!("Failed to read {} from {}: {}", key, path, e))?;
+            figment = figment.merge(Serialized::default(&key, value.trim_end_matches(['\r', '\n'])));
+        }
+        figment.data()
+    }
+}
+
+/// Values set by command-line flags, merged last so they win over files and the environment
+#[derive(Default)]
+pub struct CliOverrides {
+    values: Figment,
+}
+
+impl CliOverrides {
+    pub fn set<T: Serialize>(&mut self, key: &str, value: T) {
+        self.values = std::mem::take(&mut self.values).merge(Serialized::default(key, value));
+    }
+}
+
+impl Provider for CliOverrides {
+    fn metadata(&self) -> Metadata {
+        Metadata::named("command-line flag(s)")
+    }
+
+    fn data(&self) -> Result<Map<Profile, Dict>, figment::Error> {
+        // Re-serialize so values are untagged and take this provider's metadata when merged,
+        // instead of pointing at the inner figment's, which the outer one does not know
+        let values = self.values.data()?.remove(&Profile::Default).unwrap_or_default();
+        Serialized::defaults(values).data()
+    }
+}
+
+/// Effective configuration as `key = value` lines, each with the source that set it.
+///
+/// Values are taken from the serialized `config`, so secrets are masked. Values no source
+/// set come from the built-in defaults.
+pub fn describe_config<T: Serialize>(config: &T, figment: &Figment) -> Result<String, serde_json::Error> {
+    fn flatten(prefix: &str, value: serde_json::Value, entries: &mut Vec<(String, String)>) {
+        match value {
+            serde_json::Value::Object(fields) if !fields.is_empty() => {
+                for (name, value) in fields {
+                    let key = if prefix.is_empty() { name } else { format!("{}.{}", prefix, name) };
+                    flatten(&key, value, entries);
+                }
+            }
+            value => entries.push((prefix.to_string(), value.to_string())),
+        }
+    }
+
+    let mut entries = Vec::new();
+    flatten("", serde_json::to_value(config)?, &mut entries);
+
+    let width = entries.iter().map(|(key, _)| key.len()).max().unwrap_or_default();
+    let mut output = String::new();
+    for (key, value) in entries {
+        let source = match figment.find_metadata(&key) {
+            Some(metadata) => match &metadata.source {
+                Some(source) => format!("{} {}", metadata.name, source),
+                None => metadata.name.to_string(),
+            },
+            None => "default".to_string(),
+        };
+        output.push_str(&format!("{:width$} = {}  # {}\n", key, value, source, width = width));
+    }
+    Ok(output)
+}
+
 /// Database configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DatabaseConfig {
-    pub url: String,
+    pub url: Secret<String>,
     pub max_connections: u32,
     pub min_connections: u32,
     pub connect_timeout: u64,
@@ -23,7 +207,7 @@ pub struct DatabaseConfig {
 impl Default for DatabaseConfig {
     fn default() -> Self {
         Self {
-            url: "postgresql://localhost:5432/app".to_string(),
+            url: "postgresql://localhost:5432/app".into(),
             max_connections: 10,
             min_connections: 1,
             connect_timeout: 30,
@@ -37,7 +221,7 @@ impl Default for DatabaseConfig {
 /// Redis configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RedisConfig {
-    pub url: String,
+    pub url: Secret<String>,
     pub max_connections: u32,
     pub connect_timeout: u64,
     pub response_timeout: u64,
@@ -48,7 +232,7 @@ pub struct RedisConfig {
 impl Default for RedisConfig {
     fn default() -> Self {
         Self {
-            url: "redis://localhost:6379".to_string(),
+            url: "redis://localhost:6379".into(),
             max_connections: 10,
             connect_timeout: 5,
             response_timeout: 5,
@@ -72,7 +256,7 @@ pub struct KafkaConfig {
     pub security_protocol: Option<String>,
     pub sasl_mechanism: Option<String>,
     pub sasl_username: Option<String>,
-    pub sasl_password: Option<String>,
+    pub sasl_password: Option<Secret<String>>,
 }
 
 impl Default for KafkaConfig {
@@ -94,6 +278,31 @@ impl Default for KafkaConfig {
     }
 }
 
+impl KafkaConfig {
+    /// librdkafka properties shared by every producer and consumer
+    pub fn client_properties(&self) -> Vec<(&'static str, String)> {
+        let mut properties = vec![
+            ("bootstrap.servers", self.brokers.join(",")),
+            ("client.id", self.client_id.clone()),
+        ];
+
+        if let Some(protocol) = &self.security_protocol {
+            properties.push(("security.protocol", protocol.clone()));
+        }
+        if let Some(mechanism) = &self.sasl_mechanism {
+            properties.push(("sasl.mechanism", mechanism.clone()));
+        }
+        if let Some(username) = &self.sasl_username {
+            properties.push(("sasl.username", username.clone()));
+        }
+        if let Some(password) = &self.sasl_password {
+            properties.push(("sasl.password", password.expose().clone()));
+        }
+
+        properties
+    }
+}
+
 /// HTTP server configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ServerConfig {
@@ -127,14 +336,55 @@ impl Default for ServerConfig {
 pub struct LoggingConfig {
     pub level: String,
     pub format: String,
+    /// `stdout`, `file` or `both`
     pub output: String,
     pub file_path: Option<String>,
+    /// Size in bytes at which the log file is rotated
     pub max_file_size: Option<u64>,
+    /// Rotated log files to keep
     pub max_files: Option<u32>,
+    /// Time-based rotation of the log file, in addition to rotation by size
+    #[serde(default)]
+    pub rotation: LogRotation,
     pub jaeger_endpoint: Option<String>,
+    /// OTLP/HTTP collector base URL; spans are posted to `{endpoint}/v1/traces`
+    #[serde(default)]
+    pub otlp_endpoint: Option<String>,
+    /// Extra OpenTelemetry resource attributes attached to every exported span
+    #[serde(default)]
+    pub resource_attributes: BTreeMap<String, String>,
     pub service_name: String,
 }
 
+/// How often the log file is rotated regardless of its size
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogRotation {
+    Hourly,
+    #[default]
+    Daily,
+    Never,
+}
+
+impl LoggingConfig {
+    /// Whether logs are written to stdout
+    pub fn writes_stdout(&self) -> bool {
+        matches!(self.output.as_str(), "stdout" | "both")
+    }
+
+    /// Whether logs are written to `file_path`
+    pub fn writes_file(&self) -> bool {
+        matches!(self.output.as_str(), "file" | "both")
+    }
+
+    /// Collector that traces are exported to, if any.
+    ///
+    /// Jaeger accepts OTLP directly, so a Jaeger endpoint is used when no OTLP endpoint is set.
+    pub fn trace_endpoint(&self) -> Option<&str> {
+        self.otlp_endpoint.as_deref().or(self.jaeger_endpoint.as_deref())
+    }
+}
+
 impl Default for LoggingConfig {
     fn default() -> Self {
         Self {
@@ -144,7 +394,10 @@ impl Default for LoggingConfig {
             file_path: None,
             max_file_size: Some(100 * 1024 * 1024), // 100MB
             max_files: Some(10),
+            rotation: LogRotation::Daily,
             jaeger_endpoint: None,
+            otlp_endpoint: None,
+            resource_attributes: BTreeMap::new(),
             service_name: "rust-microservice".to_string(),
         }
     }
@@ -152,12 +405,18 @@ impl Default for LoggingConfig {
 
 /// Metrics configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct MetricsConfig {
     pub enabled: bool,
     pub host: String,
     pub port: u16,
     pub path: String,
+    /// Prefix for every metric name
     pub namespace: String,
+    /// Histogram buckets for request durations, in seconds
+    pub duration_buckets: Vec<f64>,
+    /// Histogram buckets for request and response sizes, in bytes
+    pub size_buckets: Vec<f64>,
 }
 
 impl Default for MetricsConfig {
@@ -168,14 +427,201 @@ impl Default for MetricsConfig {
             port: 9090,
             path: "/metrics".to_string(),
             namespace: "app".to_string(),
+            duration_buckets: vec![0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0],
+            size_buckets: vec![100.0, 1_000.0, 10_000.0, 100_000.0, 1_000_000.0, 10_000_000.0],
         }
     }
 }
 
-/// Security configuration
+/// Health check configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HealthConfig {
+    /// Timeout for each dependency check, in seconds
+    pub check_timeout: u64,
+    /// Time to keep reporting not-ready before shutting down, in seconds
+    pub drain_period: u64,
+}
+
+impl Default for HealthConfig {
+    fn default() -> Self {
+        Self {
+            check_timeout: 5,
+            drain_period: 5,
+        }
+    }
+}
+
+/// Graceful shutdown configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ShutdownConfig {
+    /// Deadline for the whole shutdown sequence, in seconds; the process exits when it passes
+    pub timeout: u64,
+}
+
+impl Default for ShutdownConfig {
+    fn default() -> Self {
+        Self { timeout: 30 }
+    }
+}
+
+/// Audit log configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AuditConfig {
+    /// Maximum entries written by a single insert
+    pub batch_size: usize,
+    /// Interval between writes of a partial batch, in milliseconds
+    pub flush_interval: u64,
+    /// Entries queued for the writer before recording a change waits for it
+    pub queue_size: usize,
+}
+
+impl Default for AuditConfig {
+    fn default() -> Self {
+        Self {
+            batch_size: 100,
+            flush_interval: 1000,
+            queue_size: 10_000,
+        }
+    }
+}
+
+/// Where uploaded files are stored
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StorageBackend {
+    /// A directory on the local filesystem
+    #[default]
+    Local,
+    /// An S3-compatible object store such as AWS S3 or MinIO
+    S3,
+}
+
+/// S3-compatible object store settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct S3Config {
+    /// Endpoint URL; buckets are addressed path-style, as `{endpoint}/{bucket}/{key}`
+    pub endpoint: String,
+    pub bucket: String,
+    pub region: String,
+    pub access_key_id: String,
+    pub secret_access_key: Secret<String>,
+}
+
+impl Default for S3Config {
+    fn default() -> Self {
+        Self {
+            endpoint: "http://localhost:9000".to_string(),
+            bucket: "uploads".to_string(),
+            region: "us-east-1".to_string(),
+            access_key_id: String::new(),
+            secret_access_key: Secret::default(),
+        }
+    }
+}
+
+/// File storage configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StorageConfig {
+    pub backend: StorageBackend,
+    /// Root directory of the local backend
+    pub local_path: String,
+    pub s3: S3Config,
+    /// Key signing download URLs
+    pub signing_key: Secret<String>,
+    /// Lifetime of signed download URLs, in seconds
+    pub url_ttl: u64,
+    /// Maximum size of an uploaded file in bytes
+    pub max_upload_size: usize,
+    /// Content types accepted for uploads, as detected from the file contents
+    pub allowed_content_types: Vec<String>,
+}
+
+impl Default for StorageConfig {
+    fn default() -> Self {
+        Self {
+            backend: StorageBackend::Local,
+            local_path: "data/files".to_string(),
+            s3: S3Config::default(),
+            signing_key: DEFAULT_SECRETS[1].into(),
+            url_ttl: 900,
+            max_upload_size: crate::file_limits::MAX_UPLOAD_SIZE,
+            allowed_content_types: [
+                "image/png",
+                "image/jpeg",
+                "image/gif",
+                "image/webp",
+                "application/pdf",
+                "text/plain",
+            ]
+            .iter()
+            .map(|content_type| content_type.to_string())
+            .collect(),
+        }
+    }
+}
+
+/// Circuit breaker thresholds for calls to a single dependency
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CircuitBreakerConfig {
+    /// Consecutive failures that open the circuit
+    pub failure_threshold: u32,
+    /// Time the circuit stays open before trial calls are let through, in seconds
+    pub open_duration: u64,
+    /// Successful trial calls that close the circuit again; also the number of trial calls
+    /// allowed at once
+    pub success_threshold: u32,
+}
+
+impl Default for CircuitBreakerConfig {
+    fn default() -> Self {
+        Self {
+            failure_threshold: 5,
+            open_duration: 30,
+            success_threshold: 1,
+        }
+    }
+}
+
+/// Outbound HTTP client configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HttpClientConfig {
+    /// Timeout for a single attempt, in seconds
+    pub timeout: u64,
+    /// Timeout for establishing a connection, in seconds
+    pub connect_timeout: u64,
+    /// Attempts made for an idempotent request, including the first
+    pub max_attempts: u32,
+    /// Upper bound of the delay before the first retry, in milliseconds; doubles with each retry
+    pub initial_backoff: u64,
+    /// Upper bound of the delay before any retry, in milliseconds
+    pub max_backoff: u64,
+    /// Breaker applied to each host separately
+    pub circuit_breaker: CircuitBreakerConfig,
+}
+
+impl Default for HttpClientConfig {
+    fn default() -> Self {
+        Self {
+            timeout: crate::timeouts::HTTP_CLIENT,
+            connect_timeout: 5,
+            max_attempts: crate::retries::MAX_ATTEMPTS,
+            initial_backoff: crate::retries::INITIAL_DELAY_MS,
+            max_backoff: crate::retries::MAX_DELAY_MS,
+            circuit_breaker: CircuitBreakerConfig::default(),
+        }
+    }
+}
+
+/// Security configuration
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SecurityConfig {
-    pub jwt_secret: String,
+    pub jwt_secret: Secret<String>,
     pub jwt_expiration: u64,
     pub bcrypt_cost: u32,
     pub cors_origins: Vec<String>,
@@ -188,7 +634,7 @@ pub struct SecurityConfig {
 impl Default for SecurityConfig {
     fn default() -> Self {
         Self {
-            jwt_secret: "your-secret-key".to_string(),
+            jwt_secret: "your-secret-key".into(),
             jwt_expiration: 3600,
             bcrypt_cost: 12,
             cors_origins: vec!["*".to_string()],
@@ -214,6 +660,16 @@ pub struct AppConfig {
     pub logging: LoggingConfig,
     pub metrics: MetricsConfig,
     pub security: SecurityConfig,
+    #[serde(default)]
+    pub health: HealthConfig,
+    #[serde(default)]
+    pub shutdown: ShutdownConfig,
+    #[serde(default)]
+    pub audit: AuditConfig,
+    #[serde(default)]
+    pub storage: StorageConfig,
+    #[serde(default)]
+    pub http_client: HttpClientConfig,
 }
 
 impl Default for AppConfig {
@@ -230,6 +686,11 @@ impl Default for AppConfig {
             logging: LoggingConfig::default(),
             metrics: MetricsConfig::default(),
             security: SecurityConfig::default(),
+            health: HealthConfig::default(),
+            shutdown: ShutdownConfig::default(),
+            audit: AuditConfig::default(),
+            storage: StorageConfig::default(),
+            http_client: HttpClientConfig::default(),
         }
     }
 }
@@ -237,30 +698,34 @@ impl Default for AppConfig {
 impl AppConfig {
     /// Load configuration from files and environment variables
     pub fn load() -> Result<Self, figment::Error> {
-        Figment::new()
-            .merge(Yaml::file("config/default.yml"))
-            .merge(Yaml::file(format!("config/{}.yml", std::env::var("ENVIRONMENT").unwrap_or_else(|_| "development".to_string()))))
-            .merge(Env::prefixed("APP_"))
-            .extract()
+        Self::load_from_path("config")
     }
 
     /// Load configuration with custom config path
     pub fn load_from_path(config_path: &str) -> Result<Self, figment::Error> {
+        Self::figment(config_path).extract()
+    }
+
+    /// Configuration sources, in order of precedence (lowest first).
+    ///
+    /// `APP_` variables name nested keys with a `.` (e.g. `APP_LOGGING.OTLP_ENDPOINT`), and
+    /// `APP_*_FILE` variables read values from files (see [`EnvFiles`]).
+    pub fn figment(config_path: &str) -> Figment {
         Figment::new()
             .merge(Yaml::file(format!("{}/default.yml", config_path)))
             .merge(Yaml::file(format!("{}/{}.yml", config_path, std::env::var("ENVIRONMENT").unwrap_or_else(|_| "development".to_string()))))
             .merge(Env::prefixed("APP_"))
-            .extract()
+            .merge(EnvFiles::prefixed("APP_"))
     }
 
     /// Get database URL as parsed URL
     pub fn database_url(&self) -> Result<Url, url::ParseError> {
-        Url::parse(&self.database.url)
+        Url::parse(self.database.url.expose())
     }
 
     /// Get Redis URL as parsed URL
     pub fn redis_url(&self) -> Result<Url, url::ParseError> {
-        Url::parse(&self.redis.url)
+        Url::parse(self.redis.url.expose())
     }
 
     /// Get server bind address
@@ -297,6 +762,26 @@ impl AppConfig {
     pub fn redis_connect_timeout(&self) -> Duration {
         Duration::from_secs(self.redis.connect_timeout)
     }
+
+    /// Get the audit flush interval as Duration
+    pub fn audit_flush_interval(&self) -> Duration {
+        Duration::from_millis(self.audit.flush_interval)
+    }
+
+    /// Get the lifetime of signed download URLs as Duration
+    pub fn storage_url_ttl(&self) -> Duration {
+        Duration::from_secs(self.storage.url_ttl)
+    }
+
+    /// Get the timeout of a single outbound HTTP attempt as Duration
+    pub fn http_client_timeout(&self) -> Duration {
+        Duration::from_secs(self.http_client.timeout)
+    }
+
+    /// Get the shutdown deadline as Duration
+    pub fn shutdown_timeout(&self) -> Duration {
+        Duration::from_secs(self.shutdown.timeout)
+    }
 }
 
 /// Configuration validation trait
@@ -320,15 +805,179 @@ impl ValidateConfig for AppConfig {
         }
 
         // Validate JWT secret
-        if self.security.jwt_secret.len() < 32 {
+        if self.security.jwt_secret.expose().len() < 32 {
             return Err("JWT secret must be at least 32 characters".to_string());
         }
+        if self.is_production() && self.security.jwt_secret.is_default() {
+            return Err("The default JWT secret cannot be used in production".to_string());
+        }
 
         // Validate server port
         if self.server.port == 0 {
             return Err("Server port cannot be 0".to_string());
         }
 
+        // Validate metrics histogram buckets
+        for buckets in [&self.metrics.duration_buckets, &self.metrics.size_buckets] {
+            if buckets.is_empty() || buckets.windows(2).any(|pair| pair[0] >= pair[1]) {
+                return Err("Metrics buckets must be non-empty and strictly increasing".to_string());
+            }
+        }
+
+        // Validate health checks
+        if self.health.check_timeout == 0 {
+            return Err("Health check timeout cannot be 0".to_string());
+        }
+
+        // Validate shutdown deadline
+        if self.shutdown.timeout <= self.health.drain_period {
+            return Err("Shutdown timeout must be longer than the health drain period".to_string());
+        }
+
+        // Validate audit batching; a batch is one insert, which Postgres limits to 65535 parameters
+        if self.audit.batch_size == 0 || self.audit.batch_size > 1000 {
+            return Err("Audit batch size must be between 1 and 1000".to_string());
+        }
+        if self.audit.flush_interval == 0 || self.audit.queue_size < self.audit.batch_size {
+            return Err("Audit flush interval must be non-zero and the queue must hold a full batch".to_string());
+        }
+
+        // Validate file storage
+        if self.storage.signing_key.expose().len() < 32 {
+            return Err("Storage signing key must be at least 32 characters".to_string());
+        }
+        if self.is_production() && self.storage.signing_key.is_default() {
+            return Err("The default storage signing key cannot be used in production".to_string());
+        }
+        if self.storage.url_ttl == 0 || self.storage.max_upload_size == 0 {
+            return Err("Storage URL lifetime and maximum upload size cannot be 0".to_string());
+        }
+        if self.storage.backend == StorageBackend::S3 {
+            Url::parse(&self.storage.s3.endpoint).map_err(|e| format!("Invalid S3 endpoint: {}", e))?;
+            if self.storage.s3.bucket.is_empty() || self.storage.s3.access_key_id.is_empty() {
+                return Err("The S3 storage backend requires a bucket and access key".to_string());
+            }
+        }
+
+        // Validate outbound HTTP client
+        if self.http_client.timeout == 0 || self.http_client.connect_timeout == 0 {
+            return Err("HTTP client timeouts cannot be 0".to_string());
+        }
+        if self.http_client.max_attempts == 0 {
+            return Err("HTTP client must make at least one attempt".to_string());
+        }
+        if self.http_client.initial_backoff > self.http_client.max_backoff {
+            return Err("HTTP client initial backoff cannot exceed the maximum backoff".to_string());
+        }
+        let breaker = &self.http_client.circuit_breaker;
+        if breaker.failure_threshold == 0 || breaker.success_threshold == 0 || breaker.open_duration == 0 {
+            return Err("Circuit breaker thresholds and open duration cannot be 0".to_string());
+        }
+
+        // Validate log output
+        if !matches!(self.logging.output.as_str(), "stdout" | "file" | "both") {
+            return Err(format!("Unknown log output '{}', expected stdout, file or both", self.logging.output));
+        }
+        if self.logging.writes_file() && self.logging.file_path.is_none() {
+            return Err("Logging to a file requires logging.file_path".to_string());
+        }
+        if self.logging.max_file_size == Some(0) {
+            return Err("Log max file size cannot be 0".to_string());
+        }
+
+        // Validate trace collector endpoint
+        if let Some(endpoint) = self.logging.trace_endpoint() {
+            Url::parse(endpoint).map_err(|e| format!("Invalid trace collector endpoint: {}", e))?;
+        }
+
         Ok(())
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_secret_is_redacted() {
+        let security = SecurityConfig::default();
+        assert!(!format!("{:?}", security).contains("your-secret-key"));
+        assert_eq!(serde_json::to_value(&security).unwrap()["jwt_secret"], REDACTED);
+        assert_eq!(security.jwt_secret.expose(), "your-secret-key");
+    }
+
+    #[test]
+    fn test_file_logging_requires_path() {
+        let mut config = AppConfig::default();
+        config.security.jwt_secret = "a-development-secret-of-32-characters".into();
+        config.logging.output = "both".to_string();
+        assert!(config.validate().is_err());
+
+        config.logging.file_path = Some("logs/service.log".to_string());
+        assert!(config.validate().is_ok());
+        assert!(config.logging.writes_stdout() && config.logging.writes_file());
+    }
+
+    #[test]
+    fn test_env_files() {
+        let path = std::env::temp_dir().join("shared-config-test-jwt-secret");
+        std::fs::write(&path, "a-secret-read-from-a-mounted-file\n").unwrap();
+        std::env::set_var("CONFIG_TEST_SECURITY__JWT_SECRET_FILE", &path);
+
+        let secret: Secret<String> = Figment::from(EnvFiles::prefixed("CONFIG_TEST_"))
+            .extract_inner("security.jwt_secret")
+            .unwrap();
+        assert_eq!(secret.expose(), "a-secret-read-from-a-mounted-file");
+    }
+
+    #[test]
+    fn test_default_secret_refused_in_production() {
+        let config = AppConfig {
+            environment: "production".to_string(),
+            security: SecurityConfig {
+                jwt_secret: DEFAULT_SECRETS[1].into(),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        assert!(config.validate().unwrap_err().contains("production"));
+    }
+
+    #[test]
+    fn test_s3_storage_requires_credentials() {
+        let mut config = AppConfig::default();
+        config.security.jwt_secret = "a-development-secret-of-32-characters".into();
+        config.storage.backend = StorageBackend::S3;
+        assert!(config.validate().is_err());
+
+        config.storage.s3.access_key_id = "minioadmin".to_string();
+        assert!(config.validate().is_ok());
+
+        config.environment = "production".to_string();
+        assert!(config.validate().unwrap_err().contains("storage signing key"));
+    }
+
+    #[test]
+    fn test_describe_config_sources() {
+        let mut overrides = CliOverrides::default();
+        overrides.set("server.port", 8081);
+        let figment = AppConfig::figment("does-not-exist").merge(overrides);
+
+        let config: AppConfig = Figment::from(Serialized::defaults(AppConfig::default()))
+            .merge(figment.clone())
+            .extract()
+            .unwrap();
+        let description = describe_config(&config, &figment).unwrap();
+
+        let line = |key: &str| {
+            description
+                .lines()
+                .find(|line| line.starts_with(&format!("{} ", key)))
+                .unwrap()
+                .to_string()
+        };
+        assert!(line("server.port").ends_with("= 8081  # command-line flag(s)"));
+        assert!(line("server.host").ends_with("# default"));
+        assert!(line("security.jwt_secret").contains(REDACTED));
+    }
+}
@@ -30,6 +30,12 @@ pub const USER_ID_HEADER: &str = "X-User-ID";
 /// API version header name
 pub const API_VERSION_HEADER: &str = "X-API-Version";
 
+/// Deprecation header name (RFC 9745)
+pub const DEPRECATION_HEADER: &str = "Deprecation";
+
+/// Sunset header name (RFC 8594)
+pub const SUNSET_HEADER: &str = "Sunset";
+
 /// Content type JSON
 pub const CONTENT_TYPE_JSON: &str = "application/json";
 
@@ -48,6 +54,9 @@ pub const READY_ENDPOINT: &str = "/ready";
 /// Live endpoint
 pub const LIVE_ENDPOINT: &str = "/live";
 
+/// Path prefix shared by every API version
+pub const API_PREFIX: &str = "/api";
+
 /// API base path
 pub const API_BASE_PATH: &str = "/api/v1";
 
@@ -65,8 +74,12 @@ pub mod events {
     pub const ORDER_CREATED: &str = "order.created";
     pub const ORDER_UPDATED: &str = "order.updated";
     pub const ORDER_CANCELLED: &str = "order.cancelled";
+    pub const PAYMENT_CREATED: &str = "payment.created";
     pub const PAYMENT_PROCESSED: &str = "payment.processed";
     pub const PAYMENT_FAILED: &str = "payment.failed";
+    pub const JOB_STARTED: &str = "job.started";
+    pub const JOB_COMPLETED: &str = "job.completed";
+    pub const JOB_FAILED: &str = "job.failed";
 }
 
 /// Job types
@@ -76,6 +89,38 @@ pub mod jobs {
     pub const GENERATE_REPORT: &str = "generate_report";
     pub const CLEANUP_DATA: &str = "cleanup_data";
     pub const SYNC_DATA: &str = "sync_data";
+
+    /// Job types the worker has a processor for; other types cannot be enqueued
+    pub const REGISTERED: &[&str] = &[SEND_EMAIL, PROCESS_PAYMENT, GENERATE_REPORT, CLEANUP_DATA];
+}
+
+/// User roles carried in access tokens
+pub mod roles {
+    pub const ADMIN: &str = "admin";
+    pub const USER: &str = "user";
+}
+
+/// Permissions granted through the roles claim of access tokens; administrators do not hold
+/// them implicitly
+pub mod permissions {
+    /// Read and export the tenant's audit log
+    pub const AUDIT_READ: &str = "audit:read";
+    /// Let administrators manage background jobs of every tenant, and jobs without a tenant
+    pub const JOBS_ALL_TENANTS: &str = "jobs:all-tenants";
+}
+
+/// Kafka message header names
+pub mod kafka_headers {
+    pub const CORRELATION_ID: &str = "correlation_id";
+    pub const EVENT_ID: &str = "event_id";
+    pub const EVENT_TYPE: &str = "event_type";
+    pub const SOURCE_SERVICE: &str = "source_service";
+
+    /// Set on dead-lettered messages: why handling failed and where the message came from
+    pub const DEAD_LETTER_ERROR: &str = "dead_letter_error";
+    pub const ORIGINAL_TOPIC: &str = "original_topic";
+    pub const ORIGINAL_PARTITION: &str = "original_partition";
+    pub const ORIGINAL_OFFSET: &str = "original_offset";
 }
 
 /// Cache key prefixes
@@ -85,6 +130,7 @@ pub mod cache_keys {
     pub const RATE_LIMIT: &str = "rate_limit";
     pub const CONFIG: &str = "config";
     pub const METRICS: &str = "metrics";
+    pub const NOTIFICATIONS: &str = "notifications";
 }
 
 /// Database table names
@@ -115,12 +161,33 @@ pub mod services {
 pub mod metrics {
     pub const HTTP_REQUESTS_TOTAL: &str = "http_requests_total";
     pub const HTTP_REQUEST_DURATION: &str = "http_request_duration_seconds";
+    pub const HTTP_REQUESTS_IN_FLIGHT: &str = "http_requests_in_flight";
+    pub const HTTP_REQUEST_SIZE: &str = "http_request_size_bytes";
+    pub const HTTP_RESPONSE_SIZE: &str = "http_response_size_bytes";
+    pub const API_VERSION_REQUESTS: &str = "api_version_requests_total";
     pub const DATABASE_CONNECTIONS: &str = "database_connections";
     pub const REDIS_CONNECTIONS: &str = "redis_connections";
     pub const KAFKA_MESSAGES_PRODUCED: &str = "kafka_messages_produced_total";
     pub const KAFKA_MESSAGES_CONSUMED: &str = "kafka_messages_consumed_total";
     pub const JOBS_PROCESSED: &str = "jobs_processed_total";
     pub const JOBS_FAILED: &str = "jobs_failed_total";
+    pub const HTTP_CLIENT_REQUESTS_TOTAL: &str = "http_client_requests_total";
+    pub const HTTP_CLIENT_RETRIES_TOTAL: &str = "http_client_retries_total";
+    /// Circuit state per host: 0 closed, 1 open, 2 half-open
+    pub const HTTP_CLIENT_CIRCUIT_STATE: &str = "http_client_circuit_state";
+    pub const HTTP_CLIENT_CIRCUIT_REJECTIONS: &str = "http_client_circuit_rejections_total";
+
+    /// Path label for requests that did not match a route
+    pub const UNMATCHED_PATH: &str = "unmatched";
+
+    /// Prefix a metric name with the configured namespace, e.g. `app_http_requests_total`
+    pub fn with_namespace(namespace: &str, name: &str) -> String {
+        if namespace.is_empty() {
+            name.to_string()
+        } else {
+            format!("{}_{}", namespace, name)
+        }
+    }
 }
 
 /// Log levels
@@ -177,14 +244,16 @@ pub mod rate_limits {
 pub mod file_limits {
     pub const MAX_UPLOAD_SIZE: usize = 10 * 1024 * 1024; // 10MB
     pub const MAX_JSON_PAYLOAD: usize = 1024 * 1024; // 1MB
+    /// Allowance for multipart boundaries and part headers around an uploaded file
+    pub const MULTIPART_OVERHEAD: usize = 64 * 1024; // 64KB
 }
 
 /// Validation rules
 pub mod validation {
-    pub const MIN_PASSWORD_LENGTH: usize = 8;
-    pub const MAX_PASSWORD_LENGTH: usize = 128;
-    pub const MIN_USERNAME_LENGTH: usize = 3;
-    pub const MAX_USERNAME_LENGTH: usize = 50;
-    pub const MAX_EMAIL_LENGTH: usize = 254;
-    pub const MAX_NAME_LENGTH: usize = 100;
+    pub const MIN_PASSWORD_LENGTH: u64 = 8;
+    pub const MAX_PASSWORD_LENGTH: u64 = 128;
+    pub const MIN_USERNAME_LENGTH: u64 = 3;
+    pub const MAX_USERNAME_LENGTH: u64 = 50;
+    pub const MAX_EMAIL_LENGTH: u64 = 254;
+    pub const MAX_NAME_LENGTH: u64 = 100;
 }
\ No newline at end of file
@@ -0,0 +1,115 @@
+//! Request-scoped context propagated across async boundaries
+
+use std::future::Future;
+use uuid::Uuid;
+
+use crate::{CorrelationId, TenantId, UserId};
+
+tokio::task_local! {
+    static CORRELATION_ID: CorrelationId;
+    static REQUEST_ID: Uuid;
+    static ACTOR: Actor;
+}
+
+/// Caller behind the request being handled, recorded with audit entries
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Actor {
+    pub user_id: Option<UserId>,
+    pub tenant_id: Option<TenantId>,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+}
+
+/// Run a future with the given correlation id in scope
+pub async fn with_correlation_id<F>(correlation_id: CorrelationId, future: F) -> F::Output
+where
+    F: Future,
+{
+    CORRELATION_ID.scope(correlation_id, future).await
+}
+
+/// Correlation id of the request or job currently being handled, if any
+pub fn correlation_id() -> Option<CorrelationId> {
+    CORRELATION_ID.try_with(|id| *id).ok()
+}
+
+/// Correlation id in scope, or a fresh one when work did not originate from a request
+pub fn current_correlation_id() -> CorrelationId {
+    correlation_id().unwrap_or_else(crate::generate_correlation_id)
+}
+
+/// Run a future with the id of the request being handled (`X-Request-ID`) in scope
+pub async fn with_request_id<F>(request_id: Uuid, future: F) -> F::Output
+where
+    F: Future,
+{
+    REQUEST_ID.scope(request_id, future).await
+}
+
+/// Id of the request currently being handled, if any
+pub fn request_id() -> Option<Uuid> {
+    REQUEST_ID.try_with(|id| *id).ok()
+}
+
+/// Run a future on behalf of the given caller
+pub async fn with_actor<F>(actor: Actor, future: F) -> F::Output
+where
+    F: Future,
+{
+    ACTOR.scope(actor, future).await
+}
+
+/// Caller of the request currently being handled, if any
+pub fn actor() -> Option<Actor> {
+    ACTOR.try_with(Actor::clone).ok()
+}
+
+/// Parse a correlation id received from a header, ignoring malformed values
+pub fn parse_correlation_id(value: &str) -> Option<CorrelationId> {
+    CorrelationId::parse_str(value.trim()).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_correlation_id_scope() {
+        assert!(correlation_id().is_none());
+
+        let id = Uuid::new_v4();
+        let inside = with_correlation_id(id, async { (correlation_id(), current_correlation_id()) }).await;
+        assert_eq!(inside, (Some(id), id));
+        assert!(correlation_id().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_request_id_scope() {
+        assert!(request_id().is_none());
+
+        let id = Uuid::new_v4();
+        assert_eq!(with_request_id(id, async { request_id() }).await, Some(id));
+        assert!(request_id().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_actor_scope() {
+        assert!(actor().is_none());
+
+        let caller = Actor {
+            user_id: Some(Uuid::new_v4()),
+            ip_address: Some("203.0.113.7".to_string()),
+            ..Actor::default()
+        };
+        let inside = with_actor(caller.clone(), async { actor() }).await;
+        assert_eq!(inside, Some(caller));
+        assert!(actor().is_none());
+    }
+
+    #[test]
+    fn test_parse_correlation_id() {
+        let id = Uuid::new_v4();
+        assert_eq!(parse_correlation_id(&format!(" {} ", id)), Some(id));
+        assert!(parse_correlation_id("not-a-uuid").is_none());
+    }
+}
@@ -20,6 +20,9 @@ pub enum AppError {
     #[error("Validation error: {0}")]
     Validation(String),
 
+    #[error("Validation failed: {0}")]
+    FieldValidation(ValidationErrors),
+
     #[error("Authentication error: {0}")]
     Authentication(String),
 
@@ -38,6 +41,21 @@ pub enum AppError {
     #[error("Internal server error: {0}")]
     Internal(String),
 
+    #[error("Not implemented: {0}")]
+    NotImplemented(String),
+
+    #[error("Payload too large: {0}")]
+    PayloadTooLarge(String),
+
+    #[error("Request timed out: {0}")]
+    Timeout(String),
+
+    #[error("Rate limit exceeded: {0}")]
+    RateLimited(String),
+
+    #[error("Service unavailable: {0}")]
+    ServiceUnavailable(String),
+
     #[error("External service error: {0}")]
     ExternalService(String),
 
@@ -61,15 +79,47 @@ impl AppError {
     /// Get the HTTP status code for this error
     pub fn status_code(&self) -> u16 {
         match self {
-            AppError::Validation(_) | AppError::BadRequest(_) => 400,
+            AppError::Validation(_) | AppError::FieldValidation(_) | AppError::BadRequest(_) => 400,
             AppError::Authentication(_) => 401,
             AppError::Authorization(_) => 403,
             AppError::NotFound(_) => 404,
             AppError::Conflict(_) => 409,
+            AppError::Timeout(_) => 408,
+            AppError::PayloadTooLarge(_) => 413,
+            AppError::RateLimited(_) => 429,
+            AppError::NotImplemented(_) => 501,
+            AppError::ServiceUnavailable(_) => 503,
             _ => 500,
         }
     }
 
+    /// Stable machine-readable error code exposed to API clients
+    pub fn error_code(&self) -> &'static str {
+        match self {
+            AppError::Database(_) => "DATABASE_ERROR",
+            AppError::Redis(_) => "CACHE_ERROR",
+            AppError::Kafka(_) => "MESSAGING_ERROR",
+            AppError::Serialization(_) => "SERIALIZATION_ERROR",
+            AppError::Validation(_) | AppError::FieldValidation(_) => "VALIDATION_ERROR",
+            AppError::Authentication(_) => "UNAUTHENTICATED",
+            AppError::Authorization(_) => "FORBIDDEN",
+            AppError::NotFound(_) => "NOT_FOUND",
+            AppError::Conflict(_) => "CONFLICT",
+            AppError::BadRequest(_) => "BAD_REQUEST",
+            AppError::Internal(_) => "INTERNAL_ERROR",
+            AppError::NotImplemented(_) => "NOT_IMPLEMENTED",
+            AppError::PayloadTooLarge(_) => "PAYLOAD_TOO_LARGE",
+            AppError::Timeout(_) => "REQUEST_TIMEOUT",
+            AppError::RateLimited(_) => "RATE_LIMITED",
+            AppError::ServiceUnavailable(_) => "SERVICE_UNAVAILABLE",
+            AppError::ExternalService(_) => "EXTERNAL_SERVICE_ERROR",
+            AppError::Configuration(_) => "CONFIGURATION_ERROR",
+            AppError::Network(_) => "NETWORK_ERROR",
+            AppError::Io(_) => "IO_ERROR",
+            AppError::Generic(_) => "INTERNAL_ERROR",
+        }
+    }
+
     /// Check if this error should be logged as an error (vs warning/info)
     pub fn should_log_error(&self) -> bool {
         matches!(
@@ -123,14 +173,147 @@ impl ValidationErrors {
     }
 }
 
+impl std::fmt::Display for ValidationErrors {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let fields: Vec<String> = self
+            .errors
+            .iter()
+            .map(|error| format!("{}: {}", error.field, error.message))
+            .collect();
+        f.write_str(&fields.join(", "))
+    }
+}
+
 impl Default for ValidationErrors {
     fn default() -> Self {
         Self::new()
     }
 }
 
+impl From<validator::ValidationErrors> for ValidationErrors {
+    fn from(errors: validator::ValidationErrors) -> Self {
+        let mut converted = Self::new();
+        collect_validation_errors(&mut converted, None, &errors);
+        converted.errors.sort_by(|a, b| a.field.cmp(&b.field));
+        converted
+    }
+}
+
+impl From<validator::ValidationErrors> for AppError {
+    fn from(errors: validator::ValidationErrors) -> Self {
+        AppError::FieldValidation(errors.into())
+    }
+}
+
+/// Flatten nested validator errors into dotted field paths, e.g. `items[0].name`
+fn collect_validation_errors(
+    target: &mut ValidationErrors,
+    prefix: Option<&str>,
+    errors: &validator::ValidationErrors,
+) {
+    use validator::ValidationErrorsKind;
+
+    for (field, kind) in errors.errors() {
+        let path = match prefix {
+            Some(prefix) => format!("{}.{}", prefix, field),
+            None => field.to_string(),
+        };
+
+        match kind {
+            ValidationErrorsKind::Field(field_errors) => {
+                for error in field_errors {
+                    target.add(ValidationError::new(path.clone(), validation_message(error)));
+                }
+            }
+            ValidationErrorsKind::Struct(nested) => collect_validation_errors(target, Some(&path), nested),
+            ValidationErrorsKind::List(items) => {
+                for (index, nested) in items {
+                    collect_validation_errors(target, Some(&format!("{}[{}]", path, index)), nested);
+                }
+            }
+        }
+    }
+}
+
+/// Human readable message for a validator error, preferring an explicit `message`
+fn validation_message(error: &validator::ValidationError) -> String {
+    if let Some(message) = &error.message {
+        return message.to_string();
+    }
+
+    let param = |name: &str| error.params.get(name).map(|value| value.to_string());
+
+    match error.code.as_ref() {
+        "length" => match (param("min"), param("max"), param("equal")) {
+            (_, _, Some(equal)) => format!("must be exactly {} characters long", equal),
+            (Some(min), Some(max), _) => format!("must be between {} and {} characters long", min, max),
+            (Some(min), None, _) => format!("must be at least {} characters long", min),
+            (None, Some(max), _) => format!("must be at most {} characters long", max),
+            _ => "has an invalid length".to_string(),
+        },
+        "range" => match (param("min"), param("max")) {
+            (Some(min), Some(max)) => format!("must be between {} and {}", min, max),
+            (Some(min), None) => format!("must be at least {}", min),
+            (None, Some(max)) => format!("must be at most {}", max),
+            _ => "is out of range".to_string(),
+        },
+        "email" => "must be a valid email address".to_string(),
+        "url" => "must be a valid URL".to_string(),
+        "required" => "is required".to_string(),
+        "must_match" => match param("other") {
+            Some(other) => format!("must match {}", other.trim_matches('"')),
+            None => "does not match".to_string(),
+        },
+        code => format!("is invalid ({})", code),
+    }
+}
+
 impl From<ValidationErrors> for AppError {
     fn from(errors: ValidationErrors) -> Self {
-        AppError::Validation(format!("Validation failed: {:?}", errors.errors))
+        AppError::FieldValidation(errors)
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use validator::Validate;
+
+    #[derive(Validate)]
+    struct Signup {
+        #[validate(email)]
+        email: String,
+        #[validate(length(min = 3, max = 50))]
+        username: String,
+        #[validate(range(max = 10))]
+        count: Option<u32>,
+    }
+
+    #[test]
+    fn test_validator_errors_are_converted_per_field() {
+        let signup = Signup {
+            email: "not-an-email".to_string(),
+            username: "ab".to_string(),
+            count: Some(11),
+        };
+
+        let errors: ValidationErrors = signup.validate().unwrap_err().into();
+        let fields: Vec<_> = errors.errors.iter().map(|e| (e.field.as_str(), e.message.as_str())).collect();
+
+        assert_eq!(
+            fields,
+            vec![
+                ("count", "must be at most 10"),
+                ("email", "must be a valid email address"),
+                ("username", "must be between 3 and 50 characters long"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_error_codes() {
+        assert_eq!(AppError::from(ValidationErrors::new()).error_code(), "VALIDATION_ERROR");
+        assert_eq!(AppError::NotImplemented("login".to_string()).status_code(), 501);
+        assert_eq!(AppError::NotFound("user".to_string()).error_code(), "NOT_FOUND");
+    }
+}
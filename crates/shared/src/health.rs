@@ -0,0 +1,240 @@
+//! Health check registry shared by all services
+
+use chrono::Utc;
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+use crate::{AppResult, DependencyHealth, HealthCheck, HealthStatus, ServiceStatus};
+
+type SharedCheck = Arc<dyn HealthCheck + Send + Sync>;
+
+/// Dependency checks for a service, plus its draining state.
+///
+/// Checks run concurrently, each bounded by its own [`HealthCheck::timeout`]. A failing
+/// critical check makes the service unhealthy; a failing non-critical check only degrades it.
+/// Clones share the same checks and draining flag.
+#[derive(Clone)]
+pub struct HealthRegistry {
+    service: String,
+    version: String,
+    checks: Arc<Vec<SharedCheck>>,
+    draining: Arc<AtomicBool>,
+}
+
+impl HealthRegistry {
+    pub fn new(service: impl Into<String>, version: impl Into<String>) -> Self {
+        Self {
+            service: service.into(),
+            version: version.into(),
+            checks: Arc::new(Vec::new()),
+            draining: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Add a check, critical unless the check says otherwise
+    pub fn register<C>(mut self, check: C) -> Self
+    where
+        C: HealthCheck + Send + Sync + 'static,
+    {
+        Arc::make_mut(&mut self.checks).push(Arc::new(check));
+        self
+    }
+
+    /// Add a check whose failure only degrades the service
+    pub fn register_non_critical<C>(self, check: C) -> Self
+    where
+        C: HealthCheck + Send + Sync + 'static,
+    {
+        self.register(NonCritical(check))
+    }
+
+    /// Names of the registered checks
+    pub fn check_names(&self) -> Vec<&'static str> {
+        self.checks.iter().map(|check| check.name()).collect()
+    }
+
+    /// Start reporting not-ready so load balancers stop sending traffic
+    pub fn start_draining(&self) {
+        self.draining.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_draining(&self) -> bool {
+        self.draining.load(Ordering::SeqCst)
+    }
+
+    /// Run every check and aggregate the results
+    pub async fn check(&self) -> HealthStatus {
+        let dependencies = futures::future::join_all(self.checks.iter().map(|check| run_check(check.as_ref()))).await;
+
+        HealthStatus {
+            service: self.service.clone(),
+            version: self.version.clone(),
+            status: overall_status(&dependencies),
+            timestamp: Utc::now(),
+            dependencies,
+        }
+    }
+
+    /// Run every check and log failures, for services without a health endpoint
+    pub async fn log_status(&self) -> ServiceStatus {
+        let health = self.check().await;
+        for dependency in health.dependencies.iter().filter(|d| d.status != ServiceStatus::Healthy) {
+            tracing::warn!(
+                dependency = %dependency.name,
+                critical = dependency.critical,
+                "Health check failed: {}",
+                dependency.error.as_deref().unwrap_or("unhealthy")
+            );
+        }
+        tracing::info!(status = ?health.status, "Health checks completed");
+        health.status
+    }
+
+    /// Like [`check`](Self::check), but unhealthy while draining
+    pub async fn readiness(&self) -> HealthStatus {
+        let mut health = self.check().await;
+        if self.is_draining() {
+            health.status = ServiceStatus::Unhealthy;
+        }
+        health
+    }
+}
+
+async fn run_check(check: &(dyn HealthCheck + Send + Sync)) -> DependencyHealth {
+    let start = Instant::now();
+    let timeout = check.timeout();
+
+    let (status, error) = match tokio::time::timeout(Duration::from_secs(timeout), check.check()).await {
+        Ok(Ok(true)) => (ServiceStatus::Healthy, None),
+        Ok(Ok(false)) => (ServiceStatus::Unhealthy, None),
+        Ok(Err(e)) => (ServiceStatus::Unhealthy, Some(e.to_string())),
+        Err(_) => (ServiceStatus::Unhealthy, Some(format!("Timed out after {} seconds", timeout))),
+    };
+
+    DependencyHealth {
+        name: check.name().to_string(),
+        status,
+        response_time_ms: Some(start.elapsed().as_millis() as u64),
+        error,
+        critical: check.critical(),
+    }
+}
+
+/// Unhealthy if any critical dependency fails, degraded if only non-critical ones do
+pub fn overall_status(dependencies: &[DependencyHealth]) -> ServiceStatus {
+    let failing = dependencies
+        .iter()
+        .filter(|dependency| dependency.status != ServiceStatus::Healthy);
+
+    let mut status = ServiceStatus::Healthy;
+    for dependency in failing {
+        if dependency.critical {
+            return ServiceStatus::Unhealthy;
+        }
+        status = ServiceStatus::Degraded;
+    }
+    status
+}
+
+/// Wraps a check so its failure only degrades the service
+pub struct NonCritical<C>(pub C);
+
+#[async_trait::async_trait]
+impl<C: HealthCheck + Send + Sync> HealthCheck for NonCritical<C> {
+    async fn check(&self) -> AppResult<bool> {
+        self.0.check().await
+    }
+
+    fn name(&self) -> &'static str {
+        self.0.name()
+    }
+
+    fn timeout(&self) -> u64 {
+        self.0.timeout()
+    }
+
+    fn critical(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::AppError;
+
+    struct StaticCheck {
+        name: &'static str,
+        healthy: bool,
+    }
+
+    #[async_trait::async_trait]
+    impl HealthCheck for StaticCheck {
+        async fn check(&self) -> AppResult<bool> {
+            if self.healthy {
+                Ok(true)
+            } else {
+                Err(AppError::ServiceUnavailable(format!("{} is down", self.name)))
+            }
+        }
+
+        fn name(&self) -> &'static str {
+            self.name
+        }
+    }
+
+    struct HangingCheck;
+
+    #[async_trait::async_trait]
+    impl HealthCheck for HangingCheck {
+        async fn check(&self) -> AppResult<bool> {
+            std::future::pending().await
+        }
+
+        fn name(&self) -> &'static str {
+            "hanging"
+        }
+
+        fn timeout(&self) -> u64 {
+            1
+        }
+    }
+
+    #[tokio::test]
+    async fn test_non_critical_failure_degrades() {
+        let registry = HealthRegistry::new("test", "0.1.0")
+            .register(StaticCheck { name: "postgres", healthy: true })
+            .register_non_critical(StaticCheck { name: "kafka", healthy: false });
+
+        let health = registry.check().await;
+        assert_eq!(health.status, ServiceStatus::Degraded);
+        assert_eq!(health.dependencies[1].error.as_deref(), Some("Service unavailable: kafka is down"));
+    }
+
+    #[tokio::test]
+    async fn test_critical_failure_and_draining() {
+        let registry = HealthRegistry::new("test", "0.1.0")
+            .register(StaticCheck { name: "postgres", healthy: true });
+        assert_eq!(registry.readiness().await.status, ServiceStatus::Healthy);
+
+        registry.clone().start_draining();
+        assert_eq!(registry.readiness().await.status, ServiceStatus::Unhealthy);
+
+        let registry = registry.register(StaticCheck { name: "redis", healthy: false });
+        assert_eq!(registry.check().await.status, ServiceStatus::Unhealthy);
+    }
+
+    #[tokio::test]
+    async fn test_check_timeout() {
+        let registry = HealthRegistry::new("test", "0.1.0").register(HangingCheck);
+
+        let health = registry.check().await;
+        assert_eq!(health.status, ServiceStatus::Unhealthy);
+        assert_eq!(health.dependencies[0].error.as_deref(), Some("Timed out after 1 seconds"));
+    }
+}
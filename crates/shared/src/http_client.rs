@@ -0,0 +1,343 @@
+//! Outbound HTTP client with per-host circuit breakers and retries
+//!
+//! Every request carries the correlation id and trace context of the work that sends it.
+//! Idempotent requests are retried with jittered exponential backoff after connection
+//! errors, timeouts and overload responses; other requests are sent once. Each host gets its
+//! own [`ThresholdCircuitBreaker`], so one failing dependency does not affect calls to others.
+
+use metrics::{counter, gauge};
+use rand::Rng;
+use reqwest::{Client, Method, Request, RequestBuilder, Response, StatusCode};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tracing::{debug, warn};
+
+use crate::{
+    context,
+    metrics::{self as metric_names, with_namespace},
+    AppConfig, AppError, AppResult, CircuitBreaker, CircuitBreakerState, HttpClientConfig,
+    ThresholdCircuitBreaker, CORRELATION_ID_HEADER,
+};
+
+/// HTTP client shared by a service for calls to other services and third parties
+#[derive(Debug, Clone)]
+pub struct HttpClient {
+    client: Client,
+    config: HttpClientConfig,
+    breakers: Arc<Mutex<HashMap<String, ThresholdCircuitBreaker>>>,
+    metric_names: Arc<MetricNames>,
+}
+
+#[derive(Debug)]
+struct MetricNames {
+    requests_total: String,
+    retries_total: String,
+    circuit_state: String,
+    circuit_rejections: String,
+}
+
+impl HttpClient {
+    pub fn new(config: &AppConfig) -> AppResult<Self> {
+        let settings = &config.http_client;
+        let client = Client::builder()
+            .timeout(config.http_client_timeout())
+            .connect_timeout(Duration::from_secs(settings.connect_timeout))
+            .user_agent(format!("{}/{}", config.service_name, config.version))
+            .build()
+            .map_err(|e| AppError::Configuration(format!("Failed to create HTTP client: {}", e)))?;
+
+        let namespace = &config.metrics.namespace;
+        Ok(Self {
+            client,
+            config: settings.clone(),
+            breakers: Arc::new(Mutex::new(HashMap::new())),
+            metric_names: Arc::new(MetricNames {
+                requests_total: with_namespace(namespace, metric_names::HTTP_CLIENT_REQUESTS_TOTAL),
+                retries_total: with_namespace(namespace, metric_names::HTTP_CLIENT_RETRIES_TOTAL),
+                circuit_state: with_namespace(namespace, metric_names::HTTP_CLIENT_CIRCUIT_STATE),
+                circuit_rejections: with_namespace(namespace, metric_names::HTTP_CLIENT_CIRCUIT_REJECTIONS),
+            }),
+        })
+    }
+
+    /// Start building a request; send it with [`send`](Self::send)
+    pub fn request(&self, method: Method, url: &str) -> RequestBuilder {
+        self.client.request(method, url)
+    }
+
+    pub fn get(&self, url: &str) -> RequestBuilder {
+        self.request(Method::GET, url)
+    }
+
+    pub fn post(&self, url: &str) -> RequestBuilder {
+        self.request(Method::POST, url)
+    }
+
+    pub fn put(&self, url: &str) -> RequestBuilder {
+        self.request(Method::PUT, url)
+    }
+
+    pub fn delete(&self, url: &str) -> RequestBuilder {
+        self.request(Method::DELETE, url)
+    }
+
+    /// Send a request through its host's circuit breaker, retrying idempotent requests.
+    ///
+    /// Responses of any status are returned as they are; only when every attempt failed to
+    /// get a response, or the circuit is open, is an error returned. 5xx and 429 responses
+    /// count as failures of the host.
+    pub async fn send(&self, request: RequestBuilder) -> AppResult<Response> {
+        let request = propagate_context(request).build()?;
+        let host = host_key(&request);
+        let breaker = self.breaker(&host);
+        // Requests with streaming bodies cannot be replayed
+        let attempts = if is_idempotent(request.method()) && request.try_clone().is_some() {
+            self.config.max_attempts.max(1)
+        } else {
+            1
+        };
+
+        let method = request.method().to_string();
+        let mut request = Some(request);
+        let mut attempt = 1;
+        loop {
+            let current = if attempt < attempts {
+                request.as_ref().and_then(Request::try_clone)
+            } else {
+                request.take()
+            }
+            .expect("replayable request");
+
+            let permit = match breaker.acquire() {
+                Ok(permit) => permit,
+                Err(e) => {
+                    counter!(self.metric_names.circuit_rejections.clone(), "host" => host.clone()).increment(1);
+                    self.record_state(&breaker);
+                    return Err(e);
+                }
+            };
+            let result = self.client.execute(current).await;
+            let retryable = match &result {
+                Ok(response) => is_retryable_status(response.status()),
+                Err(e) => e.is_connect() || e.is_timeout(),
+            };
+            permit.record(!failed(&result));
+            self.record_state(&breaker);
+
+            let outcome = match &result {
+                Ok(response) => response.status().as_u16().to_string(),
+                Err(_) => "error".to_string(),
+            };
+            counter!(self.metric_names.requests_total.clone(),
+                "host" => host.clone(),
+                "method" => method.clone(),
+                "outcome" => outcome
+            )
+            .increment(1);
+
+            if !retryable || attempt >= attempts {
+                return result.map_err(AppError::from);
+            }
+
+            let delay = self.backoff(attempt);
+            match &result {
+                Ok(response) => debug!(host = %host, attempt, "Retrying after {} in {:?}", response.status(), delay),
+                Err(e) => warn!(host = %host, attempt, "Retrying after error in {:?}: {}", delay, e),
+            }
+            counter!(self.metric_names.retries_total.clone(), "host" => host.clone()).increment(1);
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
+    }
+
+    /// State of the circuit for `host`, given as `host:port`
+    pub fn circuit_state(&self, host: &str) -> CircuitBreakerState {
+        self.breakers
+            .lock()
+            .unwrap()
+            .get(host)
+            .map(|breaker| breaker.state())
+            .unwrap_or(CircuitBreakerState::Closed)
+    }
+
+    fn breaker(&self, host: &str) -> ThresholdCircuitBreaker {
+        self.breakers
+            .lock()
+            .unwrap()
+            .entry(host.to_string())
+            .or_insert_with(|| ThresholdCircuitBreaker::from_config(host, &self.config.circuit_breaker))
+            .clone()
+    }
+
+    fn record_state(&self, breaker: &ThresholdCircuitBreaker) {
+        let value = match breaker.state() {
+            CircuitBreakerState::Closed => 0.0,
+            CircuitBreakerState::Open => 1.0,
+            CircuitBreakerState::HalfOpen => 2.0,
+        };
+        gauge!(self.metric_names.circuit_state.clone(), "host" => breaker.name().to_string()).set(value);
+    }
+
+    /// Random delay before retry number `attempt`, up to an exponentially growing bound
+    fn backoff(&self, attempt: u32) -> Duration {
+        let bound = self
+            .config
+            .initial_backoff
+            .saturating_mul(2u64.saturating_pow(attempt - 1))
+            .min(self.config.max_backoff);
+        Duration::from_millis(rand::thread_rng().gen_range(0..=bound))
+    }
+}
+
+/// Add the correlation id and trace context of the current work
+fn propagate_context(mut request: RequestBuilder) -> RequestBuilder {
+    if let Some(correlation_id) = context::correlation_id() {
+        request = request.header(CORRELATION_ID_HEADER, correlation_id.to_string());
+    }
+    #[cfg(feature = "telemetry")]
+    {
+        request = crate::telemetry::inject_trace_context(request);
+    }
+    request
+}
+
+/// Circuits are kept per `host:port`
+fn host_key(request: &Request) -> String {
+    let url = request.url();
+    format!(
+        "{}:{}",
+        url.host_str().unwrap_or_default(),
+        url.port_or_known_default().unwrap_or_default()
+    )
+}
+
+fn is_idempotent(method: &Method) -> bool {
+    matches!(
+        *method,
+        Method::GET | Method::HEAD | Method::PUT | Method::DELETE | Method::OPTIONS | Method::TRACE
+    )
+}
+
+fn is_retryable_status(status: StatusCode) -> bool {
+    matches!(
+        status,
+        StatusCode::TOO_MANY_REQUESTS | StatusCode::BAD_GATEWAY | StatusCode::SERVICE_UNAVAILABLE | StatusCode::GATEWAY_TIMEOUT
+    )
+}
+
+/// Whether an attempt counts against the host's circuit
+fn failed(result: &Result<Response, reqwest::Error>) -> bool {
+    match result {
+        Ok(response) => response.status().is_server_error() || response.status() == StatusCode::TOO_MANY_REQUESTS,
+        Err(_) => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+    };
+
+    /// Stand-in server answering every request with the next of `statuses`, or 200 after
+    /// they run out; returns its address and a count of requests received
+    async fn serve(statuses: Vec<u16>) -> (String, Arc<AtomicUsize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = format!("http://{}", listener.local_addr().unwrap());
+        let received = Arc::new(AtomicUsize::new(0));
+
+        let counter = received.clone();
+        tokio::spawn(async move {
+            loop {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let index = counter.fetch_add(1, Ordering::SeqCst);
+                let status = statuses.get(index).copied().unwrap_or(200);
+                let mut buffer = vec![0; 16 * 1024];
+                let _ = socket.read(&mut buffer).await.unwrap();
+                let response = format!("HTTP/1.1 {} Status\r\nconnection: close\r\ncontent-length: 0\r\n\r\n", status);
+                socket.write_all(response.as_bytes()).await.unwrap();
+            }
+        });
+
+        (address, received)
+    }
+
+    fn client(failure_threshold: u32) -> HttpClient {
+        let mut config = AppConfig::default();
+        config.http_client.initial_backoff = 1;
+        config.http_client.max_backoff = 5;
+        config.http_client.circuit_breaker.failure_threshold = failure_threshold;
+        HttpClient::new(&config).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_idempotent_requests_retried() {
+        let (address, received) = serve(vec![503, 502]).await;
+        let client = client(10);
+
+        let response = client.send(client.get(&format!("{}/status", address))).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(received.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_post_sent_once() {
+        let (address, received) = serve(vec![503]).await;
+        let client = client(10);
+
+        let response = client.send(client.post(&address).body("{}")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(received.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_circuit_opens_per_host() {
+        let (failing, received) = serve(vec![500; 10]).await;
+        let (healthy, _) = serve(Vec::new()).await;
+        let client = client(2);
+
+        for _ in 0..2 {
+            let response = client.send(client.get(&failing)).await.unwrap();
+            assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        }
+        let host = failing.trim_start_matches("http://");
+        assert_eq!(client.circuit_state(host), CircuitBreakerState::Open);
+
+        let refused = client.send(client.get(&failing)).await;
+        assert!(matches!(refused, Err(AppError::ServiceUnavailable(_))));
+        assert_eq!(received.load(Ordering::SeqCst), 2);
+
+        assert!(client.send(client.get(&healthy)).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_correlation_id_sent() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = format!("http://{}", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buffer = vec![0; 16 * 1024];
+            let read = socket.read(&mut buffer).await.unwrap();
+            socket
+                .write_all(b"HTTP/1.1 204 No Content\r\nconnection: close\r\n\r\n")
+                .await
+                .unwrap();
+            String::from_utf8_lossy(&buffer[..read]).to_lowercase()
+        });
+
+        let client = client(10);
+        let correlation_id = crate::generate_correlation_id();
+        context::with_correlation_id(correlation_id, client.send(client.get(&address)))
+            .await
+            .unwrap();
+
+        let request = server.await.unwrap();
+        assert!(request.contains(&format!("x-correlation-id: {}", correlation_id)));
+    }
+}
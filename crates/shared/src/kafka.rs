@@ -0,0 +1,64 @@
+//! Kafka client configuration and health check shared by the services
+
+use async_trait::async_trait;
+use rdkafka::{
+    config::ClientConfig,
+    consumer::{BaseConsumer, Consumer},
+};
+use std::{sync::Arc, time::Duration};
+
+use crate::{AppError, AppResult, HealthCheck, KafkaConfig};
+
+/// Build a Kafka client configuration from the shared Kafka settings
+pub fn client_config(kafka: &KafkaConfig) -> ClientConfig {
+    let mut config = ClientConfig::new();
+    for (key, value) in kafka.client_properties() {
+        config.set(key, value);
+    }
+    config
+}
+
+/// Checks that the Kafka cluster returns metadata with at least one broker
+pub struct KafkaHealthCheck {
+    client: Arc<BaseConsumer>,
+    timeout: u64,
+}
+
+impl KafkaHealthCheck {
+    pub fn new(kafka: &KafkaConfig, timeout: u64) -> AppResult<Self> {
+        let client: BaseConsumer = client_config(kafka)
+            .create()
+            .map_err(|e| AppError::Kafka(format!("Failed to create health check client: {}", e)))?;
+
+        Ok(Self {
+            client: Arc::new(client),
+            timeout,
+        })
+    }
+}
+
+#[async_trait]
+impl HealthCheck for KafkaHealthCheck {
+    async fn check(&self) -> AppResult<bool> {
+        let client = self.client.clone();
+        let timeout = Duration::from_secs(self.timeout);
+
+        // Metadata requests block, so keep them off the runtime threads
+        let brokers = tokio::task::spawn_blocking(move || {
+            client.fetch_metadata(None, timeout).map(|metadata| metadata.brokers().len())
+        })
+        .await
+        .map_err(|e| AppError::Internal(format!("Kafka health check panicked: {}", e)))?
+        .map_err(|e| AppError::Kafka(e.to_string()))?;
+
+        Ok(brokers > 0)
+    }
+
+    fn name(&self) -> &'static str {
+        "kafka"
+    }
+
+    fn timeout(&self) -> u64 {
+        self.timeout
+    }
+}
@@ -2,16 +2,34 @@
 //! used across all microservices in the application.
 
 pub mod config;
+pub mod circuit_breaker;
 pub mod constants;
+pub mod context;
 pub mod errors;
+pub mod health;
+#[cfg(feature = "http")]
+pub mod http_client;
+#[cfg(feature = "kafka")]
+pub mod kafka;
+#[cfg(feature = "telemetry")]
+pub mod logging;
+pub mod reload;
+pub mod response;
+pub mod runtime;
+#[cfg(feature = "telemetry")]
+pub mod telemetry;
 pub mod traits;
 pub mod types;
 pub mod utils;
 
 // Re-export commonly used items
+pub use circuit_breaker::*;
 pub use config::*;
 pub use constants::*;
 pub use errors::*;
+pub use health::*;
+pub use reload::*;
+pub use response::*;
 pub use traits::*;
 pub use types::*;
 pub use utils::*;
\ No newline at end of file
@@ -0,0 +1,318 @@
+//! Logging setup shared by the service binaries
+//!
+//! Log lines go to stdout, a size- and time-rotated file, or both, through non-blocking
+//! writers. Spans are also handed to the trace exporter (see [`crate::telemetry`]).
+
+use chrono::{DateTime, DurationRound, Utc};
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+use tracing::{info, warn, Subscriber};
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_subscriber::{
+    fmt::MakeWriter, layer::SubscriberExt, registry::LookupSpan, reload, util::SubscriberInitExt, EnvFilter, Layer,
+    Registry,
+};
+
+use crate::{telemetry, AppConfig, AppError, AppResult, ConfigHandle, LogRotation, LoggingConfig, ValidateConfig};
+
+/// Handle for swapping the log filter at runtime
+pub type LogFilterHandle = reload::Handle<EnvFilter, Registry>;
+
+/// Installed logging. Keep it alive for the life of the process; dropping it flushes log
+/// lines still queued for the writers.
+pub struct Logging {
+    filter: LogFilterHandle,
+    _writers: Vec<WorkerGuard>,
+}
+
+/// Install the global subscriber as described by `config.logging`.
+///
+/// `RUST_LOG` takes precedence over `logging.level` when set.
+pub fn init(config: &AppConfig) -> AppResult<Logging> {
+    let logging = &config.logging;
+    let env_filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(&logging.level));
+    let (env_filter, filter) = reload::Layer::new(env_filter);
+    let mut writers = Vec::new();
+
+    let stdout = logging.writes_stdout().then(|| {
+        let (writer, guard) = tracing_appender::non_blocking(io::stdout());
+        writers.push(guard);
+        fmt_layer(&logging.format, writer, true)
+    });
+
+    let file = if logging.writes_file() {
+        let file = RotatingFile::from_config(logging)
+            .map_err(|e| AppError::Configuration(format!("Failed to open log file: {}", e)))?;
+        let (writer, guard) = tracing_appender::non_blocking(file);
+        writers.push(guard);
+        Some(fmt_layer(&logging.format, writer, false))
+    } else {
+        None
+    };
+
+    tracing_subscriber::registry()
+        .with(env_filter)
+        .with(telemetry::tracing_layer(config)?)
+        .with(stdout)
+        .with(file)
+        .try_init()
+        .map_err(|e| AppError::Internal(format!("Failed to install logging: {}", e)))?;
+
+    Ok(Logging {
+        filter,
+        _writers: writers,
+    })
+}
+
+/// Formatting layer for `logging.format`, `json` or human-readable
+fn fmt_layer<S, W>(format: &str, writer: W, ansi: bool) -> Box<dyn Layer<S> + Send + Sync>
+where
+    S: Subscriber + for<'span> LookupSpan<'span>,
+    W: for<'writer> MakeWriter<'writer> + Send + Sync + 'static,
+{
+    let layer = tracing_subscriber::fmt::layer().with_writer(writer).with_ansi(ansi);
+
+    match format {
+        "json" => layer.json().with_current_span(false).with_span_list(true).boxed(),
+        _ => layer
+            .with_target(true)
+            .with_thread_ids(true)
+            .with_file(true)
+            .with_line_number(true)
+            .boxed(),
+    }
+}
+
+impl Logging {
+    /// Apply log level changes from configuration reloads
+    pub fn watch_level<T, F>(&self, config: &ConfigHandle<T>, level: F)
+    where
+        T: ValidateConfig + Send + Sync + 'static,
+        F: Fn(&T) -> String + Send + 'static,
+    {
+        let filter = self.filter.clone();
+        config.on_change(level, move |level| {
+            match EnvFilter::try_new(level).map(|new_filter| filter.reload(new_filter)) {
+                Ok(Ok(())) => info!("Log level changed to {}", level),
+                Ok(Err(e)) => warn!("Failed to change log level: {}", e),
+                Err(e) => warn!("Ignoring invalid log level '{}': {}", level, e),
+            }
+        });
+    }
+
+    /// Flush queued log lines and spans before the process exits
+    pub fn shutdown(self) {
+        telemetry::shutdown();
+    }
+}
+
+/// Log file rotated when it would exceed a size or when the rotation period ends.
+///
+/// The active file keeps its configured name; rotated files get a timestamp suffix, e.g.
+/// `api.log.20241018T130000.000`, and only the newest `max_files` of them are kept.
+pub struct RotatingFile {
+    path: PathBuf,
+    max_size: Option<u64>,
+    max_files: Option<u32>,
+    rotation: LogRotation,
+    file: File,
+    size: u64,
+    next_rollover: Option<DateTime<Utc>>,
+}
+
+impl RotatingFile {
+    pub fn open(
+        path: impl Into<PathBuf>,
+        max_size: Option<u64>,
+        max_files: Option<u32>,
+        rotation: LogRotation,
+    ) -> io::Result<Self> {
+        let path = path.into();
+        fs::create_dir_all(directory(&path))?;
+        let file = open_append(&path)?;
+        let size = file.metadata()?.len();
+
+        Ok(Self {
+            path,
+            max_size,
+            max_files,
+            rotation,
+            file,
+            size,
+            next_rollover: next_rollover(rotation, Utc::now()),
+        })
+    }
+
+    pub fn from_config(config: &LoggingConfig) -> io::Result<Self> {
+        let path = config
+            .file_path
+            .as_deref()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "logging.file_path is not set"))?;
+        Self::open(path, config.max_file_size, config.max_files, config.rotation)
+    }
+
+    fn should_rotate(&self, incoming: usize, now: DateTime<Utc>) -> bool {
+        let too_large = self
+            .max_size
+            .is_some_and(|max| self.size > 0 && self.size + incoming as u64 > max);
+        let period_over = self.next_rollover.is_some_and(|at| now >= at);
+        too_large || period_over
+    }
+
+    fn rotate(&mut self, now: DateTime<Utc>) -> io::Result<()> {
+        self.file.flush()?;
+        fs::rename(&self.path, self.rotated_path(now))?;
+        self.file = open_append(&self.path)?;
+        self.size = 0;
+        self.next_rollover = next_rollover(self.rotation, now);
+        self.prune()
+    }
+
+    fn file_name(&self) -> String {
+        self.path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    }
+
+    /// Timestamped name for the current file, unique even for several rotations per millisecond
+    fn rotated_path(&self, now: DateTime<Utc>) -> PathBuf {
+        let base = format!("{}.{}", self.file_name(), now.format("%Y%m%dT%H%M%S%.3f"));
+        let mut path = self.path.with_file_name(&base);
+        let mut attempt = 1;
+        while path.exists() {
+            path = self.path.with_file_name(format!("{}-{}", base, attempt));
+            attempt += 1;
+        }
+        path
+    }
+
+    /// Delete the oldest rotated files beyond `max_files`
+    fn prune(&self) -> io::Result<()> {
+        let Some(max_files) = self.max_files else {
+            return Ok(());
+        };
+
+        let prefix = format!("{}.", self.file_name());
+        let mut rotated: Vec<PathBuf> = fs::read_dir(directory(&self.path))?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| {
+                path.file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| name.starts_with(&prefix))
+            })
+            .collect();
+        rotated.sort();
+
+        let excess = rotated.len().saturating_sub(max_files as usize);
+        for path in rotated.drain(..excess) {
+            fs::remove_file(path)?;
+        }
+        Ok(())
+    }
+}
+
+impl Write for RotatingFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let now = Utc::now();
+        if self.should_rotate(buf.len(), now) {
+            if let Err(e) = self.rotate(now) {
+                // Keep writing to the current file and try again after another period or
+                // `max_size` bytes, rather than on every line
+                eprintln!("Failed to rotate log file {}: {}", self.path.display(), e);
+                self.size = 0;
+                self.next_rollover = next_rollover(self.rotation, now);
+            }
+        }
+
+        let written = self.file.write(buf)?;
+        self.size += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+fn open_append(path: &Path) -> io::Result<File> {
+    OpenOptions::new().create(true).append(true).open(path)
+}
+
+fn directory(path: &Path) -> &Path {
+    path.parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+        .unwrap_or(Path::new("."))
+}
+
+/// Start of the next rotation period after `now`
+fn next_rollover(rotation: LogRotation, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    let period = match rotation {
+        LogRotation::Hourly => chrono::Duration::hours(1),
+        LogRotation::Daily => chrono::Duration::days(1),
+        LogRotation::Never => return None,
+    };
+    now.duration_trunc(period).ok().map(|start| start + period)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    fn log_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("shared-logging-test-{}", Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn file_count(dir: &Path) -> usize {
+        fs::read_dir(dir).unwrap().count()
+    }
+
+    #[test]
+    fn test_rotates_by_size_and_keeps_max_files() {
+        let dir = log_dir();
+        let path = dir.join("service.log");
+        let mut file = RotatingFile::open(&path, Some(16), Some(2), LogRotation::Never).unwrap();
+
+        for line in 0..5 {
+            file.write_all(format!("log line {:04}\n", line).as_bytes()).unwrap();
+        }
+
+        // Every line fills a file, so four were rotated and the two newest kept
+        assert_eq!(file_count(&dir), 3);
+        assert_eq!(fs::read_to_string(&path).unwrap(), "log line 0004\n");
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_rotates_when_period_ends() {
+        let dir = log_dir();
+        let path = dir.join("service.log");
+        let mut file = RotatingFile::open(&path, None, None, LogRotation::Daily).unwrap();
+
+        file.write_all(b"yesterday\n").unwrap();
+        file.next_rollover = Some(Utc::now() - chrono::Duration::seconds(1));
+        file.write_all(b"today\n").unwrap();
+
+        assert_eq!(file_count(&dir), 2);
+        assert_eq!(fs::read_to_string(&path).unwrap(), "today\n");
+        assert!(file.next_rollover.unwrap() > Utc::now());
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_next_rollover() {
+        let now = DateTime::parse_from_rfc3339("2024-10-18T13:45:10Z").unwrap().with_timezone(&Utc);
+        let hourly = next_rollover(LogRotation::Hourly, now).unwrap();
+        let daily = next_rollover(LogRotation::Daily, now).unwrap();
+
+        assert_eq!(hourly.to_rfc3339(), "2024-10-18T14:00:00+00:00");
+        assert_eq!(daily.to_rfc3339(), "2024-10-19T00:00:00+00:00");
+        assert!(next_rollover(LogRotation::Never, now).is_none());
+    }
+}
@@ -0,0 +1,208 @@
+//! Hot reloading of configuration
+
+use arc_swap::ArcSwap;
+use notify::{RecursiveMode, Watcher};
+use std::{fmt, path::Path, sync::Arc, time::Duration};
+use tokio::{
+    sync::{mpsc, watch},
+    task::JoinHandle,
+};
+
+use crate::{AppError, AppResult, ValidateConfig};
+
+/// File events arriving within this window are coalesced into a single reload
+const RELOAD_DEBOUNCE: Duration = Duration::from_millis(250);
+
+/// Running configuration, swapped atomically when a reload is accepted.
+///
+/// Readers take a cheap snapshot with [`load`](Self::load). Subsystems that build state from
+/// the configuration (filters, layers, worker pools) subscribe to changes instead.
+/// Clones share the same configuration.
+pub struct ConfigHandle<T> {
+    current: Arc<ArcSwap<T>>,
+    updates: Arc<watch::Sender<Arc<T>>>,
+}
+
+impl<T> Clone for ConfigHandle<T> {
+    fn clone(&self) -> Self {
+        Self {
+            current: self.current.clone(),
+            updates: self.updates.clone(),
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for ConfigHandle<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ConfigHandle")
+            .field("current", &self.current.load())
+            .finish()
+    }
+}
+
+impl<T> ConfigHandle<T>
+where
+    T: ValidateConfig + Send + Sync + 'static,
+{
+    pub fn new(config: T) -> Self {
+        let config = Arc::new(config);
+        let (updates, _) = watch::channel(config.clone());
+        Self {
+            current: Arc::new(ArcSwap::new(config)),
+            updates: Arc::new(updates),
+        }
+    }
+
+    /// Snapshot of the running configuration
+    pub fn load(&self) -> Arc<T> {
+        self.current.load_full()
+    }
+
+    /// Receive every accepted configuration
+    pub fn subscribe(&self) -> watch::Receiver<Arc<T>> {
+        self.updates.subscribe()
+    }
+
+    /// Validate and swap in a new configuration; the running one is kept if validation fails
+    pub fn update(&self, config: T) -> Result<(), String> {
+        config.validate()?;
+
+        let config = Arc::new(config);
+        self.current.store(config.clone());
+        self.updates.send_replace(config);
+        Ok(())
+    }
+
+    /// Call `apply` whenever the part of the configuration picked by `select` changes
+    pub fn on_change<U, S, F>(&self, select: S, mut apply: F) -> JoinHandle<()>
+    where
+        U: PartialEq + Send + 'static,
+        S: Fn(&T) -> U + Send + 'static,
+        F: FnMut(&U) + Send + 'static,
+    {
+        let mut updates = self.subscribe();
+        let mut last = select(&updates.borrow_and_update());
+
+        tokio::spawn(async move {
+            while updates.changed().await.is_ok() {
+                let next = select(&updates.borrow_and_update());
+                if next != last {
+                    apply(&next);
+                    last = next;
+                }
+            }
+        })
+    }
+}
+
+/// Reload configuration when a file in `dir` changes or the process receives SIGHUP.
+///
+/// `load` re-reads every configuration source. A configuration that fails to load or validate
+/// is logged and discarded, leaving the running one in place.
+pub fn watch_config<T, F>(handle: ConfigHandle<T>, dir: impl AsRef<Path>, load: F) -> AppResult<JoinHandle<()>>
+where
+    T: ValidateConfig + Send + Sync + 'static,
+    F: Fn() -> Result<T, String> + Send + 'static,
+{
+    // A single pending trigger is enough, further events fold into it
+    let (trigger, mut triggers) = mpsc::channel::<()>(1);
+
+    let file_trigger = trigger.clone();
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| match event {
+        Ok(event) if !event.kind.is_access() => {
+            let _ = file_trigger.try_send(());
+        }
+        Ok(_) => {}
+        Err(e) => tracing::warn!("Configuration watcher error: {}", e),
+    })
+    .map_err(|e| AppError::Configuration(format!("Failed to create configuration watcher: {}", e)))?;
+
+    // Editors often replace files rather than writing them, so watch the directory
+    let dir = dir.as_ref();
+    match watcher.watch(dir, RecursiveMode::NonRecursive) {
+        Ok(()) => tracing::info!("Watching {} for configuration changes", dir.display()),
+        Err(e) => tracing::warn!("Not watching {} for configuration changes: {}", dir.display(), e),
+    }
+
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        let mut hangup = signal(SignalKind::hangup())
+            .map_err(|e| AppError::Configuration(format!("Failed to install SIGHUP handler: {}", e)))?;
+        tokio::spawn(async move {
+            while hangup.recv().await.is_some() {
+                tracing::info!("Received SIGHUP, reloading configuration");
+                let _ = trigger.try_send(());
+            }
+        });
+    }
+
+    Ok(tokio::spawn(async move {
+        // Dropping the watcher stops file notifications
+        let _watcher = watcher;
+
+        while triggers.recv().await.is_some() {
+            tokio::time::sleep(RELOAD_DEBOUNCE).await;
+            while triggers.try_recv().is_ok() {}
+
+            match load().and_then(|config| handle.update(config)) {
+                Ok(()) => tracing::info!("Configuration reloaded"),
+                Err(e) => tracing::warn!("Rejected configuration reload, keeping the running configuration: {}", e),
+            }
+        }
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug)]
+    struct TestConfig {
+        level: String,
+        workers: usize,
+    }
+
+    impl ValidateConfig for TestConfig {
+        fn validate(&self) -> Result<(), String> {
+            if self.workers == 0 {
+                return Err("Workers cannot be zero".to_string());
+            }
+            Ok(())
+        }
+    }
+
+    fn config(level: &str, workers: usize) -> TestConfig {
+        TestConfig {
+            level: level.to_string(),
+            workers,
+        }
+    }
+
+    #[test]
+    fn test_invalid_update_keeps_running_config() {
+        let handle = ConfigHandle::new(config("info", 2));
+
+        assert!(handle.update(config("debug", 0)).is_err());
+        assert_eq!(handle.load().level, "info");
+
+        handle.update(config("debug", 4)).unwrap();
+        assert_eq!(handle.clone().load().level, "debug");
+    }
+
+    #[tokio::test]
+    async fn test_on_change_only_fires_for_selected_changes() {
+        let handle = ConfigHandle::new(config("info", 2));
+        let (levels, mut received) = mpsc::unbounded_channel();
+        handle.on_change(|config| config.level.clone(), move |level| {
+            let _ = levels.send(level.clone());
+        });
+
+        handle.update(config("info", 4)).unwrap();
+        handle.update(config("debug", 4)).unwrap();
+
+        assert_eq!(received.recv().await.as_deref(), Some("debug"));
+        assert!(received.try_recv().is_err());
+    }
+}
@@ -0,0 +1,183 @@
+//! Standard API response envelopes and the HTTP rendering of [`AppError`]
+
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+
+use crate::{context, errors::AppError};
+
+/// API response metadata
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ApiMetadata {
+    pub version: String,
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+    pub request_id: uuid::Uuid,
+    /// Correlation id of the request, echoed from `X-Correlation-ID` when provided
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub correlation_id: Option<uuid::Uuid>,
+    pub service: String,
+}
+
+impl ApiMetadata {
+    pub fn new(version: String, service: String) -> Self {
+        Self {
+            version,
+            timestamp: chrono::Utc::now(),
+            // Matches the `X-Request-ID` response header when rendered inside a request
+            request_id: context::request_id().unwrap_or_else(uuid::Uuid::new_v4),
+            correlation_id: context::correlation_id(),
+            service,
+        }
+    }
+}
+
+/// API error response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ApiErrorResponse {
+    /// Stable machine-readable error code, e.g. `VALIDATION_ERROR`
+    pub error: String,
+    pub message: String,
+    pub code: u16,
+    #[cfg_attr(feature = "openapi", schema(value_type = Option<Object>))]
+    pub details: Option<serde_json::Value>,
+    pub metadata: ApiMetadata,
+}
+
+impl ApiErrorResponse {
+    pub fn new(
+        error: String,
+        message: String,
+        code: u16,
+        details: Option<serde_json::Value>,
+        metadata: ApiMetadata,
+    ) -> Self {
+        Self {
+            error,
+            message,
+            code,
+            details,
+            metadata,
+        }
+    }
+
+    /// Build the client-facing response for an error.
+    ///
+    /// Messages of server errors are replaced by a generic one unless
+    /// `expose_internal_errors` is set, so internals never leak in production.
+    pub fn from_error(error: &AppError, metadata: ApiMetadata, expose_internal_errors: bool) -> Self {
+        let code = error.status_code();
+
+        let message = if code >= 500 && !expose_internal_errors {
+            "An internal error occurred".to_string()
+        } else {
+            error.to_string()
+        };
+
+        let details = match error {
+            AppError::FieldValidation(errors) => serde_json::to_value(&errors.errors).ok(),
+            _ => None,
+        };
+
+        Self::new(error.error_code().to_string(), message, code, details, metadata)
+    }
+}
+
+/// Settings used when rendering errors as HTTP responses
+#[derive(Debug, Clone)]
+pub struct ErrorResponseSettings {
+    pub service: String,
+    pub version: String,
+    /// Include messages of 5xx errors in responses; disable in production
+    pub expose_internal_errors: bool,
+}
+
+impl Default for ErrorResponseSettings {
+    fn default() -> Self {
+        Self {
+            service: "unknown".to_string(),
+            version: "v1".to_string(),
+            expose_internal_errors: false,
+        }
+    }
+}
+
+static ERROR_RESPONSE_SETTINGS: OnceLock<ErrorResponseSettings> = OnceLock::new();
+
+/// Configure how errors are rendered; only the first call takes effect
+pub fn configure_error_responses(settings: ErrorResponseSettings) {
+    if ERROR_RESPONSE_SETTINGS.set(settings).is_err() {
+        tracing::warn!("Error response settings already configured");
+    }
+}
+
+/// Settings currently used to render errors
+pub fn error_response_settings() -> &'static ErrorResponseSettings {
+    ERROR_RESPONSE_SETTINGS.get_or_init(ErrorResponseSettings::default)
+}
+
+#[cfg(feature = "web")]
+impl axum::response::IntoResponse for AppError {
+    fn into_response(self) -> axum::response::Response {
+        if self.should_log_error() {
+            tracing::error!(error_code = self.error_code(), "Request failed: {}", self);
+        } else {
+            tracing::debug!(error_code = self.error_code(), "Request failed: {}", self);
+        }
+
+        let settings = error_response_settings();
+        let metadata = ApiMetadata::new(settings.version.clone(), settings.service.clone());
+        let body = ApiErrorResponse::from_error(&self, metadata, settings.expose_internal_errors);
+        let status = axum::http::StatusCode::from_u16(body.code)
+            .unwrap_or(axum::http::StatusCode::INTERNAL_SERVER_ERROR);
+
+        (status, axum::Json(body)).into_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::{ValidationError, ValidationErrors};
+
+    fn metadata() -> ApiMetadata {
+        ApiMetadata::new("v1".to_string(), "test".to_string())
+    }
+
+    #[test]
+    fn test_field_validation_details() {
+        let mut errors = ValidationErrors::new();
+        errors.add(ValidationError::new("email", "Invalid email"));
+
+        let response = ApiErrorResponse::from_error(&errors.into(), metadata(), false);
+        assert_eq!(response.error, "VALIDATION_ERROR");
+        assert_eq!(response.code, 400);
+        assert_eq!(response.details.unwrap()[0]["field"], "email");
+    }
+
+    #[test]
+    fn test_internal_errors_are_hidden() {
+        let error = AppError::Internal("connection string leaked".to_string());
+
+        let hidden = ApiErrorResponse::from_error(&error, metadata(), false);
+        assert_eq!(hidden.error, "INTERNAL_ERROR");
+        assert!(!hidden.message.contains("leaked"));
+
+        let exposed = ApiErrorResponse::from_error(&error, metadata(), true);
+        assert!(exposed.message.contains("leaked"));
+    }
+
+    #[tokio::test]
+    async fn test_metadata_carries_correlation_id() {
+        let correlation_id = uuid::Uuid::new_v4();
+        let metadata = context::with_correlation_id(correlation_id, async { metadata() }).await;
+        assert_eq!(metadata.correlation_id, Some(correlation_id));
+    }
+
+    #[tokio::test]
+    async fn test_metadata_uses_request_id_in_scope() {
+        let request_id = uuid::Uuid::new_v4();
+        let metadata = context::with_request_id(request_id, async { metadata() }).await;
+        assert_eq!(metadata.request_id, request_id);
+    }
+}
@@ -0,0 +1,322 @@
+//! Service lifecycle: cancellation, supervised tasks and phased shutdown
+//!
+//! A service owns a [`Supervisor`] whose cancellation token is the root of a token tree.
+//! Long-running tasks are spawned through the supervisor and stop taking new work when their
+//! token is cancelled. On a shutdown signal the service runs a [`Shutdown`] sequence that stops
+//! intake, drains in-flight work, flushes producers and closes pools, within a deadline.
+
+use futures::future::{join_all, BoxFuture};
+use std::{
+    fmt,
+    future::Future,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tokio::{task::JoinHandle, time::Instant};
+use tracing::{error, info, warn};
+
+pub use tokio_util::sync::CancellationToken;
+
+use crate::AppResult;
+
+/// Upper bound for the delay between restarts of a failing task
+const MAX_RESTART_BACKOFF: Duration = Duration::from_secs(60);
+
+/// What the supervisor does when a task returns an error or panics
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RestartPolicy {
+    /// Report the failure and leave the task stopped
+    Never,
+    /// Restart after `backoff`, doubling on each consecutive failure, up to `max_restarts` times
+    OnFailure { max_restarts: u32, backoff: Duration },
+}
+
+/// Runs long-lived tasks under a shared cancellation token, restarting or reporting those
+/// that crash.
+pub struct Supervisor {
+    token: CancellationToken,
+    tasks: Vec<(&'static str, JoinHandle<()>)>,
+    failed: Arc<Mutex<Vec<&'static str>>>,
+}
+
+impl Default for Supervisor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Supervisor {
+    pub fn new() -> Self {
+        Self {
+            token: CancellationToken::new(),
+            tasks: Vec::new(),
+            failed: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    /// Root token; cancelling it asks every supervised task to stop
+    pub fn token(&self) -> &CancellationToken {
+        &self.token
+    }
+
+    /// Spawn a task built by `task`, which is called again for each restart.
+    ///
+    /// The task receives a child of the supervisor's token and should return once it is
+    /// cancelled, after finishing the work in hand.
+    pub fn spawn<F, Fut>(&mut self, name: &'static str, policy: RestartPolicy, task: F)
+    where
+        F: Fn(CancellationToken) -> Fut + Send + 'static,
+        Fut: Future<Output = AppResult<()>> + Send + 'static,
+    {
+        let token = self.token.child_token();
+        let failed = self.failed.clone();
+
+        let handle = tokio::spawn(async move {
+            let mut restarts = 0;
+            loop {
+                let failure = match tokio::spawn(task(token.clone())).await {
+                    Ok(Ok(())) => return,
+                    Ok(Err(e)) => e.to_string(),
+                    Err(e) if e.is_panic() => "task panicked".to_string(),
+                    Err(e) => e.to_string(),
+                };
+
+                if token.is_cancelled() {
+                    warn!(task = name, "Task failed while stopping: {}", failure);
+                    return;
+                }
+
+                let backoff = match policy {
+                    RestartPolicy::OnFailure { max_restarts, backoff } if restarts < max_restarts => {
+                        backoff.saturating_mul(2u32.saturating_pow(restarts)).min(MAX_RESTART_BACKOFF)
+                    }
+                    _ => {
+                        error!(task = name, restarts, "Task failed and will not be restarted: {}", failure);
+                        failed.lock().unwrap_or_else(|e| e.into_inner()).push(name);
+                        return;
+                    }
+                };
+
+                restarts += 1;
+                warn!(task = name, restarts, "Task failed, restarting in {:?}: {}", backoff, failure);
+                tokio::select! {
+                    _ = token.cancelled() => return,
+                    _ = tokio::time::sleep(backoff) => {}
+                }
+            }
+        });
+
+        self.tasks.push((name, handle));
+    }
+
+    /// Tasks that crashed and were not restarted
+    pub fn failed_tasks(&self) -> Vec<&'static str> {
+        self.failed.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Cancel every task and wait for them to stop
+    pub async fn shutdown(self) {
+        self.token.cancel();
+        for (name, handle) in self.tasks {
+            if let Err(e) = handle.await {
+                warn!(task = name, "Supervised task ended abnormally: {}", e);
+            }
+        }
+    }
+}
+
+/// Shutdown phases, run in this order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ShutdownPhase {
+    /// Stop accepting requests, messages and jobs
+    StopIntake,
+    /// Wait for work already accepted to finish
+    Drain,
+    /// Deliver buffered messages, spans and log lines
+    FlushProducers,
+    /// Close database and cache connections
+    ClosePools,
+}
+
+impl fmt::Display for ShutdownPhase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ShutdownPhase::StopIntake => "stop intake",
+            ShutdownPhase::Drain => "drain",
+            ShutdownPhase::FlushProducers => "flush producers",
+            ShutdownPhase::ClosePools => "close pools",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Ordered shutdown steps with a deadline for the whole sequence.
+///
+/// Steps of the same phase run concurrently; a phase starts once the previous one finished.
+/// Steps still running when the deadline passes are abandoned.
+pub struct Shutdown {
+    deadline: Duration,
+    steps: Vec<(ShutdownPhase, &'static str, BoxFuture<'static, ()>)>,
+}
+
+impl Shutdown {
+    pub fn new(deadline: Duration) -> Self {
+        Self {
+            deadline,
+            steps: Vec::new(),
+        }
+    }
+
+    /// Add a step to run during `phase`
+    pub fn step<F>(mut self, phase: ShutdownPhase, name: &'static str, step: F) -> Self
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        self.steps.push((phase, name, Box::pin(step)));
+        self
+    }
+
+    /// Run every phase, returning `false` if the deadline passed first
+    pub async fn run(mut self) -> bool {
+        let deadline = Instant::now() + self.deadline;
+        self.steps.sort_by_key(|(phase, _, _)| *phase);
+
+        let mut steps = self.steps.into_iter().peekable();
+        while let Some((phase, _, _)) = steps.peek() {
+            let phase = *phase;
+            let mut names = Vec::new();
+            let mut futures = Vec::new();
+            while let Some((_, name, step)) = steps.next_if(|(next, _, _)| *next == phase) {
+                names.push(name);
+                futures.push(step);
+            }
+
+            info!("Shutdown phase: {} ({})", phase, names.join(", "));
+            if tokio::time::timeout_at(deadline, join_all(futures)).await.is_err() {
+                warn!(
+                    "Shutdown deadline of {}s passed during {}, abandoning remaining steps",
+                    self.deadline.as_secs(),
+                    phase
+                );
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+/// Resolves when the process is asked to stop with Ctrl+C or SIGTERM
+pub async fn shutdown_signal() {
+    use tokio::signal;
+
+    let ctrl_c = async {
+        signal::ctrl_c()
+            .await
+            .expect("Failed to install Ctrl+C handler");
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        signal::unix::signal(signal::unix::SignalKind::terminate())
+            .expect("Failed to install signal handler")
+            .recv()
+            .await;
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {
+            info!("Received Ctrl+C signal");
+        },
+        _ = terminate => {
+            info!("Received terminate signal");
+        },
+    }
+
+    info!("Starting graceful shutdown");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::AppError;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    #[tokio::test]
+    async fn test_supervisor_restarts_failed_task() {
+        let mut supervisor = Supervisor::new();
+        let runs = Arc::new(AtomicU32::new(0));
+
+        let counter = runs.clone();
+        supervisor.spawn(
+            "flaky",
+            RestartPolicy::OnFailure { max_restarts: 2, backoff: Duration::from_millis(1) },
+            move |_| {
+                let counter = counter.clone();
+                async move {
+                    counter.fetch_add(1, Ordering::SeqCst);
+                    Err(AppError::Internal("boom".to_string()))
+                }
+            },
+        );
+        supervisor.spawn("panics", RestartPolicy::Never, |_| async { panic!("boom") });
+        supervisor.spawn("worker", RestartPolicy::Never, |token| async move {
+            token.cancelled().await;
+            Ok(())
+        });
+
+        // Wait for both to give up rather than sleeping, as capturing a panic backtrace can be slow
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while supervisor.failed_tasks().len() < 2 {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .unwrap();
+        assert_eq!(runs.load(Ordering::SeqCst), 3);
+
+        let mut failed = supervisor.failed_tasks();
+        failed.sort();
+        assert_eq!(failed, vec!["flaky", "panics"]);
+
+        // The worker stops when cancelled, so shutdown completes
+        supervisor.shutdown().await;
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_runs_phases_in_order() {
+        let order = Arc::new(Mutex::new(Vec::new()));
+        let record = |name: &'static str| {
+            let order = order.clone();
+            async move { order.lock().unwrap().push(name) }
+        };
+
+        let completed = Shutdown::new(Duration::from_secs(1))
+            .step(ShutdownPhase::ClosePools, "database", record("database"))
+            .step(ShutdownPhase::StopIntake, "consumer", record("consumer"))
+            .step(ShutdownPhase::Drain, "jobs", record("jobs"))
+            .run()
+            .await;
+
+        assert!(completed);
+        assert_eq!(*order.lock().unwrap(), vec!["consumer", "jobs", "database"]);
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_deadline() {
+        let closed = Arc::new(Mutex::new(false));
+        let flag = closed.clone();
+
+        let completed = Shutdown::new(Duration::from_millis(50))
+            .step(ShutdownPhase::Drain, "stuck", std::future::pending())
+            .step(ShutdownPhase::ClosePools, "database", async move { *flag.lock().unwrap() = true })
+            .run()
+            .await;
+
+        assert!(!completed);
+        assert!(!*closed.lock().unwrap());
+    }
+}
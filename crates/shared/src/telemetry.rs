@@ -0,0 +1,202 @@
+//! Trace export and W3C trace context propagation
+//!
+//! Spans recorded through `tracing` are exported over OTLP/HTTP when a collector is configured.
+//! The `traceparent` and `tracestate` values of the current span travel with HTTP requests,
+//! Kafka messages and job rows, so a single trace follows work across services.
+
+use opentelemetry::{
+    propagation::TextMapPropagator,
+    trace::{TraceContextExt, TracerProvider as _},
+    KeyValue,
+};
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::{
+    propagation::TraceContextPropagator,
+    runtime,
+    trace::{self, Tracer, TracerProvider},
+    Resource,
+};
+use std::collections::HashMap;
+use tracing::{Span, Subscriber};
+use tracing_opentelemetry::{OpenTelemetryLayer, OpenTelemetrySpanExt};
+use tracing_subscriber::registry::LookupSpan;
+
+use crate::{AppConfig, AppError, AppResult, EventMetadata};
+
+pub const TRACEPARENT_HEADER: &str = "traceparent";
+pub const TRACESTATE_HEADER: &str = "tracestate";
+
+/// Headers making up the W3C trace context
+pub const TRACE_CONTEXT_HEADERS: [&str; 2] = [TRACEPARENT_HEADER, TRACESTATE_HEADER];
+
+/// Trace context carried between services, keyed by header name
+pub type TraceContext = HashMap<String, String>;
+
+/// Build the tracer provider for a service.
+///
+/// Without a collector spans are still given trace ids, so context received from upstream
+/// services is passed on even when this service does not export anything.
+pub fn tracer_provider(config: &AppConfig) -> AppResult<TracerProvider> {
+    let Some(endpoint) = config.logging.trace_endpoint() else {
+        return Ok(TracerProvider::builder().build());
+    };
+
+    let exporter = opentelemetry_otlp::new_exporter()
+        .http()
+        .with_endpoint(endpoint.trim_end_matches('/'))
+        .build_span_exporter()
+        .map_err(|e| AppError::Configuration(format!("Failed to create trace exporter: {}", e)))?;
+
+    Ok(TracerProvider::builder()
+        .with_config(trace::config().with_resource(resource(config)))
+        .with_batch_exporter(exporter, runtime::Tokio)
+        .build())
+}
+
+/// Resource attributes identifying the service in the trace backend
+fn resource(config: &AppConfig) -> Resource {
+    let mut attributes = vec![
+        KeyValue::new("service.name", config.service_name.clone()),
+        KeyValue::new("service.version", config.version.clone()),
+        KeyValue::new("deployment.environment", config.environment.clone()),
+    ];
+    attributes.extend(
+        config
+            .logging
+            .resource_attributes
+            .iter()
+            .map(|(key, value)| KeyValue::new(key.clone(), value.clone())),
+    );
+
+    Resource::default().merge(&Resource::new(attributes))
+}
+
+/// `tracing` layer turning spans into OpenTelemetry spans.
+///
+/// Installs the provider and the W3C propagator globally; call [`shutdown`] before exiting
+/// to flush spans still buffered for export.
+pub fn tracing_layer<S>(config: &AppConfig) -> AppResult<OpenTelemetryLayer<S, Tracer>>
+where
+    S: Subscriber + for<'span> LookupSpan<'span>,
+{
+    let provider = tracer_provider(config)?;
+    let tracer = provider.tracer(config.service_name.clone());
+
+    opentelemetry::global::set_text_map_propagator(TraceContextPropagator::new());
+    opentelemetry::global::set_tracer_provider(provider);
+
+    Ok(tracing_opentelemetry::layer().with_tracer(tracer))
+}
+
+/// Flush and stop trace export
+pub fn shutdown() {
+    opentelemetry::global::shutdown_tracer_provider();
+}
+
+/// Trace context of the current span, empty when there is none
+pub fn current_trace_context() -> TraceContext {
+    let mut context = TraceContext::new();
+    TraceContextPropagator::new().inject_context(&Span::current().context(), &mut context);
+    context
+}
+
+/// Kafka headers for an event: its metadata plus the trace context of the current span
+pub fn event_headers(metadata: &EventMetadata) -> Vec<(String, String)> {
+    metadata
+        .headers()
+        .into_iter()
+        .map(|(key, value)| (key.to_string(), value))
+        .chain(current_trace_context())
+        .collect()
+}
+
+/// `traceparent` of the current span, for storing alongside deferred work
+pub fn current_traceparent() -> Option<String> {
+    current_trace_context().remove(TRACEPARENT_HEADER)
+}
+
+/// Trace context holding only a stored `traceparent`
+pub fn trace_context_from_traceparent(traceparent: &str) -> TraceContext {
+    TraceContext::from([(TRACEPARENT_HEADER.to_string(), traceparent.to_string())])
+}
+
+/// Add the current trace context to an outgoing HTTP request
+#[cfg(feature = "http")]
+pub fn inject_trace_context(mut request: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+    for (name, value) in current_trace_context() {
+        request = request.header(name, value);
+    }
+    request
+}
+
+/// Make `span` a child of the remote span described by `context`.
+///
+/// Missing or malformed context is ignored and the span starts a new trace.
+pub fn set_remote_parent(span: &Span, context: &TraceContext) {
+    let parent = TraceContextPropagator::new().extract(context);
+    if parent.span().span_context().is_valid() {
+        span.set_parent(parent);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use opentelemetry::trace::Tracer as _;
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+    };
+    use tracing_subscriber::layer::SubscriberExt;
+
+    const TRACE_ID: &str = "4bf92f3577b34da6a3ce929d0e0e4736";
+    const PARENT: &str = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+
+    #[test]
+    fn test_trace_context_round_trip() {
+        // Tracers stop recording once their provider is dropped
+        let provider = TracerProvider::builder().build();
+        let subscriber = tracing_subscriber::registry().with(tracing_opentelemetry::layer().with_tracer(provider.tracer("test")));
+
+        tracing::subscriber::with_default(subscriber, || {
+            let span = tracing::info_span!("request");
+            set_remote_parent(&span, &trace_context_from_traceparent(PARENT));
+
+            let traceparent = span.in_scope(current_traceparent).unwrap();
+            assert!(traceparent.starts_with(&format!("00-{}-", TRACE_ID)));
+            assert!(!traceparent.contains("00f067aa0ba902b7"));
+
+            let span = tracing::info_span!("unrelated");
+            set_remote_parent(&span, &trace_context_from_traceparent("garbage"));
+            let traceparent = span.in_scope(current_traceparent).unwrap();
+            assert!(!traceparent.contains(TRACE_ID));
+        });
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_spans_exported_to_collector() {
+        // Stand-in for an OTLP/HTTP collector that accepts a single export request
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let mut config = AppConfig::default();
+        config.logging.otlp_endpoint = Some(format!("http://{}", listener.local_addr().unwrap()));
+
+        let collector = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buffer = vec![0; 64 * 1024];
+            let read = socket.read(&mut buffer).await.unwrap();
+            socket
+                .write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 0\r\n\r\n")
+                .await
+                .unwrap();
+            String::from_utf8_lossy(&buffer[..read]).into_owned()
+        });
+
+        let provider = tracer_provider(&config).unwrap();
+        provider.tracer("test").in_span("exported", |_| {});
+        tokio::task::spawn_blocking(move || provider.force_flush()).await.unwrap();
+
+        let request = collector.await.unwrap();
+        assert!(request.starts_with("POST /v1/traces"));
+        assert!(request.contains("application/x-protobuf"));
+    }
+}
@@ -5,7 +5,7 @@ use serde::{Deserialize, Serialize};
 use std::fmt::Debug;
 use uuid::Uuid;
 
-use crate::{AppResult, CorrelationId, PaginationParams, PaginatedResponse};
+use crate::{AppResult, CorrelationId, PaginationParams, PaginatedResponse, TenantId};
 
 /// Repository trait for data access layer
 #[async_trait]
@@ -141,6 +141,11 @@ pub trait HealthCheck {
     fn timeout(&self) -> u64 {
         5
     }
+
+    /// Whether a failure makes the service unhealthy rather than degraded
+    fn critical(&self) -> bool {
+        true
+    }
 }
 
 /// Metrics collector trait
@@ -286,12 +291,17 @@ pub trait AuditLogger {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AuditEvent {
     pub event_id: Uuid,
+    pub tenant_id: Option<TenantId>,
     pub user_id: Option<Uuid>,
     pub action: String,
     pub resource: String,
+    pub resource_id: Option<Uuid>,
+    /// Changed fields before the change, with sensitive values redacted
+    pub old_values: Option<serde_json::Value>,
+    /// Changed fields after the change, with sensitive values redacted
+    pub new_values: Option<serde_json::Value>,
     pub timestamp: chrono::DateTime<chrono::Utc>,
     pub ip_address: Option<String>,
     pub user_agent: Option<String>,
     pub correlation_id: CorrelationId,
-    pub metadata: serde_json::Value,
 }
\ No newline at end of file
@@ -16,8 +16,9 @@ pub type CorrelationId = Uuid;
 
 /// Common pagination parameters
 #[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+#[cfg_attr(feature = "openapi", derive(utoipa::IntoParams), into_params(parameter_in = Query))]
 pub struct PaginationParams {
-    #[validate(range(min = 1, max = 1000))]
+    #[validate(range(min = 1, max = "crate::MAX_PAGE_SIZE"))]
     pub limit: Option<u32>,
     
     #[validate(range(min = 0))]
@@ -38,6 +39,7 @@ impl Default for PaginationParams {
 
 /// Paginated response wrapper
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct PaginatedResponse<T> {
     pub data: Vec<T>,
     pub pagination: PaginationInfo,
@@ -45,6 +47,7 @@ pub struct PaginatedResponse<T> {
 
 /// Pagination metadata
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct PaginationInfo {
     pub total: Option<u64>,
     pub limit: u32,
@@ -89,6 +92,7 @@ impl<T> ApiResponse<T> {
 
 /// Health check status
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct HealthStatus {
     pub service: String,
     pub version: String,
@@ -98,7 +102,8 @@ pub struct HealthStatus {
 }
 
 /// Service status enum
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 #[serde(rename_all = "lowercase")]
 pub enum ServiceStatus {
     Healthy,
@@ -108,11 +113,15 @@ pub enum ServiceStatus {
 
 /// Dependency health check
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct DependencyHealth {
     pub name: String,
     pub status: ServiceStatus,
     pub response_time_ms: Option<u64>,
     pub error: Option<String>,
+    /// Whether a failure makes the service unhealthy rather than degraded
+    #[serde(default)]
+    pub critical: bool,
 }
 
 /// Event metadata for Kafka messages
@@ -146,6 +155,11 @@ impl EventMetadata {
         }
     }
 
+    /// Metadata carrying the correlation id of the request or job in scope
+    pub fn current(event_type: impl Into<String>, source_service: impl Into<String>) -> Self {
+        Self::new(event_type, source_service, crate::context::current_correlation_id())
+    }
+
     pub fn with_tenant(mut self, tenant_id: TenantId) -> Self {
         self.tenant_id = Some(tenant_id);
         self
@@ -155,6 +169,17 @@ impl EventMetadata {
         self.user_id = Some(user_id);
         self
     }
+
+    /// Kafka headers carrying the metadata, so consumers can restore the correlation id
+    /// without decoding the payload
+    pub fn headers(&self) -> Vec<(&'static str, String)> {
+        vec![
+            (crate::kafka_headers::CORRELATION_ID, self.correlation_id.to_string()),
+            (crate::kafka_headers::EVENT_ID, self.event_id.to_string()),
+            (crate::kafka_headers::EVENT_TYPE, self.event_type.clone()),
+            (crate::kafka_headers::SOURCE_SERVICE, self.source_service.clone()),
+        ]
+    }
 }
 
 /// Generic event wrapper
@@ -176,6 +201,14 @@ impl<T> Event<T> {
             payload,
         }
     }
+
+    /// Wrap a payload using the correlation id of the request or job in scope
+    pub fn current(event_type: impl Into<String>, source_service: impl Into<String>, payload: T) -> Self {
+        Self {
+            metadata: EventMetadata::current(event_type, source_service),
+            payload,
+        }
+    }
 }
 
 /// Job status for background processing
@@ -0,0 +1,62 @@
+//! Storage backend abstraction
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use shared::{AppError, AppResult, StorageBackend, StorageConfig};
+use std::{fmt, sync::Arc};
+
+use crate::{local::LocalStorage, s3::S3Storage};
+
+/// A flat namespace of objects addressed by `/`-separated keys.
+///
+/// Keys are chosen by the application, not by clients; backends still refuse keys that
+/// could escape their namespace.
+#[async_trait]
+pub trait Storage: Send + Sync + fmt::Debug {
+    /// Store an object, replacing any object with the same key
+    async fn put(&self, key: &str, data: Bytes, content_type: &str) -> AppResult<()>;
+
+    /// Read an object, or `None` if there is none with this key
+    async fn get(&self, key: &str) -> AppResult<Option<Bytes>>;
+
+    /// Remove an object; removing a missing object succeeds
+    async fn delete(&self, key: &str) -> AppResult<()>;
+
+    /// Backend name for logs
+    fn name(&self) -> &'static str;
+}
+
+/// Build the backend selected by `config.backend`
+pub fn from_config(config: &StorageConfig) -> AppResult<Arc<dyn Storage>> {
+    let storage: Arc<dyn Storage> = match config.backend {
+        StorageBackend::Local => Arc::new(LocalStorage::new(&config.local_path)),
+        StorageBackend::S3 => Arc::new(S3Storage::new(&config.s3)?),
+    };
+    Ok(storage)
+}
+
+/// Check that a key is a relative path of plain segments
+pub(crate) fn validate_key(key: &str) -> AppResult<()> {
+    let valid = !key.is_empty()
+        && key
+            .split('/')
+            .all(|segment| !segment.is_empty() && segment != "." && segment != ".." && !segment.contains('\\'));
+    if valid {
+        Ok(())
+    } else {
+        Err(AppError::Internal(format!("Invalid storage key '{}'", key)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_key() {
+        assert!(validate_key("tenant/file").is_ok());
+        for key in ["", "/etc/passwd", "a/../b", "a//b", "a/", "..\\b"] {
+            assert!(validate_key(key).is_err(), "{} should be refused", key);
+        }
+    }
+}
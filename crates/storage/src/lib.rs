@@ -0,0 +1,14 @@
+//! Object storage for uploaded files, with local filesystem and S3-compatible backends
+
+pub mod backend;
+pub mod local;
+pub mod s3;
+pub mod signing;
+pub mod sniff;
+
+// Re-export commonly used items
+pub use backend::*;
+pub use local::*;
+pub use s3::*;
+pub use signing::*;
+pub use sniff::*;
@@ -0,0 +1,92 @@
+//! Local filesystem storage backend
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use shared::AppResult;
+use std::{
+    io::ErrorKind,
+    path::{Path, PathBuf},
+};
+use tokio::fs;
+use uuid::Uuid;
+
+use crate::backend::{validate_key, Storage};
+
+/// Stores each object as a file under a root directory, at the path given by its key
+#[derive(Debug, Clone)]
+pub struct LocalStorage {
+    root: PathBuf,
+}
+
+impl LocalStorage {
+    pub fn new(root: impl AsRef<Path>) -> Self {
+        Self {
+            root: root.as_ref().to_path_buf(),
+        }
+    }
+
+    fn path(&self, key: &str) -> AppResult<PathBuf> {
+        validate_key(key)?;
+        Ok(self.root.join(key))
+    }
+}
+
+#[async_trait]
+impl Storage for LocalStorage {
+    async fn put(&self, key: &str, data: Bytes, _content_type: &str) -> AppResult<()> {
+        let path = self.path(key)?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).await?;
+        }
+
+        // Write beside the target and rename, so readers never see a partial file
+        let partial = path.with_extension(format!("{}.partial", Uuid::new_v4()));
+        if let Err(e) = fs::write(&partial, &data).await {
+            let _ = fs::remove_file(&partial).await;
+            return Err(e.into());
+        }
+        fs::rename(&partial, &path).await?;
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> AppResult<Option<Bytes>> {
+        match fs::read(self.path(key)?).await {
+            Ok(data) => Ok(Some(Bytes::from(data))),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn delete(&self, key: &str) -> AppResult<()> {
+        match fs::remove_file(self.path(key)?).await {
+            Err(e) if e.kind() != ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        "local"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_put_get_delete() {
+        let root = std::env::temp_dir().join(format!("storage-local-test-{}", Uuid::new_v4()));
+        let storage = LocalStorage::new(&root);
+
+        storage.put("tenant/file", Bytes::from_static(b"hello"), "text/plain").await.unwrap();
+        assert_eq!(storage.get("tenant/file").await.unwrap().unwrap(), "hello");
+        assert_eq!(std::fs::read_dir(root.join("tenant")).unwrap().count(), 1);
+
+        storage.delete("tenant/file").await.unwrap();
+        assert!(storage.get("tenant/file").await.unwrap().is_none());
+        storage.delete("tenant/file").await.unwrap();
+
+        assert!(storage.get("../outside").await.is_err());
+        std::fs::remove_dir_all(root).unwrap();
+    }
+}
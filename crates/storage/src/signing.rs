@@ -0,0 +1,96 @@
+//! Signed, time-limited download links
+
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use shared::{AppError, AppResult, Secret};
+use std::time::Duration;
+use uuid::Uuid;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Query parameters of a signed download link
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DownloadSignature {
+    /// Unix time after which the link is refused
+    pub expires: i64,
+    /// Hex-encoded HMAC-SHA256 of the file ID and `expires`
+    pub signature: String,
+}
+
+/// Signs and verifies links that grant access to a file until they expire
+#[derive(Debug, Clone)]
+pub struct UrlSigner {
+    key: Secret<String>,
+    ttl: Duration,
+}
+
+impl UrlSigner {
+    pub fn new(key: Secret<String>, ttl: Duration) -> Self {
+        Self { key, ttl }
+    }
+
+    /// Sign a link to `file_id` valid for the configured lifetime
+    pub fn sign(&self, file_id: &Uuid) -> DownloadSignature {
+        let expires = Utc::now().timestamp() + self.ttl.as_secs() as i64;
+        DownloadSignature {
+            expires,
+            signature: hex::encode(self.mac(file_id, expires).finalize().into_bytes()),
+        }
+    }
+
+    /// Check a link's signature and expiry
+    pub fn verify(&self, file_id: &Uuid, expires: i64, signature: &str) -> AppResult<()> {
+        let invalid = || AppError::Authorization("Invalid download signature".to_string());
+        let signature = hex::decode(signature).map_err(|_| invalid())?;
+        // Constant-time comparison
+        self.mac(file_id, expires).verify_slice(&signature).map_err(|_| invalid())?;
+
+        if expires < Utc::now().timestamp() {
+            return Err(AppError::Authorization("Download link has expired".to_string()));
+        }
+        Ok(())
+    }
+
+    fn mac(&self, file_id: &Uuid, expires: i64) -> HmacSha256 {
+        let mut mac = HmacSha256::new_from_slice(self.key.expose().as_bytes()).expect("HMAC accepts keys of any length");
+        mac.update(file_id.as_bytes());
+        mac.update(&expires.to_be_bytes());
+        mac
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn signer() -> UrlSigner {
+        UrlSigner::new("a-signing-key-of-at-least-32-characters".into(), Duration::from_secs(60))
+    }
+
+    #[test]
+    fn test_verify_signed_link() {
+        let signer = signer();
+        let file_id = Uuid::new_v4();
+        let link = signer.sign(&file_id);
+
+        assert!(signer.verify(&file_id, link.expires, &link.signature).is_ok());
+        assert!(signer.verify(&Uuid::new_v4(), link.expires, &link.signature).is_err());
+        assert!(signer.verify(&file_id, link.expires + 60, &link.signature).is_err());
+        assert!(signer.verify(&file_id, link.expires, "not-hex").is_err());
+
+        let other = UrlSigner::new("another-signing-key-of-32-characters".into(), Duration::from_secs(60));
+        assert!(other.verify(&file_id, link.expires, &link.signature).is_err());
+    }
+
+    #[test]
+    fn test_expired_link_refused() {
+        let signer = signer();
+        let file_id = Uuid::new_v4();
+        let expires = Utc::now().timestamp() - 1;
+        let signature = hex::encode(signer.mac(&file_id, expires).finalize().into_bytes());
+
+        let error = signer.verify(&file_id, expires, &signature).unwrap_err();
+        assert!(error.to_string().contains("expired"));
+    }
+}
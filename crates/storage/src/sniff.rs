@@ -0,0 +1,48 @@
+//! Content type detection from file contents
+
+/// Leading bytes identifying binary formats, with the content type each identifies
+const SIGNATURES: &[(&[u8], &str)] = &[
+    (b"\x89PNG\r\n\x1a\n", "image/png"),
+    (b"\xff\xd8\xff", "image/jpeg"),
+    (b"GIF87a", "image/gif"),
+    (b"GIF89a", "image/gif"),
+    (b"%PDF-", "application/pdf"),
+    (b"PK\x03\x04", "application/zip"),
+    (b"\x1f\x8b", "application/gzip"),
+];
+
+/// Detect the content type of a file from its contents, ignoring what the client claims.
+///
+/// Returns `None` for empty files and unrecognized binary data. Markup such as HTML or SVG
+/// is reported as `text/plain`, so it is never served as something a browser would render.
+pub fn sniff_content_type(data: &[u8]) -> Option<&'static str> {
+    if let Some((_, content_type)) = SIGNATURES.iter().find(|(magic, _)| data.starts_with(magic)) {
+        return Some(*content_type);
+    }
+    if data.len() >= 12 && &data[..4] == b"RIFF" && &data[8..12] == b"WEBP" {
+        return Some("image/webp");
+    }
+
+    let text = std::str::from_utf8(data).ok()?;
+    let printable = text
+        .chars()
+        .all(|c| !c.is_control() || matches!(c, '\t' | '\n' | '\r' | '\x0c'));
+    (!text.is_empty() && printable).then_some("text/plain")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sniff_content_type() {
+        assert_eq!(sniff_content_type(b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR"), Some("image/png"));
+        assert_eq!(sniff_content_type(b"\xff\xd8\xff\xe0\0\x10JFIF"), Some("image/jpeg"));
+        assert_eq!(sniff_content_type(b"RIFF\x24\0\0\0WEBPVP8 "), Some("image/webp"));
+        assert_eq!(sniff_content_type(b"%PDF-1.7\n"), Some("application/pdf"));
+        assert_eq!(sniff_content_type("plain text, naïve\n".as_bytes()), Some("text/plain"));
+        assert_eq!(sniff_content_type(b"<svg onload=alert(1)>"), Some("text/plain"));
+        assert_eq!(sniff_content_type(b"\x7fELF\x02\x01\x01\0"), None);
+        assert_eq!(sniff_content_type(b""), None);
+    }
+}
@@ -1,7 +1,8 @@
 //! Worker service configuration
 
-use shared::AppConfig;
+use shared::{AppConfig, ValidateConfig};
 use serde::{Deserialize, Serialize};
+use std::str::FromStr;
 
 /// Worker service configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -11,11 +12,13 @@ pub struct WorkerConfig {
     pub app: AppConfig,
     
     /// Worker specific settings
+    #[serde(default)]
     pub worker: WorkerSettings,
 }
 
 /// Worker specific settings
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct WorkerSettings {
     /// Number of worker threads
     pub worker_threads: usize,
@@ -47,6 +50,7 @@ pub struct WorkerSettings {
 
 /// Scheduler settings
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct SchedulerSettings {
     /// Enable cron job scheduling
     pub enable_cron: bool,
@@ -121,13 +125,28 @@ impl Default for SchedulerSettings {
 impl WorkerConfig {
     /// Load worker configuration
     pub fn load() -> Result<Self, figment::Error> {
-        use figment::{providers::{Env, Format, Yaml}, Figment};
-        
-        Figment::new()
-            .merge(Yaml::file("config/worker.yml"))
-            .merge(Yaml::file(format!("config/worker-{}.yml", std::env::var("ENVIRONMENT").unwrap_or_else(|_| "development".to_string()))))
-            .merge(Env::prefixed("WORKER_"))
-            .extract()
+        Self::load_from_path("config")
+    }
+
+    /// Load worker configuration from a config directory
+    pub fn load_from_path(config_path: &str) -> Result<Self, figment::Error> {
+        Self::figment(config_path).extract()
+    }
+
+    /// Worker configuration sources, in order of precedence (lowest first).
+    ///
+    /// Base settings are read like [`AppConfig::figment`], then overlaid with `worker.yml`,
+    /// `worker-{environment}.yml` and `WORKER_` variables, using `__` for nesting
+    /// (e.g. `WORKER_SCHEDULER__ENABLE_CRON=false`).
+    pub fn figment(config_path: &str) -> figment::Figment {
+        use figment::providers::{Env, Format, Yaml};
+
+        let environment = std::env::var("ENVIRONMENT").unwrap_or_else(|_| "development".to_string());
+
+        AppConfig::figment(config_path)
+            .merge(Yaml::file(format!("{}/worker.yml", config_path)))
+            .merge(Yaml::file(format!("{}/worker-{}.yml", config_path, environment)))
+            .merge(Env::prefixed("WORKER_").split("__").map(|key| format!("worker.{}", key.as_str()).into()))
     }
     
     /// Get job timeout as Duration
@@ -150,9 +169,11 @@ impl WorkerConfig {
         self.worker.job_types.contains(&"*".to_string()) || 
         self.worker.job_types.contains(&job_type.to_string())
     }
-    
+}
+
+impl ValidateConfig for WorkerConfig {
     /// Validate worker configuration
-    pub fn validate(&self) -> Result<(), String> {
+    fn validate(&self) -> Result<(), String> {
         // Validate base app config
         self.app.validate()?;
         
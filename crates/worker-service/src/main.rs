@@ -1,9 +1,14 @@
 //! Worker Service - Background job processor
 
 use anyhow::Result;
-use clap::Parser;
-use shared::{AppConfig, ValidateConfig};
-use tracing::{info, warn};
+use clap::{Parser, Subcommand};
+use figment::Figment;
+use shared::{
+    describe_config, logging,
+    runtime::{shutdown_signal, Shutdown, ShutdownPhase, Supervisor},
+    watch_config, CliOverrides, ConfigHandle, ValidateConfig,
+};
+use tracing::info;
 
 mod config;
 mod jobs;
@@ -14,7 +19,7 @@ use config::WorkerConfig;
 use scheduler::JobScheduler;
 
 /// Worker Service CLI arguments
-#[derive(Parser, Debug)]
+#[derive(Parser, Debug, Clone)]
 #[command(name = "worker-service")]
 #[command(about = "Background job processor")]
 struct Args {
@@ -33,6 +38,24 @@ struct Args {
     /// Job types to process (comma-separated)
     #[arg(long, env = "JOB_TYPES")]
     job_types: Option<String>,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug, Clone)]
+enum Command {
+    /// Inspect the service configuration
+    #[command(subcommand)]
+    Config(ConfigCommand),
+}
+
+#[derive(Subcommand, Debug, Clone)]
+enum ConfigCommand {
+    /// Load and validate the configuration as startup does, including cron expressions, then exit
+    Check,
+    /// Print the effective configuration and where each value came from, with secrets masked
+    Print,
 }
 
 #[tokio::main]
@@ -43,124 +66,103 @@ async fn main() -> Result<()> {
     // Parse CLI arguments
     let args = Args::parse();
 
-    // Initialize configuration
-    let mut config = if args.config == "config" {
-        AppConfig::load()?
-    } else {
-        AppConfig::load_from_path(&args.config)?
-    };
-
-    // Override config with CLI arguments
-    if let Some(environment) = args.environment {
-        config.environment = environment;
+    if let Some(Command::Config(command)) = &args.command {
+        return run_config_command(&args, command);
     }
 
+    // Initialize configuration
+    let worker_config = load_config(&args).map_err(anyhow::Error::msg)?;
+
     // Validate configuration
-    config.validate()?;
+    worker_config.validate().map_err(anyhow::Error::msg)?;
+    let config = worker_config.app.clone();
 
     // Initialize logging
-    init_logging(&config)?;
+    let logging = logging::init(&config)?;
 
     info!("Starting Worker service");
     info!("Environment: {}", config.environment);
     info!("Version: {}", config.version);
+    info!("Worker threads: {}", worker_config.worker.worker_threads);
+    info!("Processing job types: {:?}", worker_config.worker.job_types);
 
-    // Initialize job scheduler
-    let worker_threads = args.workers.unwrap_or_else(|| {
-        std::thread::available_parallelism()
-            .map(|n| n.get())
-            .unwrap_or(4)
-    });
-
-    let job_types = args.job_types
-        .map(|types| types.split(',').map(|s| s.trim().to_string()).collect())
-        .unwrap_or_else(|| vec!["*".to_string()]); // Process all job types by default
+    // Reload configuration on file changes and SIGHUP
+    let config_handle = ConfigHandle::new(worker_config);
+    let reload_args = args.clone();
+    watch_config(config_handle.clone(), &args.config, move || load_config(&reload_args))?;
+    logging.watch_level(&config_handle, |config| config.app.logging.level.clone());
 
-    info!("Worker threads: {}", worker_threads);
-    info!("Processing job types: {:?}", job_types);
+    // Initialize job scheduler
+    let scheduler = JobScheduler::new(config_handle).await?;
 
-    let scheduler = JobScheduler::new(config, worker_threads, job_types).await?;
+    // Report dependency health at startup
+    scheduler.health().log_status().await;
 
     // Start the scheduler
-    scheduler.start().await?;
+    let mut supervisor = Supervisor::new();
+    scheduler.start(&mut supervisor);
 
     // Wait for shutdown signal
     shutdown_signal().await;
 
-    // Graceful shutdown
+    // Graceful shutdown: stop polling, let running jobs finish, then close the pool
     info!("Shutting down worker service");
-    scheduler.shutdown().await?;
-
+    let token = supervisor.token().clone();
+    let database = scheduler.database().clone();
+    Shutdown::new(config.shutdown_timeout())
+        .step(ShutdownPhase::StopIntake, "job polling", async move { token.cancel() })
+        .step(ShutdownPhase::Drain, "workers", supervisor.shutdown())
+        .step(ShutdownPhase::ClosePools, "database", async move { database.close().await })
+        .run()
+        .await;
+
+    logging.shutdown();
     info!("Worker service stopped");
     Ok(())
 }
 
-/// Initialize logging and tracing
-fn init_logging(config: &AppConfig) -> Result<()> {
-    use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
+/// Configuration sources, with CLI overrides taking precedence
+fn config_figment(args: &Args) -> Figment {
+    let mut overrides = CliOverrides::default();
+    if let Some(environment) = &args.environment {
+        overrides.set("environment", environment);
+    }
+    if let Some(workers) = args.workers {
+        overrides.set("worker.worker_threads", workers);
+    }
+    if let Some(job_types) = &args.job_types {
+        let job_types: Vec<_> = job_types.split(',').map(|s| s.trim().to_string()).collect();
+        overrides.set("worker.job_types", job_types);
+    }
 
-    let env_filter = EnvFilter::try_from_default_env()
-        .unwrap_or_else(|_| EnvFilter::new(&config.logging.level));
+    WorkerConfig::figment(&args.config).merge(overrides)
+}
 
-    let subscriber = tracing_subscriber::registry().with(env_filter);
+/// Load configuration from files, the environment and CLI flags
+fn load_config(args: &Args) -> Result<WorkerConfig, String> {
+    config_figment(args).extract().map_err(|e| e.to_string())
+}
 
-    match config.logging.format.as_str() {
-        "json" => {
-            let json_layer = tracing_subscriber::fmt::layer()
-                .json()
-                .with_current_span(false)
-                .with_span_list(true);
-            subscriber.with(json_layer).init();
-        }
-        _ => {
-            let fmt_layer = tracing_subscriber::fmt::layer()
-                .with_target(true)
-                .with_thread_ids(true)
-                .with_file(true)
-                .with_line_number(true);
-            subscriber.with(fmt_layer).init();
+/// Run a `config` subcommand
+fn run_config_command(args: &Args, command: &ConfigCommand) -> Result<()> {
+    let figment = config_figment(args);
+    let config: WorkerConfig = figment.extract()?;
+
+    match command {
+        ConfigCommand::Check => {
+            config.validate().map_err(anyhow::Error::msg)?;
+            println!("Configuration is valid");
         }
+        ConfigCommand::Print => print!("{}", describe_config(&config, &figment)?),
     }
 
     Ok(())
 }
 
-/// Graceful shutdown signal
-async fn shutdown_signal() {
-    use tokio::signal;
-
-    let ctrl_c = async {
-        signal::ctrl_c()
-            .await
-            .expect("Failed to install Ctrl+C handler");
-    };
-
-    #[cfg(unix)]
-    let terminate = async {
-        signal::unix::signal(signal::unix::SignalKind::terminate())
-            .expect("Failed to install signal handler")
-            .recv()
-            .await;
-    };
-
-    #[cfg(not(unix))]
-    let terminate = std::future::pending::<()>();
-
-    tokio::select! {
-        _ = ctrl_c => {
-            info!("Received Ctrl+C signal");
-        },
-        _ = terminate => {
-            info!("Received terminate signal");
-        },
-    }
-
-    info!("Starting graceful shutdown");
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
+    use shared::AppConfig;
 
     #[tokio::test]
     async fn test_config_loading() {
@@ -1,7 +1,7 @@
 //! Job processors
 
 use async_trait::async_trait;
-use shared::{AppResult, CorrelationId};
+use shared::{jobs, AppResult, CorrelationId};
 use std::time::Duration;
 use tokio::time::timeout;
 use tracing::{error, info, warn};
@@ -21,10 +21,10 @@ impl Processor for DefaultProcessor {
         info!("Processing job: type={}, correlation_id={}", job_type, correlation_id);
         
         match job_type {
-            "send_email" => process_email_job(payload).await,
-            "process_payment" => process_payment_job(payload).await,
-            "generate_report" => process_report_job(payload).await,
-            "cleanup_data" => process_cleanup_job(payload).await,
+            jobs::SEND_EMAIL => process_email_job(payload).await,
+            jobs::PROCESS_PAYMENT => process_payment_job(payload).await,
+            jobs::GENERATE_REPORT => process_report_job(payload).await,
+            jobs::CLEANUP_DATA => process_cleanup_job(payload).await,
             _ => {
                 warn!("Unknown job type: {}", job_type);
                 Err(shared::AppError::BadRequest(format!("Unknown job type: {}", job_type)))
@@ -1,245 +1,386 @@
 //! Job scheduler
 
 use crate::{config::WorkerConfig, processors::{DefaultProcessor, JobExecutor, JobContext, Processor}};
-use database::{DatabaseManager, JobRepository};
-use shared::{AppResult, CorrelationId};
-use std::{sync::Arc, time::Duration};
-use tokio::{sync::RwLock, time::interval};
-use tracing::{error, info, warn};
-use uuid::Uuid;
+use cache::{Notification, NotificationChannel, NotificationPublisher, RedisManager};
+use cache::RedisHealthCheck;
+use database::{DatabaseManager, Job, JobRepository, JobStatus, MigrationHealthCheck, PostgresHealthCheck};
+use shared::{
+    context, events, generate_correlation_id,
+    runtime::{CancellationToken, RestartPolicy, Supervisor},
+    telemetry, AppResult, ConfigHandle, CorrelationId, HealthRegistry,
+};
+use chrono::Utc;
+use std::time::Duration;
+use tokio::time::interval;
+use tracing::{error, info, warn, Instrument};
+
+/// Upper bound for the delay before a failed job runs again
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(3600);
 
 /// Job scheduler for managing background job processing
 pub struct JobScheduler {
-    config: WorkerConfig,
+    config: ConfigHandle<WorkerConfig>,
     database: DatabaseManager,
     job_repository: JobRepository,
+    notifications: NotificationPublisher,
     executor: JobExecutor<DefaultProcessor>,
-    running: Arc<RwLock<bool>>,
-    worker_handles: Vec<tokio::task::JoinHandle<()>>,
+    health: HealthRegistry,
 }
 
+/// Everything a worker needs to poll and run jobs
+#[derive(Clone)]
+struct Worker {
+    config: ConfigHandle<WorkerConfig>,
+    job_repository: JobRepository,
+    notifications: NotificationPublisher,
+}
+
+/// Restart policy for the scheduler's tasks, which only fail on unexpected errors
+const RESTART: RestartPolicy = RestartPolicy::OnFailure {
+    max_restarts: 5,
+    backoff: Duration::from_secs(1),
+};
+
 impl JobScheduler {
     /// Create a new job scheduler
-    pub async fn new(
-        config: shared::AppConfig,
-        worker_threads: usize,
-        job_types: Vec<String>,
-    ) -> AppResult<Self> {
-        let database = DatabaseManager::new(&config.database).await?;
+    pub async fn new(config: ConfigHandle<WorkerConfig>) -> AppResult<Self> {
+        let snapshot = config.load();
+        let database = DatabaseManager::new(&snapshot.app.database).await?;
         let job_repository = JobRepository::new(database.pool().clone());
+        let redis = RedisManager::new(&snapshot.app.redis).await?;
+        let notifications = NotificationPublisher::new(redis.clone());
         let executor = JobExecutor::new(DefaultProcessor);
-        
-        let worker_config = WorkerConfig {
-            app: config,
-            worker: crate::config::WorkerSettings {
-                worker_threads,
-                job_types,
-                ..Default::default()
-            },
-        };
+
+        let timeout = snapshot.app.health.check_timeout;
+        let health = HealthRegistry::new(snapshot.app.service_name.clone(), snapshot.app.version.clone())
+            .register(PostgresHealthCheck::new(database.clone(), timeout))
+            .register(MigrationHealthCheck::new(&database, timeout))
+            .register(RedisHealthCheck::new(redis, timeout));
 
         Ok(Self {
-            config: worker_config,
+            config,
             database,
             job_repository,
+            notifications,
             executor,
-            running: Arc::new(RwLock::new(false)),
-            worker_handles: Vec::new(),
+            health,
         })
     }
 
-    /// Start the job scheduler
-    pub async fn start(&mut self) -> AppResult<()> {
-        info!("Starting job scheduler with {} worker threads", self.config.worker.worker_threads);
-        
-        {
-            let mut running = self.running.write().await;
-            *running = true;
-        }
+    /// Dependency health checks for the worker
+    pub fn health(&self) -> &HealthRegistry {
+        &self.health
+    }
 
-        // Start worker threads
-        for worker_id in 0..self.config.worker.worker_threads {
-            let handle = self.spawn_worker(worker_id).await;
-            self.worker_handles.push(handle);
-        }
+    /// Database pool used by the workers, closed last on shutdown
+    pub fn database(&self) -> &DatabaseManager {
+        &self.database
+    }
+
+    /// Start the worker pool and background tasks under `supervisor`.
+    ///
+    /// Cancelling the supervisor's token stops workers from picking up new jobs; jobs already
+    /// running are finished before the pool task returns.
+    pub fn start(&self, supervisor: &mut Supervisor) {
+        info!("Starting job scheduler with {} worker threads", self.config.load().worker.worker_threads);
+
+        // Start worker threads, resized when the configuration is reloaded
+        let worker = Worker {
+            config: self.config.clone(),
+            job_repository: self.job_repository.clone(),
+            notifications: self.notifications.clone(),
+        };
+        supervisor.spawn("worker pool", RESTART, move |token| run_worker_pool(worker.clone(), token));
 
         // Start cron scheduler if enabled
-        if self.config.worker.scheduler.enable_cron {
-            let handle = self.spawn_cron_scheduler().await;
-            self.worker_handles.push(handle);
+        if self.config.load().worker.scheduler.enable_cron {
+            let config = self.config.clone();
+            supervisor.spawn("cron scheduler", RESTART, move |token| run_cron_scheduler(config.clone(), token));
         }
 
         // Start cleanup task
-        let handle = self.spawn_cleanup_task().await;
-        self.worker_handles.push(handle);
+        supervisor.spawn("job cleanup", RESTART, run_cleanup_task);
 
         info!("Job scheduler started successfully");
-        Ok(())
     }
+}
 
-    /// Stop the job scheduler
-    pub async fn shutdown(&self) -> AppResult<()> {
-        info!("Shutting down job scheduler");
-        
-        {
-            let mut running = self.running.write().await;
-            *running = false;
+/// Keep `worker.worker_threads` workers running until cancelled, then wait for them to finish.
+///
+/// When the pool grows, missing workers are started; when it shrinks, workers beyond the
+/// new size stop after their current batch.
+async fn run_worker_pool(worker: Worker, token: CancellationToken) -> AppResult<()> {
+    let mut updates = worker.config.subscribe();
+    let mut workers: Vec<tokio::task::JoinHandle<()>> = Vec::new();
+    let mut size = 0;
+
+    loop {
+        let target = worker.config.load().worker.worker_threads;
+        if target != size {
+            if size != 0 {
+                info!("Resizing worker pool from {} to {} workers", size, target);
+            }
+            size = target;
         }
 
-        // Wait for all workers to finish
-        for handle in &self.worker_handles {
-            handle.abort();
+        for worker_id in 0..size {
+            let idle = workers.get(worker_id).map_or(true, |handle| handle.is_finished());
+            if idle {
+                let handle = spawn_worker(worker.clone(), worker_id, token.clone());
+                match workers.get_mut(worker_id) {
+                    Some(slot) => *slot = handle,
+                    None => workers.push(handle),
+                }
+            }
+        }
+
+        tokio::select! {
+            _ = token.cancelled() => break,
+            changed = updates.changed() => {
+                if changed.is_err() {
+                    break;
+                }
+            }
         }
+    }
 
-        info!("Job scheduler stopped");
-        Ok(())
+    info!("Waiting for {} workers to finish their current jobs", workers.len());
+    for handle in workers {
+        if let Err(e) = handle.await {
+            error!("Worker ended abnormally: {}", e);
+        }
     }
+    Ok(())
+}
 
-    /// Spawn a worker thread
-    async fn spawn_worker(&self, worker_id: usize) -> tokio::task::JoinHandle<()> {
-        let config = self.config.clone();
-        let job_repository = self.job_repository.clone();
-        let executor = JobExecutor::new(DefaultProcessor);
-        let running = self.running.clone();
-
-        tokio::spawn(async move {
-            info!("Worker {} started", worker_id);
-            
-            let mut poll_interval = interval(config.poll_interval_duration());
-            
-            loop {
-                // Check if we should continue running
-                {
-                    let is_running = running.read().await;
-                    if !*is_running {
-                        break;
-                    }
-                }
+/// Run the cron scheduler until cancelled
+async fn run_cron_scheduler(config: ConfigHandle<WorkerConfig>, token: CancellationToken) -> AppResult<()> {
+    info!("Cron scheduler started");
 
-                poll_interval.tick().await;
-
-                // Fetch pending jobs
-                match job_repository.find_pending(config.worker.batch_size as i64).await {
-                    Ok(jobs) => {
-                        for job in jobs {
-                            // Check if we should process this job type
-                            if !config.should_process_job_type(&job.job_type) {
-                                continue;
-                            }
-
-                            // Mark job as started
-                            if let Err(e) = job_repository.mark_started(&job.id).await {
-                                error!("Failed to mark job as started: {}", e);
-                                continue;
-                            }
-
-                            let context = JobContext {
-                                job_id: job.id,
-                                job_type: job.job_type.clone(),
-                                correlation_id: Uuid::new_v4(), // TODO: Use actual correlation ID
-                                retry_count: job.retry_count as u32,
-                                max_retries: job.max_retries as u32,
-                                timeout_duration: config.job_timeout_duration(),
-                            };
-
-                            // Execute the job
-                            match executor.execute(context, job.payload).await {
-                                Ok(result) => {
-                                    if let Err(e) = job_repository.mark_completed(&job.id, Some(result)).await {
-                                        error!("Failed to mark job as completed: {}", e);
-                                    }
-                                }
-                                Err(e) => {
-                                    let error_msg = e.to_string();
-                                    if job.retry_count < job.max_retries {
-                                        // Schedule retry
-                                        warn!("Job failed, will retry: id={}, error={}", job.id, error_msg);
-                                        // TODO: Implement retry scheduling with delay
-                                    } else {
-                                        // Mark as failed
-                                        error!("Job failed permanently: id={}, error={}", job.id, error_msg);
-                                        if let Err(e) = job_repository.mark_failed(&job.id, &error_msg).await {
-                                            error!("Failed to mark job as failed: {}", e);
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                    }
-                    Err(e) => {
-                        error!("Failed to fetch pending jobs: {}", e);
-                        tokio::time::sleep(Duration::from_secs(5)).await;
-                    }
-                }
+    let mut check_interval = interval(Duration::from_secs(60)); // Check every minute
+
+    loop {
+        tokio::select! {
+            _ = token.cancelled() => break,
+            _ = check_interval.tick() => {}
+        }
+
+        // TODO: Implement cron job scheduling
+        // Check each cron job definition and schedule if due
+        let config = config.load();
+        for cron_job in &config.worker.scheduler.cron_jobs {
+            if cron_job.enabled {
+                // Parse cron expression and check if job should run
+                // Create job entry in database if due
             }
+        }
+    }
 
-            info!("Worker {} stopped", worker_id);
-        })
+    info!("Cron scheduler stopped");
+    Ok(())
+}
+
+/// Run the cleanup task until cancelled
+async fn run_cleanup_task(token: CancellationToken) -> AppResult<()> {
+    info!("Cleanup task started");
+
+    let mut cleanup_interval = interval(Duration::from_secs(3600)); // Run every hour
+
+    loop {
+        tokio::select! {
+            _ = token.cancelled() => break,
+            _ = cleanup_interval.tick() => {}
+        }
+
+        // TODO: Implement job cleanup logic
+        // Remove old completed/failed jobs based on configuration
+        info!("Running job cleanup task");
     }
 
-    /// Spawn cron scheduler
-    async fn spawn_cron_scheduler(&self) -> tokio::task::JoinHandle<()> {
-        let config = self.config.clone();
-        let running = self.running.clone();
-
-        tokio::spawn(async move {
-            info!("Cron scheduler started");
-            
-            let mut check_interval = interval(Duration::from_secs(60)); // Check every minute
-            
-            loop {
-                // Check if we should continue running
-                {
-                    let is_running = running.read().await;
-                    if !*is_running {
-                        break;
-                    }
-                }
+    info!("Cleanup task stopped");
+    Ok(())
+}
+
+/// Spawn a worker thread; it stops when cancelled or when the pool shrinks below its id.
+/// A job that has started always runs to completion.
+fn spawn_worker(worker: Worker, worker_id: usize, token: CancellationToken) -> tokio::task::JoinHandle<()> {
+    let Worker {
+        config,
+        job_repository,
+        notifications,
+    } = worker;
+    let executor = JobExecutor::new(DefaultProcessor);
+
+    tokio::spawn(async move {
+        info!("Worker {} started", worker_id);
+        
+        let mut poll_interval = interval(config.load().poll_interval_duration());
+        
+        loop {
+            // Pick up reloaded settings between batches
+            let config = config.load();
+            if worker_id >= config.worker.worker_threads {
+                break;
+            }
+            if poll_interval.period() != config.poll_interval_duration() {
+                poll_interval = interval(config.poll_interval_duration());
+            }
+
+            tokio::select! {
+                _ = token.cancelled() => break,
+                _ = poll_interval.tick() => {}
+            }
 
-                check_interval.tick().await;
+            // Fetch pending jobs
+            match job_repository.find_pending(config.worker.batch_size as i64).await {
+                Ok(jobs) => {
+                    for job in jobs {
+                        // Leave the rest of the batch pending for another instance
+                        if token.is_cancelled() {
+                            break;
+                        }
+
+                        // Check if we should process this job type
+                        if !config.should_process_job_type(&job.job_type) {
+                            continue;
+                        }
 
-                // TODO: Implement cron job scheduling
-                // Check each cron job definition and schedule if due
-                for cron_job in &config.worker.scheduler.cron_jobs {
-                    if cron_job.enabled {
-                        // Parse cron expression and check if job should run
-                        // Create job entry in database if due
+                        // Restore the correlation id and trace of the request that enqueued the job
+                        let correlation_id = job.correlation_id.unwrap_or_else(generate_correlation_id);
+                        let span = tracing::info_span!(
+                            "job",
+                            job_id = %job.id,
+                            job_type = %job.job_type,
+                            correlation_id = %correlation_id,
+                        );
+                        if let Some(traceparent) = &job.traceparent {
+                            telemetry::set_remote_parent(&span, &telemetry::trace_context_from_traceparent(traceparent));
+                        }
+
+                        let run = process_job(&config, &job_repository, &notifications, &executor, &job, correlation_id);
+                        context::with_correlation_id(correlation_id, run.instrument(span)).await;
+                    }
+                }
+                Err(e) => {
+                    error!("Failed to fetch pending jobs: {}", e);
+                    tokio::select! {
+                        _ = token.cancelled() => break,
+                        _ = tokio::time::sleep(Duration::from_secs(5)) => {}
                     }
                 }
             }
+        }
 
-            info!("Cron scheduler stopped");
-        })
+        info!("Worker {} stopped", worker_id);
+    })
+}
+
+/// Run a single pending job and record its outcome
+async fn process_job(
+    config: &WorkerConfig,
+    job_repository: &JobRepository,
+    notifications: &NotificationPublisher,
+    executor: &JobExecutor<DefaultProcessor>,
+    job: &Job,
+    correlation_id: CorrelationId,
+) {
+    // Mark job as started, unless it was cancelled since it was fetched
+    match job_repository.mark_started(&job.id).await {
+        Ok(true) => {}
+        Ok(false) => {
+            info!("Job is no longer pending, skipping: id={}", job.id);
+            return;
+        }
+        Err(e) => {
+            error!("Failed to mark job as started: {}", e);
+            return;
+        }
     }
+    publish_progress(notifications, job, events::JOB_STARTED, serde_json::json!({})).await;
+
+    let context = JobContext {
+        job_id: job.id,
+        job_type: job.job_type.clone(),
+        correlation_id,
+        retry_count: job.retry_count as u32,
+        max_retries: job.max_retries as u32,
+        timeout_duration: config.job_timeout_duration(),
+    };
+
+    // Execute the job, abandoning it if it is cancelled while it runs
+    let outcome = tokio::select! {
+        outcome = executor.execute(context, job.payload.clone()) => outcome,
+        _ = cancelled(job_repository, job, config.poll_interval_duration()) => {
+            info!("Job cancelled while running: id={}", job.id);
+            return;
+        }
+    };
 
-    /// Spawn cleanup task
-    async fn spawn_cleanup_task(&self) -> tokio::task::JoinHandle<()> {
-        let config = self.config.clone();
-        let running = self.running.clone();
-
-        tokio::spawn(async move {
-            info!("Cleanup task started");
-            
-            let mut cleanup_interval = interval(Duration::from_secs(3600)); // Run every hour
-            
-            loop {
-                // Check if we should continue running
-                {
-                    let is_running = running.read().await;
-                    if !*is_running {
-                        break;
-                    }
+    match outcome {
+        Ok(result) => {
+            if let Err(e) = job_repository.mark_completed(&job.id, Some(result.clone())).await {
+                error!("Failed to mark job as completed: {}", e);
+            }
+            publish_progress(notifications, job, events::JOB_COMPLETED, serde_json::json!({ "result": result })).await;
+        }
+        Err(e) => {
+            let error_msg = e.to_string();
+            if job.retry_count < job.max_retries {
+                // Return the job to pending so it runs again after the backoff
+                let delay = retry_delay(config.retry_delay_duration(), job.retry_count);
+                warn!("Job failed, retrying in {:?}: id={}, error={}", delay, job.id, error_msg);
+                let scheduled_at = Utc::now() + chrono::Duration::milliseconds(delay.as_millis() as i64);
+                if let Err(e) = job_repository.schedule_retry(&job.id, &error_msg, scheduled_at).await {
+                    error!("Failed to schedule job retry: {}", e);
                 }
+            } else {
+                // Mark as failed
+                error!("Job failed permanently: id={}, error={}", job.id, error_msg);
+                if let Err(e) = job_repository.mark_failed(&job.id, &error_msg).await {
+                    error!("Failed to mark job as failed: {}", e);
+                }
+                publish_progress(notifications, job, events::JOB_FAILED, serde_json::json!({ "error": error_msg })).await;
+            }
+        }
+    }
+}
 
-                cleanup_interval.tick().await;
+/// Delay before a job that has failed `retry_count` times runs again, doubling from `base`
+fn retry_delay(base: Duration, retry_count: i32) -> Duration {
+    base.saturating_mul(2u32.saturating_pow(retry_count.max(0) as u32))
+        .min(MAX_RETRY_DELAY)
+}
 
-                // TODO: Implement job cleanup logic
-                // Remove old completed/failed jobs based on configuration
-                info!("Running job cleanup task");
-            }
+/// Resolves once the job has been cancelled, checking its status every `interval`
+async fn cancelled(job_repository: &JobRepository, job: &Job, interval: Duration) {
+    loop {
+        tokio::time::sleep(interval).await;
+        match job_repository.find_status(&job.id).await {
+            Ok(Some(JobStatus::Cancelled)) => return,
+            Ok(_) => {}
+            Err(e) => warn!("Failed to check whether job {} was cancelled: {}", job.id, e),
+        }
+    }
+}
 
-            info!("Cleanup task stopped");
-        })
+/// Notify subscribers of a tenant's job channel about job progress
+async fn publish_progress(notifications: &NotificationPublisher, job: &Job, event: &str, details: serde_json::Value) {
+    // Jobs without a tenant are internal and not visible to clients
+    let Some(tenant_id) = job.tenant_id else {
+        return;
+    };
+
+    let mut payload = serde_json::json!({
+        "job_type": job.job_type,
+        "retry_count": job.retry_count,
+    });
+    if let (Some(payload), serde_json::Value::Object(details)) = (payload.as_object_mut(), details) {
+        payload.extend(details);
+    }
+
+    let notification = Notification::new(tenant_id, NotificationChannel::Jobs, event, payload).with_resource(job.id);
+    if let Err(e) = notifications.publish(&notification).await {
+        warn!("Failed to publish job progress: id={}, error={}", job.id, e);
     }
 }
 
@@ -249,10 +390,19 @@ mod tests {
 
     #[tokio::test]
     async fn test_job_scheduler_creation() {
-        let config = shared::AppConfig::default();
+        let config = WorkerConfig::default();
         
         // This test would require a running database
-        // let scheduler = JobScheduler::new(config, 2, vec!["test".to_string()]).await;
+        // let scheduler = JobScheduler::new(ConfigHandle::new(config)).await;
         // assert!(scheduler.is_ok());
     }
+
+    #[test]
+    fn test_retry_delay_backs_off() {
+        let base = Duration::from_secs(60);
+        assert_eq!(retry_delay(base, 0), Duration::from_secs(60));
+        assert_eq!(retry_delay(base, 2), Duration::from_secs(240));
+        assert_eq!(retry_delay(base, 10), MAX_RETRY_DELAY);
+        assert_eq!(retry_delay(base, 100), MAX_RETRY_DELAY);
+    }
 }
\ No newline at end of file
@@ -0,0 +1,3 @@
+-- Support streaming a tenant's events in (created_at, id) order
+
+CREATE INDEX idx_events_tenant_created_at_id ON events(tenant_id, created_at, id);
@@ -0,0 +1,5 @@
+-- Track the request that enqueued a job so its processing can be correlated
+
+ALTER TABLE jobs ADD COLUMN correlation_id UUID;
+
+CREATE INDEX idx_jobs_correlation_id ON jobs(correlation_id);
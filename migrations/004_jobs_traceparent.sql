@@ -0,0 +1,3 @@
+-- Keep the W3C trace context of the work that enqueued a job so its processing joins the same trace
+
+ALTER TABLE jobs ADD COLUMN traceparent TEXT;
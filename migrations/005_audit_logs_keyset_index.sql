@@ -0,0 +1,3 @@
+-- Support paging through a tenant's audit log in (created_at, id) order
+
+CREATE INDEX idx_audit_logs_tenant_created_at_id ON audit_logs(tenant_id, created_at, id);